@@ -0,0 +1,104 @@
+//! TOML description of a pool, see `pool.example.toml`.
+use std::{path::Path, str::FromStr};
+
+use anchor_lang::prelude::Pubkey;
+use anyhow::{Context, Result};
+use safe_presale::instructions::InitPoolArgs;
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PoolConfig {
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    pub decimals: u8,
+    pub quote_mint: String,
+    pub presale_target: u64,
+    /// Seconds
+    pub presale_duration: u32,
+    pub vesting_period: u32,
+    pub reward_vesting_period: u32,
+    pub creator_reward_vesting_period: u32,
+    pub liquidity_pool_supply: u64,
+    pub initial_supply: u64,
+    pub creator_fee_basis_points: u16,
+    pub max_amount_per_purchase: Option<u64>,
+    pub claim_period: Option<u32>,
+    pub reclaim_destination: Option<String>,
+    pub delegate: Option<String>,
+    #[serde(default)]
+    pub dissolve_quorum_basis_points: u16,
+    #[serde(default)]
+    pub dissolve_vote_window: u32,
+    /// Seed of the reward mint, a fresh one is picked when missing
+    pub random_key: Option<u64>,
+    /// Collections allowed to buy, anybody can buy when empty
+    #[serde(default)]
+    pub collections: Vec<String>,
+}
+
+impl PoolConfig {
+    pub fn read(path: &Path) -> Result<Self> {
+        let text =
+            std::fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+        toml::from_str(&text).with_context(|| format!("parsing {}", path.display()))
+    }
+
+    pub fn collections(&self) -> Result<Vec<Pubkey>> {
+        self.collections
+            .iter()
+            .map(|key| parse_pubkey(key))
+            .collect()
+    }
+
+    pub fn init_pool_args(&self, random_key: u64) -> Result<InitPoolArgs> {
+        Ok(InitPoolArgs {
+            name: self.name.clone(),
+            symbol: self.symbol.clone(),
+            uri: self.uri.clone(),
+            decimals: self.decimals,
+            presale_target: self.presale_target,
+            presale_duration: self.presale_duration,
+            vesting_period: self.vesting_period,
+            reward_vesting_period: self.reward_vesting_period,
+            creator_reward_vesting_period: self.creator_reward_vesting_period,
+            dissolve_quorum_basis_points: self.dissolve_quorum_basis_points,
+            dissolve_vote_window: self.dissolve_vote_window,
+            claim_period: self.claim_period,
+            reclaim_destination: self
+                .reclaim_destination
+                .as_deref()
+                .map(parse_pubkey)
+                .transpose()?,
+            max_amount_per_purchase: self.max_amount_per_purchase,
+            liquidity_pool_supply: self.liquidity_pool_supply,
+            initial_supply: self.initial_supply,
+            creator_fee_basis_points: self.creator_fee_basis_points,
+            delegate: self.delegate.as_deref().map(parse_pubkey).transpose()?,
+            random_key,
+            requires_collection: !self.collections.is_empty(),
+            quote_mint: parse_pubkey(&self.quote_mint)?,
+        })
+    }
+}
+
+pub fn parse_pubkey(key: &str) -> Result<Pubkey> {
+    Pubkey::from_str(key).with_context(|| format!("invalid public key {key}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn example_config_is_valid() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("pool.example.toml");
+        let config = PoolConfig::read(&path).unwrap();
+        let args = config.init_pool_args(7).unwrap();
+        assert_eq!(args.random_key, 7);
+        assert!(args.requires_collection);
+        assert_eq!(config.collections().unwrap().len(), 1);
+        assert_eq!(args.reclaim_destination, None);
+    }
+}
@@ -0,0 +1,282 @@
+//! Operator CLI for creating and managing safe_presale pools.
+mod config;
+mod operator;
+
+use std::path::PathBuf;
+
+use anchor_lang::prelude::Pubkey;
+use anyhow::{bail, Context, Result};
+use clap::{Parser, Subcommand};
+use config::{parse_pubkey, PoolConfig};
+use operator::Operator;
+use safe_presale::state::{PoolQuote, PoolStatus};
+use safe_presale_client::{instructions, pda, raydium::Cpmm};
+use solana_sdk::{compute_budget::ComputeBudgetInstruction, signature::read_keypair_file};
+
+/// Collection authorisations sent per transaction.
+const AUTHORISATIONS_PER_TRANSACTION: usize = 8;
+/// The CPMM pool creation does not fit in the default compute limit.
+const LAUNCH_COMPUTE_UNIT_LIMIT: u32 = 500_000;
+
+#[derive(Parser)]
+#[command(name = "safe-presale", about = "Create and manage safe_presale pools")]
+struct Cli {
+    /// RPC endpoint, a local test validator by default
+    #[arg(
+        long,
+        short = 'u',
+        env = "SAFE_PRESALE_RPC_URL",
+        default_value = "http://127.0.0.1:8899"
+    )]
+    url: String,
+    /// Payer and pool authority, the Solana CLI keypair by default
+    #[arg(long, short = 'k', env = "SAFE_PRESALE_KEYPAIR")]
+    keypair: Option<PathBuf>,
+    /// Simulate the transactions and print their logs without sending them
+    #[arg(long)]
+    dry_run: bool,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Create a pool from a TOML config, see pool.example.toml
+    InitPool {
+        #[arg(long)]
+        config: PathBuf,
+    },
+    /// Allow holders of the collections to buy into the pool
+    AuthoriseCollection {
+        #[arg(long, value_parser = parse_pubkey)]
+        pool: Pubkey,
+        #[arg(long = "collection", value_parser = parse_pubkey, required = true)]
+        collections: Vec<Pubkey>,
+    },
+    /// Show the state of the pool
+    Status {
+        #[arg(long, value_parser = parse_pubkey)]
+        pool: Pubkey,
+    },
+    /// Launch the pool on Raydium CPMM, every Raydium account is derived
+    Launch {
+        #[arg(long, value_parser = parse_pubkey)]
+        pool: Pubkey,
+        /// Unix timestamp trading opens at, now by default
+        #[arg(long)]
+        open_time: Option<u64>,
+        /// CPMM fee tier, the first one by default
+        #[arg(long)]
+        amm_config_index: Option<u16>,
+    },
+    /// Claim the vested creator allocations of lp and reward tokens
+    ClaimCreator {
+        #[arg(long, value_parser = parse_pubkey)]
+        pool: Pubkey,
+    },
+    /// List the purchase receipts of the pool
+    Receipts {
+        #[arg(long, value_parser = parse_pubkey)]
+        pool: Pubkey,
+    },
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let keypair_path = match cli.keypair {
+        Some(keypair_path) => keypair_path,
+        None => default_keypair_path()?,
+    };
+    let payer = read_keypair_file(&keypair_path)
+        .map_err(|err| anyhow::anyhow!("reading keypair {}: {err}", keypair_path.display()))?;
+    let operator = Operator::new(cli.url, payer, cli.dry_run);
+
+    match cli.command {
+        Command::InitPool { config } => init_pool(&operator, &PoolConfig::read(&config)?),
+        Command::AuthoriseCollection { pool, collections } => {
+            authorise_collections(&operator, &pool, &collections)
+        }
+        Command::Status { pool } => status(&operator, &pool),
+        Command::Launch {
+            pool,
+            open_time,
+            amm_config_index,
+        } => launch(&operator, &pool, open_time, amm_config_index),
+        Command::ClaimCreator { pool } => claim_creator(&operator, &pool),
+        Command::Receipts { pool } => receipts(&operator, &pool),
+    }
+}
+
+fn default_keypair_path() -> Result<PathBuf> {
+    let home = std::env::var_os("HOME").context("HOME is not set, pass --keypair")?;
+    Ok(PathBuf::from(home).join(".config/solana/id.json"))
+}
+
+fn init_pool(operator: &Operator, config: &PoolConfig) -> Result<()> {
+    let random_key = config.random_key.unwrap_or_else(rand::random);
+    let args = config.init_pool_args(random_key)?;
+    let collections = config.collections()?;
+    let reward_mint = pda::reward_mint(random_key);
+    let pool = pda::pool(&reward_mint);
+    println!("pool: {pool}");
+    println!("reward mint: {reward_mint}");
+    println!("random key: {random_key}");
+
+    operator.send(
+        "init_pool",
+        &[instructions::init_pool(&operator.payer(), args)],
+        &[],
+    )?;
+    if operator.dry_run && !collections.is_empty() {
+        println!(
+            "collection authorisations need the pool, run authorise-collection once it exists"
+        );
+        return Ok(());
+    }
+    authorise_collections(operator, &pool, &collections)
+}
+
+fn authorise_collections(operator: &Operator, pool: &Pubkey, collections: &[Pubkey]) -> Result<()> {
+    for chunk in collections.chunks(AUTHORISATIONS_PER_TRANSACTION) {
+        let authorisations: Vec<_> = chunk
+            .iter()
+            .map(|collection| {
+                instructions::create_purchase_authorisation(&operator.payer(), pool, *collection)
+            })
+            .collect();
+        operator.send("create_purchase_authorisation", &authorisations, &[])?;
+    }
+    Ok(())
+}
+
+fn status(operator: &Operator, pool: &Pubkey) -> Result<()> {
+    let state = operator.pool(pool)?;
+    let now = operator.now()?;
+    let quote: PoolQuote = operator.view(instructions::quote_pool(pool))?;
+    println!("pool: {pool}");
+    println!("status: {:?}", state.status_at(now));
+    println!("authority: {}", state.authority);
+    if let Some(delegate) = state.delegate.get() {
+        println!("delegate: {delegate}");
+    }
+    println!("reward mint: {}", state.mint);
+    println!("quote mint: {}", state.quote_mint);
+    println!(
+        "collected: {} / {}",
+        state.liquidity_collected, state.presale_target
+    );
+    println!("presale ends at: {}", state.presale_time_limit);
+    println!("purchase receipts: {}", state.purchase_receipt_count);
+    println!("frozen: {}", state.frozen.get());
+    if let Some(lp_mint) = state.lp_mint.get() {
+        println!("lp mint: {lp_mint}");
+    }
+    if let Some(vesting_started_at) = state.vesting_started_at.get() {
+        println!("vesting started at: {vesting_started_at}");
+        println!(
+            "receipts checked: {} / {}",
+            state.purchase_receipts_checked, state.purchase_receipt_count
+        );
+        println!("creator lp claimable: {}", quote.creator_lp_claimable);
+        println!("creator reward claimable: {}", quote.creator_mint_claimable);
+    }
+    if let Some(claim_deadline) = state.claim_deadline.get() {
+        println!("claim deadline: {claim_deadline}");
+    }
+    println!("event seq: {}", state.event_seq);
+    Ok(())
+}
+
+fn launch(
+    operator: &Operator,
+    pool: &Pubkey,
+    open_time: Option<u64>,
+    amm_config_index: Option<u16>,
+) -> Result<()> {
+    let state = operator.pool(pool)?;
+    let now = operator.now()?;
+    let status = state.status_at(now);
+    if status != PoolStatus::Filled || now < state.presale_time_limit {
+        bail!("pool {pool} can not launch while {status:?}");
+    }
+    let cpmm = match amm_config_index {
+        Some(index) => Cpmm::mainnet().with_amm_config_index(index),
+        None => Cpmm::mainnet(),
+    };
+    let open_time = open_time.unwrap_or(now as u64);
+    let launch_accounts =
+        cpmm.launch_accounts(pool, &state.mint, &state.quote_mint, &operator.payer());
+    println!("amm pool: {}", launch_accounts.pool_state);
+    println!("lp mint: {}", launch_accounts.lp_mint);
+
+    operator.send(
+        "launch_token_amm",
+        &[
+            ComputeBudgetInstruction::set_compute_unit_limit(LAUNCH_COMPUTE_UNIT_LIMIT),
+            instructions::launch_token_amm(&operator.payer(), pool, &state, &cpmm, open_time),
+        ],
+        &[],
+    )
+}
+
+fn claim_creator(operator: &Operator, pool: &Pubkey) -> Result<()> {
+    let state = operator.pool(pool)?;
+    if state.authority != operator.payer() {
+        bail!("only the pool authority {} can claim", state.authority);
+    }
+    if !state.status_at(operator.now()?).has_launched() {
+        bail!("pool {pool} has not launched");
+    }
+    // Each claim fails when nothing is claimable, only send the ones that pay out
+    let quote: PoolQuote = operator.view(instructions::quote_pool(pool))?;
+    let payer = operator.payer();
+    let mut claims = vec![];
+    if quote.creator_lp_claimable > 0 {
+        claims.push(instructions::withdraw_lp_tokens_for_creators(
+            &payer, &payer, pool, &state,
+        ));
+    }
+    if quote.creator_mint_claimable > 0 {
+        claims.push(instructions::claim_reward_token_for_creators(
+            &payer, &payer, pool, &state,
+        ));
+    }
+    if claims.is_empty() {
+        bail!("nothing to claim for pool {pool} yet");
+    }
+    operator.send("creator claims", &claims, &[])
+}
+
+fn receipts(operator: &Operator, pool: &Pubkey) -> Result<()> {
+    let mut purchase_receipts = operator.purchase_receipts(pool)?;
+    purchase_receipts
+        .sort_by_key(|(_, purchase_receipt)| std::cmp::Reverse(purchase_receipt.amount));
+    println!(
+        "{:<44} {:<44} {:>16} {:>20} {:>20}",
+        "receipt", "nft", "amount", "lp claimed", "reward claimed"
+    );
+    for (address, purchase_receipt) in &purchase_receipts {
+        let claimed = |claimed: u64, elligible: Option<u64>| match elligible {
+            Some(elligible) => format!("{claimed}/{elligible}"),
+            None => "-".to_string(),
+        };
+        println!(
+            "{:<44} {:<44} {:>16} {:>20} {:>20}{}",
+            address.to_string(),
+            purchase_receipt.original_mint.to_string(),
+            purchase_receipt.amount,
+            claimed(purchase_receipt.lp_claimed, purchase_receipt.lp_elligible),
+            claimed(
+                purchase_receipt.mint_claimed,
+                purchase_receipt.mint_elligible
+            ),
+            if purchase_receipt.reclaimed {
+                " reclaimed"
+            } else {
+                ""
+            },
+        );
+    }
+    println!("{} purchase receipts", purchase_receipts.len());
+    Ok(())
+}
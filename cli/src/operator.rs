@@ -0,0 +1,164 @@
+//! RPC access and transaction submission shared by the commands.
+use anchor_lang::{prelude::Pubkey, AnchorDeserialize, Discriminator};
+use anyhow::{anyhow, bail, Context, Result};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use safe_presale::state::{Pool, PurchaseReceipt, PURCHASE_RECEIPT_SIZE};
+use safe_presale_client::accounts::{decode_pool, decode_purchase_receipt};
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::{
+    rpc_client::RpcClient,
+    rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig, RpcSimulateTransactionConfig},
+    rpc_filter::{Memcmp, RpcFilterType},
+    rpc_response::RpcSimulateTransactionResult,
+};
+use solana_sdk::{
+    account::from_account,
+    clock::Clock,
+    commitment_config::CommitmentConfig,
+    instruction::Instruction,
+    signature::{Keypair, Signer},
+    sysvar,
+    transaction::Transaction,
+};
+
+/// Offset of `PurchaseReceipt::pool`, after the discriminator, version, is_initialized and bump.
+const PURCHASE_RECEIPT_POOL_OFFSET: usize = 8 + 1 + 1 + 1;
+
+pub struct Operator {
+    pub rpc: RpcClient,
+    pub payer: Keypair,
+    /// Simulate transactions and print their logs instead of sending them
+    pub dry_run: bool,
+}
+
+impl Operator {
+    pub fn new(url: String, payer: Keypair, dry_run: bool) -> Self {
+        Operator {
+            rpc: RpcClient::new_with_commitment(url, CommitmentConfig::confirmed()),
+            payer,
+            dry_run,
+        }
+    }
+
+    pub fn payer(&self) -> Pubkey {
+        self.payer.pubkey()
+    }
+
+    pub fn pool(&self, pool: &Pubkey) -> Result<Pool> {
+        let data = self
+            .rpc
+            .get_account_data(pool)
+            .with_context(|| format!("fetching pool {pool}"))?;
+        decode_pool(&data).map_err(|err| anyhow!("decoding pool {pool}: {err}"))
+    }
+
+    pub fn now(&self) -> Result<i64> {
+        let account = self.rpc.get_account(&sysvar::clock::ID)?;
+        let clock: Clock = from_account(&account).context("decoding the clock sysvar")?;
+        Ok(clock.unix_timestamp)
+    }
+
+    /// Purchase receipts of the pool with the current layout, receipts waiting for
+    /// `migrate_receipt` are left out.
+    pub fn purchase_receipts(&self, pool: &Pubkey) -> Result<Vec<(Pubkey, PurchaseReceipt)>> {
+        let config = RpcProgramAccountsConfig {
+            filters: Some(vec![
+                RpcFilterType::DataSize(PURCHASE_RECEIPT_SIZE as u64),
+                RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+                    0,
+                    &PurchaseReceipt::DISCRIMINATOR,
+                )),
+                RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+                    PURCHASE_RECEIPT_POOL_OFFSET,
+                    pool.as_ref(),
+                )),
+            ]),
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        self.rpc
+            .get_program_accounts_with_config(&safe_presale::ID, config)?
+            .into_iter()
+            .map(|(address, account)| {
+                decode_purchase_receipt(&account.data)
+                    .map(|purchase_receipt| (address, purchase_receipt))
+                    .map_err(|err| anyhow!("decoding purchase receipt {address}: {err}"))
+            })
+            .collect()
+    }
+
+    /// Sends the instructions in one transaction, or only simulates it on a dry run.
+    pub fn send(
+        &self,
+        label: &str,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Result<()> {
+        let mut all_signers = vec![&self.payer];
+        all_signers.extend_from_slice(signers);
+        let blockhash = self.rpc.get_latest_blockhash()?;
+        let transaction = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.payer()),
+            &all_signers,
+            blockhash,
+        );
+        if self.dry_run {
+            let result = self.simulate(&transaction)?;
+            println!("{label}: simulated");
+            print_simulation(&result);
+            if let Some(err) = result.err {
+                bail!("{label} would fail: {err}");
+            }
+            return Ok(());
+        }
+        let signature = self
+            .rpc
+            .send_and_confirm_transaction_with_spinner(&transaction)
+            .with_context(|| format!("sending {label}"))?;
+        println!("{label}: {signature}");
+        Ok(())
+    }
+
+    /// Simulates a view instruction and deserializes its return data.
+    pub fn view<T: AnchorDeserialize>(&self, instruction: Instruction) -> Result<T> {
+        let transaction = Transaction::new_with_payer(&[instruction], Some(&self.payer()));
+        let result = self.simulate(&transaction)?;
+        if let Some(err) = &result.err {
+            print_simulation(&result);
+            bail!("view failed: {err}");
+        }
+        let return_data = result
+            .return_data
+            .ok_or_else(|| anyhow!("view returned no data"))?;
+        let data = STANDARD.decode(return_data.data.0)?;
+        Ok(T::deserialize(&mut data.as_slice())?)
+    }
+
+    fn simulate(&self, transaction: &Transaction) -> Result<RpcSimulateTransactionResult> {
+        Ok(self
+            .rpc
+            .simulate_transaction_with_config(
+                transaction,
+                RpcSimulateTransactionConfig {
+                    sig_verify: false,
+                    replace_recent_blockhash: true,
+                    commitment: Some(CommitmentConfig::confirmed()),
+                    ..Default::default()
+                },
+            )?
+            .value)
+    }
+}
+
+fn print_simulation(result: &RpcSimulateTransactionResult) {
+    if let Some(units) = result.units_consumed {
+        println!("  compute units: {units}");
+    }
+    for log in result.logs.iter().flatten() {
+        println!("  {log}");
+    }
+}
@@ -0,0 +1,67 @@
+//! Decoders for the accounts of the program, from raw account data.
+use anchor_lang::{prelude::*, AccountDeserialize, Discriminator};
+use safe_presale::{
+    error::CustomError,
+    state::{Pool, PurchaseReceipt, POOL_SIZE, POOL_VERSION, PURCHASE_RECEIPT_SIZE},
+};
+
+/// Decodes a pool with the current layout. Pools of an earlier layout must go through
+/// `migrate_pool` first.
+pub fn decode_pool(data: &[u8]) -> Result<Pool> {
+    require!(
+        data.len() >= 8 && data[..8] == Pool::DISCRIMINATOR,
+        ErrorCode::AccountDiscriminatorMismatch
+    );
+    require!(data.len() == POOL_SIZE, CustomError::InvalidAccountLayout);
+    let pool: Pool = bytemuck::pod_read_unaligned(&data[8..]);
+    require!(
+        pool.version == POOL_VERSION,
+        CustomError::InvalidAccountLayout
+    );
+    Ok(pool)
+}
+
+/// Decodes a purchase receipt with the current layout. Receipts of an earlier layout must
+/// go through `migrate_receipt` first.
+pub fn decode_purchase_receipt(data: &[u8]) -> Result<PurchaseReceipt> {
+    require!(
+        data.len() == PURCHASE_RECEIPT_SIZE,
+        CustomError::InvalidAccountLayout
+    );
+    PurchaseReceipt::try_deserialize(&mut &data[..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytemuck::Zeroable;
+    use safe_presale::state::LEGACY_PURCHASE_RECEIPT_SIZE;
+
+    fn pool_data(pool: &Pool) -> Vec<u8> {
+        let mut data = Pool::DISCRIMINATOR.to_vec();
+        data.extend_from_slice(bytemuck::bytes_of(pool));
+        data
+    }
+
+    #[test]
+    fn decodes_current_pools_only() {
+        let mut pool = Pool::zeroed();
+        pool.version = POOL_VERSION;
+        pool.presale_target = 42;
+        assert_eq!(decode_pool(&pool_data(&pool)).unwrap().presale_target, 42);
+
+        pool.version = POOL_VERSION - 1;
+        assert!(decode_pool(&pool_data(&pool)).is_err());
+
+        let mut data = pool_data(&pool);
+        data[0] ^= 1;
+        assert!(decode_pool(&data).is_err());
+    }
+
+    #[test]
+    fn rejects_legacy_purchase_receipts() {
+        let mut data = PurchaseReceipt::DISCRIMINATOR.to_vec();
+        data.resize(LEGACY_PURCHASE_RECEIPT_SIZE, 0);
+        assert!(decode_purchase_receipt(&data).is_err());
+    }
+}
@@ -0,0 +1,980 @@
+//! Builders for every instruction of the program.
+//!
+//! Builders of instructions on an existing pool take the pool address and its decoded
+//! state, every other account is derived from them.
+use std::str::FromStr;
+
+use anchor_lang::{
+    prelude::AccountMeta,
+    solana_program::{instruction::Instruction, pubkey::Pubkey, system_program, sysvar},
+    InstructionData, ToAccountMetas,
+};
+use anchor_spl::{associated_token, token::spl_token};
+use safe_presale::{
+    instructions::{ApproveClaimArgs, InitPoolArgs, UpdatePoolArgs},
+    state::{Pool, PurchaseReceipt, FEE_COLLECTOR},
+};
+
+use crate::{pda, raydium::Cpmm};
+
+pub const TOKEN_PROGRAM: Pubkey = spl_token::ID;
+
+/// The nft of a purchase receipt and the token account holding it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ReceiptNft {
+    pub mint: Pubkey,
+    pub owner: Pubkey,
+    pub token_account: Pubkey,
+}
+
+impl ReceiptNft {
+    /// Nft held in the owner's associated token account, frozen or not.
+    pub fn in_wallet(mint: Pubkey, owner: Pubkey) -> Self {
+        ReceiptNft {
+            mint,
+            owner,
+            token_account: pda::token_account(&owner, &mint),
+        }
+    }
+}
+
+/// Allowlisted staking program holding the nft, see `add_staking_program`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Staking {
+    pub staking_program: Pubkey,
+    /// Freeze delegate of the nft, or the escrow vault holding it
+    pub staking_authority: Pubkey,
+}
+
+/// Why the payer of a claim may act for the nft owner.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Delegation {
+    /// The payer is the nft owner
+    Owner,
+    Staked(Staking),
+    /// The nft owner approved the payer with `approve_claim`, `scoped` when the approval only
+    /// covers the receipt of this nft
+    Approved {
+        scoped: bool,
+    },
+}
+
+fn instruction(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: safe_presale::ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// Lp mint of a launched pool.
+///
+/// # Panics
+///
+/// When the pool has not launched.
+fn lp_mint(state: &Pool) -> Pubkey {
+    state.lp_mint.get().expect("pool has not launched")
+}
+
+fn fee_collector() -> Pubkey {
+    Pubkey::from_str(FEE_COLLECTOR).unwrap()
+}
+
+/// `(purchase_receipt, nft_owner_nft_token_account)` pairs read by the batch claims.
+fn purchase_receipt_pairs(pool: &Pubkey, nft_owner: &Pubkey, nfts: &[Pubkey]) -> Vec<AccountMeta> {
+    nfts.iter()
+        .flat_map(|nft| {
+            [
+                AccountMeta::new(pda::purchase_receipt(pool, nft), false),
+                AccountMeta::new_readonly(pda::token_account(nft_owner, nft), false),
+            ]
+        })
+        .collect()
+}
+
+/// `(purchase_receipt, nft_owner_nft_token_account, receipt_payer)` triples read by the batch
+/// refund, `receipts` pairs every nft with whoever paid for its purchase receipt.
+fn purchase_receipt_refunds(
+    pool: &Pubkey,
+    nft_owner: &Pubkey,
+    receipts: &[(Pubkey, Pubkey)],
+) -> Vec<AccountMeta> {
+    receipts
+        .iter()
+        .flat_map(|(nft, receipt_payer)| {
+            [
+                AccountMeta::new(pda::purchase_receipt(pool, nft), false),
+                AccountMeta::new_readonly(pda::token_account(nft_owner, nft), false),
+                AccountMeta::new(*receipt_payer, false),
+            ]
+        })
+        .collect()
+}
+
+fn staking_accounts(pool: &Pubkey, delegation: Delegation) -> (Option<Pubkey>, Option<Pubkey>) {
+    match delegation {
+        Delegation::Staked(staking) => (
+            Some(pda::staking_program_record(pool, &staking.staking_program)),
+            Some(staking.staking_authority),
+        ),
+        Delegation::Owner | Delegation::Approved { .. } => (None, None),
+    }
+}
+
+fn claim_approval(
+    payer: &Pubkey,
+    purchase_receipt: &Pubkey,
+    nft: &ReceiptNft,
+    delegation: Delegation,
+) -> Option<Pubkey> {
+    match delegation {
+        Delegation::Approved { scoped } => Some(pda::claim_approval(
+            &nft.owner,
+            payer,
+            scoped.then_some(*purchase_receipt),
+        )),
+        Delegation::Owner | Delegation::Staked(_) => None,
+    }
+}
+
+pub fn init_config(payer: &Pubkey, admin: Pubkey) -> Instruction {
+    instruction(
+        safe_presale::accounts::InitConfigCtx {
+            config: pda::config(),
+            safe_presale_program: safe_presale::ID,
+            program_data: pda::program_data(),
+            payer: *payer,
+            system_program: system_program::ID,
+            event_authority: pda::event_authority(),
+            program: safe_presale::ID,
+        },
+        safe_presale::instruction::InitConfig { admin },
+    )
+}
+
+pub fn set_paused(admin: &Pubkey, paused: bool) -> Instruction {
+    instruction(
+        safe_presale::accounts::SetPausedCtx {
+            config: pda::config(),
+            admin: *admin,
+            event_authority: pda::event_authority(),
+            program: safe_presale::ID,
+        },
+        safe_presale::instruction::SetPaused { paused },
+    )
+}
+
+pub fn set_pool_frozen(admin: &Pubkey, pool: &Pubkey, frozen: bool) -> Instruction {
+    instruction(
+        safe_presale::accounts::SetPoolFrozenCtx {
+            config: pda::config(),
+            pool: *pool,
+            admin: *admin,
+            event_authority: pda::event_authority(),
+            program: safe_presale::ID,
+        },
+        safe_presale::instruction::SetPoolFrozen { frozen },
+    )
+}
+
+/// Creates the pool of `args.random_key`, its address is `pda::pool(&pda::reward_mint(random_key))`.
+pub fn init_pool(payer: &Pubkey, args: InitPoolArgs) -> Instruction {
+    let reward_mint = pda::reward_mint(args.random_key);
+    let pool = pda::pool(&reward_mint);
+    instruction(
+        safe_presale::accounts::InitPoolCtx {
+            reward_mint,
+            pool,
+            config: pda::config(),
+            reward_mint_metadata: pda::metadata(&reward_mint),
+            pool_reward_mint_token_account: pda::token_account(&pool, &reward_mint),
+            payer: *payer,
+            system_program: system_program::ID,
+            token_program: TOKEN_PROGRAM,
+            associated_token_program: associated_token::ID,
+            mpl_token_program: mpl_token_metadata::ID,
+            event_authority: pda::event_authority(),
+            program: safe_presale::ID,
+        },
+        safe_presale::instruction::InitPool { args },
+    )
+}
+
+/// Only includes the metadata accounts when the uri is updated.
+pub fn update_pool(
+    authority: &Pubkey,
+    pool: &Pubkey,
+    state: &Pool,
+    args: UpdatePoolArgs,
+) -> Instruction {
+    let updates_uri = args.uri.is_some();
+    instruction(
+        safe_presale::accounts::UpdatePoolCtx {
+            pool: *pool,
+            config: pda::config(),
+            reward_mint_metadata: updates_uri.then(|| pda::metadata(&state.mint)),
+            mpl_token_program: updates_uri.then_some(mpl_token_metadata::ID),
+            authority: *authority,
+            event_authority: pda::event_authority(),
+            program: safe_presale::ID,
+        },
+        safe_presale::instruction::UpdatePool { args },
+    )
+}
+
+pub fn create_purchase_authorisation(
+    authority: &Pubkey,
+    pool: &Pubkey,
+    collection_mint: Pubkey,
+) -> Instruction {
+    instruction(
+        safe_presale::accounts::CreatePurchaseAuthorizationCtx {
+            purchase_authorisation_record: pda::purchase_authorisation_record(
+                pool,
+                &collection_mint,
+            ),
+            pool: *pool,
+            config: pda::config(),
+            payer: *authority,
+            system_program: system_program::ID,
+            event_authority: pda::event_authority(),
+            program: safe_presale::ID,
+        },
+        safe_presale::instruction::CreatePurchaseAuthorisation { collection_mint },
+    )
+}
+
+pub fn add_staking_program(
+    authority: &Pubkey,
+    pool: &Pubkey,
+    staking_program: Pubkey,
+    escrow_seed: Option<Vec<u8>>,
+) -> Instruction {
+    instruction(
+        safe_presale::accounts::AddStakingProgramCtx {
+            staking_program_record: pda::staking_program_record(pool, &staking_program),
+            pool: *pool,
+            config: pda::config(),
+            authority: *authority,
+            system_program: system_program::ID,
+            event_authority: pda::event_authority(),
+            program: safe_presale::ID,
+        },
+        safe_presale::instruction::AddStakingProgram {
+            staking_program,
+            escrow_seed,
+        },
+    )
+}
+
+pub fn remove_staking_program(
+    authority: &Pubkey,
+    pool: &Pubkey,
+    staking_program: &Pubkey,
+) -> Instruction {
+    instruction(
+        safe_presale::accounts::RemoveStakingProgramCtx {
+            staking_program_record: pda::staking_program_record(pool, staking_program),
+            pool: *pool,
+            config: pda::config(),
+            authority: *authority,
+            event_authority: pda::event_authority(),
+            program: safe_presale::ID,
+        },
+        safe_presale::instruction::RemoveStakingProgram {},
+    )
+}
+
+pub fn set_delegate(authority: &Pubkey, pool: &Pubkey, delegate: Option<Pubkey>) -> Instruction {
+    instruction(
+        safe_presale::accounts::SetDelegateCtx {
+            pool: *pool,
+            config: pda::config(),
+            authority: *authority,
+            event_authority: pda::event_authority(),
+            program: safe_presale::ID,
+        },
+        safe_presale::instruction::SetDelegate { delegate },
+    )
+}
+
+pub fn propose_authority(
+    authority: &Pubkey,
+    pool: &Pubkey,
+    new_authority: Option<Pubkey>,
+) -> Instruction {
+    instruction(
+        safe_presale::accounts::ProposeAuthorityCtx {
+            pool: *pool,
+            config: pda::config(),
+            authority: *authority,
+            event_authority: pda::event_authority(),
+            program: safe_presale::ID,
+        },
+        safe_presale::instruction::ProposeAuthority { new_authority },
+    )
+}
+
+pub fn accept_authority(new_authority: &Pubkey, pool: &Pubkey) -> Instruction {
+    instruction(
+        safe_presale::accounts::AcceptAuthorityCtx {
+            pool: *pool,
+            config: pda::config(),
+            new_authority: *new_authority,
+            event_authority: pda::event_authority(),
+            program: safe_presale::ID,
+        },
+        safe_presale::instruction::AcceptAuthority {},
+    )
+}
+
+/// `collection_mint` is the verified collection of the nft, required by pools that
+/// restrict purchases to authorised collections.
+pub fn buy_presale(
+    payer: &Pubkey,
+    pool: &Pubkey,
+    state: &Pool,
+    nft: &Pubkey,
+    collection_mint: Option<Pubkey>,
+    amount: u64,
+) -> Instruction {
+    instruction(
+        safe_presale::accounts::BuyPresaleCtx {
+            purchase_receipt: pda::purchase_receipt(pool, nft),
+            pool: *pool,
+            config: pda::config(),
+            pool_quote_mint_token_account: pda::token_account(pool, &state.quote_mint),
+            quote_mint: state.quote_mint,
+            nft: *nft,
+            nft_metadata: pda::metadata(nft),
+            purchase_authorisation_record: collection_mint
+                .map(|collection_mint| pda::purchase_authorisation_record(pool, &collection_mint)),
+            payer: *payer,
+            payer_quote_mint_token_account: pda::token_account(payer, &state.quote_mint),
+            fee_collector_quote_mint_token_account: pda::token_account(
+                &fee_collector(),
+                &state.quote_mint,
+            ),
+            reward_mint_metadata: pda::metadata(&state.mint),
+            mpl_token_program: mpl_token_metadata::ID,
+            system_program: system_program::ID,
+            token_program: TOKEN_PROGRAM,
+            event_authority: pda::event_authority(),
+            program: safe_presale::ID,
+        },
+        safe_presale::instruction::BuyPresale { amount },
+    )
+}
+
+/// Refund of a failed or expired presale, the owner receives the quote tokens and
+/// `receipt_payer`, who paid for the purchase receipt, its rent.
+pub fn withdraw(
+    payer: &Pubkey,
+    pool: &Pubkey,
+    state: &Pool,
+    nft: &ReceiptNft,
+    receipt_payer: &Pubkey,
+    staking: Option<Staking>,
+) -> Instruction {
+    let delegation = staking.map_or(Delegation::Owner, Delegation::Staked);
+    let (staking_program_record, staking_authority) = staking_accounts(pool, delegation);
+    instruction(
+        safe_presale::accounts::Withdraw {
+            purchase_receipt: pda::purchase_receipt(pool, &nft.mint),
+            nft_owner_nft_token_account: nft.token_account,
+            pool: *pool,
+            nft_owner_quote_mint_token_account: pda::token_account(&nft.owner, &state.quote_mint),
+            pool_quote_mint_token_account: pda::token_account(pool, &state.quote_mint),
+            quote_mint: state.quote_mint,
+            nft_owner: nft.owner,
+            nft_metadata: pda::metadata(&nft.mint),
+            staking_program_record,
+            staking_authority,
+            receipt_payer: *receipt_payer,
+            payer: *payer,
+            system_program: system_program::ID,
+            token_program: TOKEN_PROGRAM,
+            associated_token_program: associated_token::ID,
+            event_authority: pda::event_authority(),
+            program: safe_presale::ID,
+        },
+        safe_presale::instruction::Withdraw {},
+    )
+}
+
+/// Refunds every receipt of the payer's nfts in one transfer, `receipts` pairs each nft with
+/// whoever paid for its purchase receipt, who receives the receipt rent.
+pub fn batch_withdraw(
+    payer: &Pubkey,
+    pool: &Pubkey,
+    state: &Pool,
+    receipts: &[(Pubkey, Pubkey)],
+) -> Instruction {
+    let mut instruction = instruction(
+        safe_presale::accounts::BatchWithdrawCtx {
+            pool: *pool,
+            nft_owner_quote_mint_token_account: pda::token_account(payer, &state.quote_mint),
+            pool_quote_mint_token_account: pda::token_account(pool, &state.quote_mint),
+            quote_mint: state.quote_mint,
+            nft_owner: *payer,
+            payer: *payer,
+            associated_token_program: associated_token::ID,
+            token_program: TOKEN_PROGRAM,
+            system_program: system_program::ID,
+            event_authority: pda::event_authority(),
+            program: safe_presale::ID,
+        },
+        safe_presale::instruction::BatchWithdraw {},
+    );
+    instruction
+        .accounts
+        .extend(purchase_receipt_refunds(pool, payer, receipts));
+    instruction
+}
+
+/// Launches the pool on the CPMM and makes the reward mint metadata immutable, `user_wallet`
+/// is the pool authority or its delegate.
+///
+/// The pool only launches on `Cpmm::mainnet`.
+pub fn launch_token_amm(
+    user_wallet: &Pubkey,
+    pool: &Pubkey,
+    state: &Pool,
+    cpmm: &Cpmm,
+    open_time: u64,
+) -> Instruction {
+    let mut instruction = instruction(
+        safe_presale::accounts::LaunchTokenAmmCtx {
+            pool: *pool,
+            config: pda::config(),
+            user_wallet: *user_wallet,
+            user_token_coin: pda::token_account(user_wallet, &state.mint),
+            user_token_pc: pda::token_account(user_wallet, &state.quote_mint),
+            pool_token_coin: pda::token_account(pool, &state.mint),
+            pool_token_pc: pda::token_account(pool, &state.quote_mint),
+            rent: sysvar::rent::ID,
+            system_program: system_program::ID,
+            token_program: TOKEN_PROGRAM,
+            associated_token_program: associated_token::ID,
+            amm_coin_mint: state.mint,
+            amm_pc_mint: state.quote_mint,
+            raydium_amm_program: cpmm.program_id,
+            reward_mint_metadata: pda::metadata(&state.mint),
+            mpl_token_program: mpl_token_metadata::ID,
+            event_authority: pda::event_authority(),
+            program: safe_presale::ID,
+        },
+        safe_presale::instruction::LaunchTokenAmm { open_time },
+    );
+    instruction.accounts.extend(
+        cpmm.launch_accounts(pool, &state.mint, &state.quote_mint, user_wallet)
+            .to_account_metas(),
+    );
+    instruction
+}
+
+/// Escrows the entitlement of the receipt of `nft` in the receipt's token accounts.
+pub fn check_claim_ellgibility(
+    payer: &Pubkey,
+    pool: &Pubkey,
+    state: &Pool,
+    nft: &Pubkey,
+) -> Instruction {
+    let purchase_receipt = pda::purchase_receipt(pool, nft);
+    let lp_mint = lp_mint(state);
+    instruction(
+        safe_presale::accounts::CheckClaimCtx {
+            purchase_receipt,
+            purchase_receipt_lp_token_account: pda::token_account(&purchase_receipt, &lp_mint),
+            pool_lp_token_account: pda::token_account(pool, &lp_mint),
+            lp_mint,
+            purchase_receipt_reward_token_account: pda::token_account(
+                &purchase_receipt,
+                &state.mint,
+            ),
+            pool_reward_token_account: pda::token_account(pool, &state.mint),
+            reward_mint: state.mint,
+            pool: *pool,
+            config: pda::config(),
+            payer: *payer,
+            associated_token_program: associated_token::ID,
+            token_program: TOKEN_PROGRAM,
+            system_program: system_program::ID,
+            event_authority: pda::event_authority(),
+            program: safe_presale::ID,
+        },
+        safe_presale::instruction::CheckClaimEllgibility {},
+    )
+}
+
+pub fn withdraw_lp_tokens(
+    payer: &Pubkey,
+    pool: &Pubkey,
+    state: &Pool,
+    nft: &ReceiptNft,
+    delegation: Delegation,
+) -> Instruction {
+    let purchase_receipt = pda::purchase_receipt(pool, &nft.mint);
+    let lp_mint = lp_mint(state);
+    let (staking_program_record, staking_authority) = staking_accounts(pool, delegation);
+    instruction(
+        safe_presale::accounts::WithdrawLpCtx {
+            purchase_receipt,
+            purchase_receipt_lp_token_account: pda::token_account(&purchase_receipt, &lp_mint),
+            pool: *pool,
+            nft_owner_nft_token_account: nft.token_account,
+            nft_owner_lp_token_account: pda::token_account(&nft.owner, &lp_mint),
+            nft_owner: nft.owner,
+            nft_metadata: pda::metadata(&nft.mint),
+            staking_program_record,
+            staking_authority,
+            claim_approval: claim_approval(payer, &purchase_receipt, nft, delegation),
+            lp_mint,
+            payer: *payer,
+            associated_token_program: associated_token::ID,
+            token_program: TOKEN_PROGRAM,
+            system_program: system_program::ID,
+            event_authority: pda::event_authority(),
+            program: safe_presale::ID,
+        },
+        safe_presale::instruction::WithdrawLpTokens {},
+    )
+}
+
+pub fn claim_reward_token(
+    payer: &Pubkey,
+    pool: &Pubkey,
+    state: &Pool,
+    nft: &ReceiptNft,
+    delegation: Delegation,
+) -> Instruction {
+    let purchase_receipt = pda::purchase_receipt(pool, &nft.mint);
+    let (staking_program_record, staking_authority) = staking_accounts(pool, delegation);
+    instruction(
+        safe_presale::accounts::ClaimRewardCtx {
+            purchase_receipt,
+            purchase_receipt_reward_token_account: pda::token_account(
+                &purchase_receipt,
+                &state.mint,
+            ),
+            pool: *pool,
+            nft_owner_nft_token_account: nft.token_account,
+            nft_owner_reward_token_account: pda::token_account(&nft.owner, &state.mint),
+            nft_owner: nft.owner,
+            nft_metadata: pda::metadata(&nft.mint),
+            staking_program_record,
+            staking_authority,
+            claim_approval: claim_approval(payer, &purchase_receipt, nft, delegation),
+            reward_mint: state.mint,
+            payer: *payer,
+            associated_token_program: associated_token::ID,
+            token_program: TOKEN_PROGRAM,
+            system_program: system_program::ID,
+            event_authority: pda::event_authority(),
+            program: safe_presale::ID,
+        },
+        safe_presale::instruction::ClaimRewardToken {},
+    )
+}
+
+/// Claims both vested allocations of the payer's `nft` straight from the pool vaults.
+pub fn claim_all(payer: &Pubkey, pool: &Pubkey, state: &Pool, nft: &Pubkey) -> Instruction {
+    let lp_mint = lp_mint(state);
+    instruction(
+        safe_presale::accounts::ClaimAllCtx {
+            purchase_receipt: pda::purchase_receipt(pool, nft),
+            pool: *pool,
+            pool_lp_token_account: pda::token_account(pool, &lp_mint),
+            pool_reward_token_account: pda::token_account(pool, &state.mint),
+            nft_owner_nft_token_account: pda::token_account(payer, nft),
+            nft_owner_lp_token_account: pda::token_account(payer, &lp_mint),
+            nft_owner_reward_token_account: pda::token_account(payer, &state.mint),
+            nft_owner: *payer,
+            lp_mint,
+            reward_mint: state.mint,
+            config: pda::config(),
+            payer: *payer,
+            associated_token_program: associated_token::ID,
+            token_program: TOKEN_PROGRAM,
+            system_program: system_program::ID,
+            event_authority: pda::event_authority(),
+            program: safe_presale::ID,
+        },
+        safe_presale::instruction::ClaimAll {},
+    )
+}
+
+pub fn batch_claim_reward_token(
+    payer: &Pubkey,
+    pool: &Pubkey,
+    state: &Pool,
+    nfts: &[Pubkey],
+) -> Instruction {
+    let mut instruction = instruction(
+        safe_presale::accounts::BatchClaimRewardCtx {
+            pool: *pool,
+            pool_reward_token_account: pda::token_account(pool, &state.mint),
+            nft_owner_reward_token_account: pda::token_account(payer, &state.mint),
+            nft_owner: *payer,
+            reward_mint: state.mint,
+            config: pda::config(),
+            payer: *payer,
+            associated_token_program: associated_token::ID,
+            token_program: TOKEN_PROGRAM,
+            system_program: system_program::ID,
+            event_authority: pda::event_authority(),
+            program: safe_presale::ID,
+        },
+        safe_presale::instruction::BatchClaimRewardToken {},
+    );
+    instruction
+        .accounts
+        .extend(purchase_receipt_pairs(pool, payer, nfts));
+    instruction
+}
+
+pub fn batch_withdraw_lp_tokens(
+    payer: &Pubkey,
+    pool: &Pubkey,
+    state: &Pool,
+    nfts: &[Pubkey],
+) -> Instruction {
+    let lp_mint = lp_mint(state);
+    let mut instruction = instruction(
+        safe_presale::accounts::BatchWithdrawLpCtx {
+            pool: *pool,
+            pool_lp_token_account: pda::token_account(pool, &lp_mint),
+            nft_owner_lp_token_account: pda::token_account(payer, &lp_mint),
+            nft_owner: *payer,
+            lp_mint,
+            config: pda::config(),
+            payer: *payer,
+            associated_token_program: associated_token::ID,
+            token_program: TOKEN_PROGRAM,
+            system_program: system_program::ID,
+            event_authority: pda::event_authority(),
+            program: safe_presale::ID,
+        },
+        safe_presale::instruction::BatchWithdrawLpTokens {},
+    );
+    instruction
+        .accounts
+        .extend(purchase_receipt_pairs(pool, payer, nfts));
+    instruction
+}
+
+pub fn withdraw_lp_tokens_for_creators(
+    payer: &Pubkey,
+    authority: &Pubkey,
+    pool: &Pubkey,
+    state: &Pool,
+) -> Instruction {
+    let lp_mint = lp_mint(state);
+    instruction(
+        safe_presale::accounts::WithdrawLpCreatorCtx {
+            pool_lp_token_account: pda::token_account(pool, &lp_mint),
+            pool_authority_lp_token_account: pda::token_account(authority, &lp_mint),
+            lp_mint,
+            pool: *pool,
+            authority: *authority,
+            payer: *payer,
+            associated_token_program: associated_token::ID,
+            token_program: TOKEN_PROGRAM,
+            system_program: system_program::ID,
+            event_authority: pda::event_authority(),
+            program: safe_presale::ID,
+        },
+        safe_presale::instruction::WithdrawLpTokensForCreators {},
+    )
+}
+
+pub fn claim_reward_token_for_creators(
+    payer: &Pubkey,
+    authority: &Pubkey,
+    pool: &Pubkey,
+    state: &Pool,
+) -> Instruction {
+    instruction(
+        safe_presale::accounts::ClaimRewardCreatorCtx {
+            pool_reward_token_account: pda::token_account(pool, &state.mint),
+            pool_authority_reward_token_account: pda::token_account(authority, &state.mint),
+            reward_mint: state.mint,
+            pool: *pool,
+            authority: *authority,
+            payer: *payer,
+            associated_token_program: associated_token::ID,
+            token_program: TOKEN_PROGRAM,
+            system_program: system_program::ID,
+            event_authority: pda::event_authority(),
+            program: safe_presale::ID,
+        },
+        safe_presale::instruction::ClaimRewardTokenForCreators {},
+    )
+}
+
+/// Votes to dissolve the creator lp allocation with the payer's `nft`.
+pub fn vote_dissolve(payer: &Pubkey, pool: &Pubkey, nft: &Pubkey) -> Instruction {
+    let purchase_receipt = pda::purchase_receipt(pool, nft);
+    instruction(
+        safe_presale::accounts::VoteDissolveCtx {
+            dissolve_vote_record: pda::dissolve_vote_record(&purchase_receipt),
+            purchase_receipt,
+            nft_owner_nft_token_account: pda::token_account(payer, nft),
+            pool: *pool,
+            config: pda::config(),
+            payer: *payer,
+            system_program: system_program::ID,
+            event_authority: pda::event_authority(),
+            program: safe_presale::ID,
+        },
+        safe_presale::instruction::VoteDissolve {},
+    )
+}
+
+pub fn claim_dissolved_lp_tokens(
+    payer: &Pubkey,
+    pool: &Pubkey,
+    state: &Pool,
+    nft: &Pubkey,
+) -> Instruction {
+    let lp_mint = lp_mint(state);
+    instruction(
+        safe_presale::accounts::ClaimDissolvedLpCtx {
+            purchase_receipt: pda::purchase_receipt(pool, nft),
+            pool_lp_token_account: pda::token_account(pool, &lp_mint),
+            pool: *pool,
+            nft_owner_nft_token_account: pda::token_account(payer, nft),
+            nft_owner_lp_token_account: pda::token_account(payer, &lp_mint),
+            nft_owner: *payer,
+            lp_mint,
+            config: pda::config(),
+            payer: *payer,
+            associated_token_program: associated_token::ID,
+            token_program: TOKEN_PROGRAM,
+            system_program: system_program::ID,
+            event_authority: pda::event_authority(),
+            program: safe_presale::ID,
+        },
+        safe_presale::instruction::ClaimDissolvedLpTokens {},
+    )
+}
+
+/// Moves the unclaimed entitlement of the receipt of `nft` to the pool's reclaim wallet.
+pub fn reclaim_purchase_receipt(
+    authority: &Pubkey,
+    pool: &Pubkey,
+    state: &Pool,
+    nft: &Pubkey,
+) -> Instruction {
+    let lp_mint = lp_mint(state);
+    let reclaim_wallet = state.reclaim_wallet();
+    instruction(
+        safe_presale::accounts::ReclaimPurchaseReceiptCtx {
+            purchase_receipt: pda::purchase_receipt(pool, nft),
+            pool: *pool,
+            config: pda::config(),
+            pool_lp_token_account: pda::token_account(pool, &lp_mint),
+            pool_reward_token_account: pda::token_account(pool, &state.mint),
+            destination_lp_token_account: pda::token_account(&reclaim_wallet, &lp_mint),
+            destination_reward_token_account: pda::token_account(&reclaim_wallet, &state.mint),
+            lp_mint,
+            reward_mint: state.mint,
+            authority: *authority,
+            token_program: TOKEN_PROGRAM,
+            event_authority: pda::event_authority(),
+            program: safe_presale::ID,
+        },
+        safe_presale::instruction::ReclaimPurchaseReceipt {},
+    )
+}
+
+/// Closes a fully claimed receipt, refunding the rent to whoever paid it.
+pub fn close_purchase_receipt(
+    payer: &Pubkey,
+    state: &Pool,
+    purchase_receipt: &PurchaseReceipt,
+) -> Instruction {
+    let address = pda::purchase_receipt(&purchase_receipt.pool, &purchase_receipt.original_mint);
+    // Reclaimed and directly claimed receipts never had claim token accounts
+    let escrowed = !purchase_receipt.reclaimed && !purchase_receipt.direct_claim;
+    instruction(
+        safe_presale::accounts::ClosePurchaseReceiptCtx {
+            purchase_receipt: address,
+            purchase_receipt_lp_token_account: escrowed
+                .then(|| pda::token_account(&address, &lp_mint(state))),
+            purchase_receipt_reward_token_account: escrowed
+                .then(|| pda::token_account(&address, &state.mint)),
+            pool: purchase_receipt.pool,
+            config: pda::config(),
+            receipt_payer: purchase_receipt.payer,
+            claim_payer: purchase_receipt.claim_payer.filter(|_| escrowed),
+            payer: *payer,
+            token_program: TOKEN_PROGRAM,
+            event_authority: pda::event_authority(),
+            program: safe_presale::ID,
+        },
+        safe_presale::instruction::ClosePurchaseReceipt {},
+    )
+}
+
+/// Closes the pool and its token accounts. The quote token account only exists once
+/// somebody bought into the presale, the lp token account rent goes to whoever launched.
+pub fn close_pool(
+    authority: &Pubkey,
+    pool: &Pubkey,
+    state: &Pool,
+    close_quote_token_account: bool,
+) -> Instruction {
+    instruction(
+        safe_presale::accounts::ClosePoolCtx {
+            pool: *pool,
+            config: pda::config(),
+            pool_reward_token_account: pda::token_account(pool, &state.mint),
+            pool_quote_token_account: close_quote_token_account
+                .then(|| pda::token_account(pool, &state.quote_mint)),
+            pool_lp_token_account: state
+                .lp_mint
+                .get()
+                .map(|lp_mint| pda::token_account(pool, &lp_mint)),
+            pool_payer: state.payer,
+            launch_payer: state.launch_payer.get(),
+            authority: *authority,
+            token_program: TOKEN_PROGRAM,
+            event_authority: pda::event_authority(),
+            program: safe_presale::ID,
+        },
+        safe_presale::instruction::ClosePool {},
+    )
+}
+
+/// Burns the rounding dust left in the pool vaults, or sends it to the authority.
+pub fn sweep_dust(authority: &Pubkey, pool: &Pubkey, state: &Pool, burn: bool) -> Instruction {
+    let lp_mint = lp_mint(state);
+    instruction(
+        safe_presale::accounts::SweepDustCtx {
+            pool: *pool,
+            config: pda::config(),
+            pool_lp_token_account: pda::token_account(pool, &lp_mint),
+            pool_reward_token_account: pda::token_account(pool, &state.mint),
+            lp_mint,
+            reward_mint: state.mint,
+            authority_lp_token_account: (!burn).then(|| pda::token_account(authority, &lp_mint)),
+            authority_reward_token_account: (!burn)
+                .then(|| pda::token_account(authority, &state.mint)),
+            authority: *authority,
+            token_program: TOKEN_PROGRAM,
+            event_authority: pda::event_authority(),
+            program: safe_presale::ID,
+        },
+        safe_presale::instruction::SweepDust { burn },
+    )
+}
+
+pub fn burn_reward_supply(payer: &Pubkey, pool: &Pubkey, state: &Pool) -> Instruction {
+    instruction(
+        safe_presale::accounts::BurnRewardSupplyCtx {
+            pool: *pool,
+            config: pda::config(),
+            pool_reward_token_account: pda::token_account(pool, &state.mint),
+            reward_mint: state.mint,
+            payer: *payer,
+            token_program: TOKEN_PROGRAM,
+            event_authority: pda::event_authority(),
+            program: safe_presale::ID,
+        },
+        safe_presale::instruction::BurnRewardSupply {},
+    )
+}
+
+pub fn approve_claim(owner: &Pubkey, delegate: &Pubkey, args: ApproveClaimArgs) -> Instruction {
+    instruction(
+        safe_presale::accounts::ApproveClaimCtx {
+            claim_approval: pda::claim_approval(owner, delegate, args.purchase_receipt),
+            delegate: *delegate,
+            owner: *owner,
+            system_program: system_program::ID,
+            event_authority: pda::event_authority(),
+            program: safe_presale::ID,
+        },
+        safe_presale::instruction::ApproveClaim { args },
+    )
+}
+
+/// Revokes the approval of `delegate`, scoped to `purchase_receipt` or covering every receipt.
+pub fn revoke_claim_approval(
+    owner: &Pubkey,
+    delegate: &Pubkey,
+    purchase_receipt: Option<Pubkey>,
+) -> Instruction {
+    instruction(
+        safe_presale::accounts::RevokeClaimApprovalCtx {
+            claim_approval: pda::claim_approval(owner, delegate, purchase_receipt),
+            owner: *owner,
+            event_authority: pda::event_authority(),
+            program: safe_presale::ID,
+        },
+        safe_presale::instruction::RevokeClaimApproval {},
+    )
+}
+
+/// View instruction, simulate it and decode the `PoolQuote` from the return data.
+pub fn quote_pool(pool: &Pubkey) -> Instruction {
+    instruction(
+        safe_presale::accounts::QuotePoolCtx { pool: *pool },
+        safe_presale::instruction::QuotePool {},
+    )
+}
+
+/// View instruction, simulate it and decode the `PurchaseReceiptQuote` from the return data.
+pub fn quote_purchase_receipt(pool: &Pubkey, nft: &Pubkey) -> Instruction {
+    instruction(
+        safe_presale::accounts::QuotePurchaseReceiptCtx {
+            purchase_receipt: pda::purchase_receipt(pool, nft),
+            pool: *pool,
+        },
+        safe_presale::instruction::QuotePurchaseReceipt {},
+    )
+}
+
+pub fn sync_pool_status(pool: &Pubkey) -> Instruction {
+    instruction(
+        safe_presale::accounts::SyncPoolStatusCtx {
+            pool: *pool,
+            config: pda::config(),
+            event_authority: pda::event_authority(),
+            program: safe_presale::ID,
+        },
+        safe_presale::instruction::SyncPoolStatus {},
+    )
+}
+
+pub fn migrate_pool(payer: &Pubkey, pool: &Pubkey) -> Instruction {
+    instruction(
+        safe_presale::accounts::MigratePoolCtx {
+            pool: *pool,
+            config: pda::config(),
+            payer: *payer,
+            system_program: system_program::ID,
+            event_authority: pda::event_authority(),
+            program: safe_presale::ID,
+        },
+        safe_presale::instruction::MigratePool {},
+    )
+}
+
+/// Migrates the receipt of the payer's `nft`, the payer becomes its rent recipient.
+pub fn migrate_receipt(payer: &Pubkey, pool: &Pubkey, nft: &Pubkey) -> Instruction {
+    instruction(
+        safe_presale::accounts::MigrateReceiptCtx {
+            purchase_receipt: pda::purchase_receipt(pool, nft),
+            pool: *pool,
+            config: pda::config(),
+            nft_owner_nft_token_account: pda::token_account(payer, nft),
+            payer: *payer,
+            system_program: system_program::ID,
+            event_authority: pda::event_authority(),
+            program: safe_presale::ID,
+        },
+        safe_presale::instruction::MigrateReceipt {},
+    )
+}
@@ -0,0 +1,11 @@
+//! Rust client for the safe_presale program.
+//!
+//! `pda` derives the program addresses, `raydium` the Raydium CPMM addresses used at launch,
+//! `instructions` builds every instruction of the program and `accounts` decodes its accounts.
+//! Token accounts are the associated token accounts of the classic token program.
+pub mod accounts;
+pub mod instructions;
+pub mod pda;
+pub mod raydium;
+
+pub use safe_presale::ID;
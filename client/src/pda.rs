@@ -0,0 +1,114 @@
+//! Program derived addresses of the safe_presale program.
+use anchor_lang::solana_program::{bpf_loader_upgradeable, pubkey::Pubkey};
+use anchor_spl::associated_token::get_associated_token_address;
+use safe_presale::state::{
+    ClaimApproval, CLAIM_APPROVAL_PREFIX, CONFIG_PREFIX, DISSOLVE_VOTE_PREFIX, MINT_PREFIX,
+    POOL_PREFIX, PURCHASE_AUTHORISATION_PREFIX, PURCHASE_RECEIPT_PREFIX, STAKING_PROGRAM_PREFIX,
+};
+
+pub fn config() -> Pubkey {
+    Pubkey::find_program_address(&[CONFIG_PREFIX.as_bytes()], &safe_presale::ID).0
+}
+
+/// Signs the self CPI of `emit_cpi!`.
+pub fn event_authority() -> Pubkey {
+    Pubkey::find_program_address(&[b"__event_authority"], &safe_presale::ID).0
+}
+
+/// Upgradeable loader data account of the program, checked by `init_config`.
+pub fn program_data() -> Pubkey {
+    Pubkey::find_program_address(&[safe_presale::ID.as_ref()], &bpf_loader_upgradeable::ID).0
+}
+
+/// Reward mint of the pool created with `random_key`.
+pub fn reward_mint(random_key: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[MINT_PREFIX.as_bytes(), random_key.to_le_bytes().as_ref()],
+        &safe_presale::ID,
+    )
+    .0
+}
+
+pub fn pool(reward_mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[POOL_PREFIX.as_bytes(), reward_mint.as_ref()],
+        &safe_presale::ID,
+    )
+    .0
+}
+
+pub fn purchase_receipt(pool: &Pubkey, nft: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            PURCHASE_RECEIPT_PREFIX.as_bytes(),
+            pool.as_ref(),
+            nft.as_ref(),
+        ],
+        &safe_presale::ID,
+    )
+    .0
+}
+
+pub fn purchase_authorisation_record(pool: &Pubkey, collection_mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            PURCHASE_AUTHORISATION_PREFIX.as_bytes(),
+            pool.as_ref(),
+            collection_mint.as_ref(),
+        ],
+        &safe_presale::ID,
+    )
+    .0
+}
+
+pub fn staking_program_record(pool: &Pubkey, staking_program: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            STAKING_PROGRAM_PREFIX.as_bytes(),
+            pool.as_ref(),
+            staking_program.as_ref(),
+        ],
+        &safe_presale::ID,
+    )
+    .0
+}
+
+/// Approval of `delegate` by `owner`, scoped to `purchase_receipt` or covering all their receipts.
+pub fn claim_approval(
+    owner: &Pubkey,
+    delegate: &Pubkey,
+    purchase_receipt: Option<Pubkey>,
+) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            CLAIM_APPROVAL_PREFIX.as_bytes(),
+            owner.as_ref(),
+            delegate.as_ref(),
+            ClaimApproval::scope_seed(&purchase_receipt),
+        ],
+        &safe_presale::ID,
+    )
+    .0
+}
+
+pub fn dissolve_vote_record(purchase_receipt: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[DISSOLVE_VOTE_PREFIX.as_bytes(), purchase_receipt.as_ref()],
+        &safe_presale::ID,
+    )
+    .0
+}
+
+/// Token metadata account of `mint`.
+pub fn metadata(mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"metadata", mpl_token_metadata::ID.as_ref(), mint.as_ref()],
+        &mpl_token_metadata::ID,
+    )
+    .0
+}
+
+/// Associated token account of `owner` for `mint`, owner may be a PDA.
+pub fn token_account(owner: &Pubkey, mint: &Pubkey) -> Pubkey {
+    get_associated_token_address(owner, mint)
+}
@@ -0,0 +1,212 @@
+//! Addresses of the Raydium CPMM pool created by `launch_token_amm`.
+use std::str::FromStr;
+
+use anchor_lang::{prelude::AccountMeta, solana_program::pubkey::Pubkey};
+use safe_presale::state::{RAYDIUM_CPMM_V4_DEVNET, RAYDIUM_CPMM_V4_MAINNET};
+
+use crate::pda;
+
+pub const CREATE_POOL_FEE_RECEIVER_MAINNET: &str = "DNXgeM9EiiaAbaWvwjHj9fQQLAX5ZsfHyxVYXXW5Vd6W";
+pub const CREATE_POOL_FEE_RECEIVER_DEVNET: &str = "G11FKBRaAkHAKuLCgLM6K6NUc9rTjPAznRCjZifrTQe2";
+pub const AMM_CONFIG_DEVNET: &str = "9zSzfkYy6awexsHvmggeH36pfVUdDGyCcwmjT3AQPBj6";
+
+/// A CPMM deployment and the fee tier new pools are created with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cpmm {
+    pub program_id: Pubkey,
+    pub amm_config: Pubkey,
+    pub create_pool_fee_receiver: Pubkey,
+}
+
+impl Cpmm {
+    /// Mainnet deployment with the first fee tier. The program only launches on mainnet.
+    pub fn mainnet() -> Self {
+        let program_id = Pubkey::from_str(RAYDIUM_CPMM_V4_MAINNET).unwrap();
+        Cpmm {
+            program_id,
+            amm_config: amm_config(&program_id, 0),
+            create_pool_fee_receiver: Pubkey::from_str(CREATE_POOL_FEE_RECEIVER_MAINNET).unwrap(),
+        }
+    }
+
+    pub fn devnet() -> Self {
+        Cpmm {
+            program_id: Pubkey::from_str(RAYDIUM_CPMM_V4_DEVNET).unwrap(),
+            amm_config: Pubkey::from_str(AMM_CONFIG_DEVNET).unwrap(),
+            create_pool_fee_receiver: Pubkey::from_str(CREATE_POOL_FEE_RECEIVER_DEVNET).unwrap(),
+        }
+    }
+
+    /// Same deployment with the fee tier at `index`.
+    pub fn with_amm_config_index(self, index: u16) -> Self {
+        Cpmm {
+            amm_config: amm_config(&self.program_id, index),
+            ..self
+        }
+    }
+
+    /// Signs for the vaults and mints the lp tokens of every pool.
+    pub fn authority(&self) -> Pubkey {
+        Pubkey::find_program_address(&[b"vault_and_lp_mint_auth_seed"], &self.program_id).0
+    }
+
+    /// Pool of the two mints, in either order.
+    pub fn pool_state(&self, mint_a: &Pubkey, mint_b: &Pubkey) -> Pubkey {
+        let (token_0_mint, token_1_mint) = sort_mints(*mint_a, *mint_b);
+        Pubkey::find_program_address(
+            &[
+                b"pool",
+                self.amm_config.as_ref(),
+                token_0_mint.as_ref(),
+                token_1_mint.as_ref(),
+            ],
+            &self.program_id,
+        )
+        .0
+    }
+
+    pub fn lp_mint(&self, pool_state: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"pool_lp_mint", pool_state.as_ref()], &self.program_id).0
+    }
+
+    pub fn vault(&self, pool_state: &Pubkey, mint: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(
+            &[b"pool_vault", pool_state.as_ref(), mint.as_ref()],
+            &self.program_id,
+        )
+        .0
+    }
+
+    pub fn observation_state(&self, pool_state: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"observation", pool_state.as_ref()], &self.program_id).0
+    }
+
+    /// Every address of the pool launched for the presale `pool` of `reward_mint`, paid by
+    /// `user_wallet`.
+    pub fn launch_accounts(
+        &self,
+        pool: &Pubkey,
+        reward_mint: &Pubkey,
+        quote_mint: &Pubkey,
+        user_wallet: &Pubkey,
+    ) -> LaunchAccounts {
+        let (token_0_mint, token_1_mint) = sort_mints(*reward_mint, *quote_mint);
+        let pool_state = self.pool_state(&token_0_mint, &token_1_mint);
+        let lp_mint = self.lp_mint(&pool_state);
+        LaunchAccounts {
+            lp_mint,
+            user_token_lp: pda::token_account(user_wallet, &lp_mint),
+            pool_token_lp: pda::token_account(pool, &lp_mint),
+            amm_config: self.amm_config,
+            authority: self.authority(),
+            pool_state,
+            token_0_vault: self.vault(&pool_state, &token_0_mint),
+            token_1_vault: self.vault(&pool_state, &token_1_mint),
+            create_pool_fee_receiver: self.create_pool_fee_receiver,
+            observation_state: self.observation_state(&pool_state),
+        }
+    }
+}
+
+/// The CPMM orders the mints of a pool by address.
+pub fn sort_mints(mint_a: Pubkey, mint_b: Pubkey) -> (Pubkey, Pubkey) {
+    if mint_a < mint_b {
+        (mint_a, mint_b)
+    } else {
+        (mint_b, mint_a)
+    }
+}
+
+pub fn amm_config(program_id: &Pubkey, index: u16) -> Pubkey {
+    Pubkey::find_program_address(&[b"amm_config", &index.to_be_bytes()], program_id).0
+}
+
+/// Accounts `launch_token_amm` reads from its remaining accounts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LaunchAccounts {
+    pub lp_mint: Pubkey,
+    /// Receives the lp tokens minted by the CPMM
+    pub user_token_lp: Pubkey,
+    /// Holds the lp tokens of the presale, created by the launch
+    pub pool_token_lp: Pubkey,
+    pub amm_config: Pubkey,
+    pub authority: Pubkey,
+    pub pool_state: Pubkey,
+    pub token_0_vault: Pubkey,
+    pub token_1_vault: Pubkey,
+    pub create_pool_fee_receiver: Pubkey,
+    pub observation_state: Pubkey,
+}
+
+impl LaunchAccounts {
+    /// Remaining accounts in the order the handler reads them.
+    pub fn to_account_metas(&self) -> Vec<AccountMeta> {
+        vec![
+            AccountMeta::new(self.lp_mint, false),
+            AccountMeta::new(self.user_token_lp, false),
+            AccountMeta::new(self.pool_token_lp, false),
+            AccountMeta::new(self.amm_config, false),
+            AccountMeta::new_readonly(self.authority, false),
+            AccountMeta::new(self.pool_state, false),
+            AccountMeta::new(self.token_0_vault, false),
+            AccountMeta::new(self.token_1_vault, false),
+            AccountMeta::new(self.create_pool_fee_receiver, false),
+            AccountMeta::new(self.observation_state, false),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pool_state_ignores_mint_order() {
+        let cpmm = Cpmm::mainnet();
+        let (mint_a, mint_b) = (Pubkey::new_unique(), Pubkey::new_unique());
+        assert_eq!(
+            cpmm.pool_state(&mint_a, &mint_b),
+            cpmm.pool_state(&mint_b, &mint_a)
+        );
+    }
+
+    #[test]
+    fn launch_accounts_follow_the_handler_layout() {
+        let cpmm = Cpmm::devnet();
+        let (pool, reward_mint, quote_mint, user_wallet) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let launch = cpmm.launch_accounts(&pool, &reward_mint, &quote_mint, &user_wallet);
+        let (token_0_mint, token_1_mint) = sort_mints(reward_mint, quote_mint);
+        let pool_state = cpmm.pool_state(&reward_mint, &quote_mint);
+        let lp_mint = cpmm.lp_mint(&pool_state);
+
+        let metas = launch.to_account_metas();
+        let expected = [
+            lp_mint,
+            pda::token_account(&user_wallet, &lp_mint),
+            pda::token_account(&pool, &lp_mint),
+            Pubkey::from_str(AMM_CONFIG_DEVNET).unwrap(),
+            cpmm.authority(),
+            pool_state,
+            cpmm.vault(&pool_state, &token_0_mint),
+            cpmm.vault(&pool_state, &token_1_mint),
+            Pubkey::from_str(CREATE_POOL_FEE_RECEIVER_DEVNET).unwrap(),
+            cpmm.observation_state(&pool_state),
+        ];
+        assert_eq!(
+            metas.iter().map(|meta| meta.pubkey).collect::<Vec<_>>(),
+            expected
+        );
+        assert!(metas.iter().all(|meta| !meta.is_signer));
+        assert_eq!(
+            metas.iter().filter(|meta| !meta.is_writable).count(),
+            1,
+            "only the authority is read only"
+        );
+        assert!(!metas[4].is_writable);
+    }
+}
@@ -0,0 +1,161 @@
+//! Decoding of the events emitted with `emit_cpi!`.
+//!
+//! The program emits an event by invoking itself with the event as instruction data:
+//! `EVENT_IX_TAG_LE`, then the event discriminator and its Borsh serialization.
+use anchor_lang::{event::EVENT_IX_TAG_LE, prelude::Pubkey, AnchorDeserialize, Discriminator};
+use anyhow::{Context, Result};
+use safe_presale::state::*;
+
+macro_rules! events {
+    (pool: [$($pool_event:ident),* $(,)?], global: [$($global_event:ident),* $(,)?] $(,)?) => {
+        /// Every event of the program.
+        pub enum Event {
+            $($pool_event($pool_event),)*
+            $($global_event($global_event),)*
+        }
+
+        impl Event {
+            /// Decodes an event from its discriminator and Borsh serialization, `None` for
+            /// unknown discriminators.
+            pub fn decode(data: &[u8]) -> Result<Option<Event>> {
+                if data.len() < 8 {
+                    return Ok(None);
+                }
+                let (discriminator, mut event) = data.split_at(8);
+                $(
+                    if discriminator == $pool_event::DISCRIMINATOR {
+                        let event = $pool_event::deserialize(&mut event)
+                            .context(stringify!($pool_event))?;
+                        return Ok(Some(Event::$pool_event(event)));
+                    }
+                )*
+                $(
+                    if discriminator == $global_event::DISCRIMINATOR {
+                        let event = $global_event::deserialize(&mut event)
+                            .context(stringify!($global_event))?;
+                        return Ok(Some(Event::$global_event(event)));
+                    }
+                )*
+                Ok(None)
+            }
+
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $(Event::$pool_event(_) => stringify!($pool_event),)*
+                    $(Event::$global_event(_) => stringify!($global_event),)*
+                }
+            }
+
+            /// Pool of the event and its sequence number, `None` for events not tied to a pool.
+            pub fn pool(&self) -> Option<(Pubkey, u64)> {
+                match self {
+                    $(Event::$pool_event(event) => Some((event.pool, event.event_seq)),)*
+                    $(Event::$global_event(_) => None,)*
+                }
+            }
+        }
+    };
+}
+
+events! {
+    pool: [
+        InitializedPoolEvent,
+        CreatePurchaseAuthorisationEvent,
+        StakingProgramAddedEvent,
+        StakingProgramRemovedEvent,
+        PurchasedPresaleEvent,
+        CheckClaimEvent,
+        ClaimRewardEvent,
+        ClaimRewardForCreatorEvent,
+        WithdrawLpTokenForCreatorEvent,
+        WithdrawLpTokenEvent,
+        LaunchTokenAmmEvent,
+        WithdrawEvent,
+        DissolveVoteEvent,
+        PoolDissolvedEvent,
+        ClaimDissolvedLpTokenEvent,
+        DelegateUpdatedEvent,
+        AuthorityTransferProposedEvent,
+        AuthorityTransferredEvent,
+        PoolFrozenEvent,
+        PoolUpdatedEvent,
+        ClosePurchaseReceiptEvent,
+        ClosePoolEvent,
+        SweepDustEvent,
+        BurnRewardSupplyEvent,
+        ReclaimPurchaseReceiptEvent,
+        ClaimAllEvent,
+        BatchClaimRewardEvent,
+        BatchWithdrawLpTokenEvent,
+        BatchWithdrawEvent,
+        PoolStatusChanged,
+        MigratePoolEvent,
+        MigratePurchaseReceiptEvent,
+    ],
+    global: [
+        ClaimApprovalUpdatedEvent,
+        ClaimApprovalRevokedEvent,
+        InitializedConfigEvent,
+        ProgramPausedEvent,
+    ],
+}
+
+/// Event carried by the data of an instruction invoking the program, `None` when the
+/// instruction is not an event.
+pub fn event_data(instruction_data: &[u8]) -> Option<&[u8]> {
+    instruction_data.strip_prefix(EVENT_IX_TAG_LE.as_slice())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::AnchorSerialize;
+
+    /// Instruction data of the self invocation made by `emit_cpi!`.
+    pub fn emit_cpi_data(event: &impl anchor_lang::Event) -> Vec<u8> {
+        let mut data = EVENT_IX_TAG_LE.to_vec();
+        data.extend(event.data());
+        data
+    }
+
+    #[test]
+    fn decodes_emitted_events() {
+        let pool = Pubkey::new_unique();
+        let purchased = PurchasedPresaleEvent {
+            payer: Pubkey::new_unique(),
+            amount: 5,
+            pool,
+            original_mint: Pubkey::new_unique(),
+            receipt_amount: 5,
+            liquidity_collected: 15,
+            purchase_receipt_count: 3,
+            event_seq: 9,
+        };
+        let data = emit_cpi_data(&purchased);
+        let event = Event::decode(event_data(&data).unwrap()).unwrap().unwrap();
+        assert_eq!(event.name(), "PurchasedPresaleEvent");
+        assert_eq!(event.pool(), Some((pool, 9)));
+        let Event::PurchasedPresaleEvent(decoded) = event else {
+            panic!("decoded as another event");
+        };
+        assert_eq!(
+            decoded.try_to_vec().unwrap(),
+            purchased.try_to_vec().unwrap()
+        );
+
+        let paused = ProgramPausedEvent {
+            admin: Pubkey::new_unique(),
+            paused: true,
+        };
+        let event = Event::decode(event_data(&emit_cpi_data(&paused)).unwrap())
+            .unwrap()
+            .unwrap();
+        assert_eq!(event.pool(), None);
+    }
+
+    #[test]
+    fn ignores_other_instructions() {
+        assert!(event_data(&[1, 2, 3]).is_none());
+        assert!(Event::decode(&[0; 16]).unwrap().is_none());
+    }
+}
@@ -0,0 +1,13 @@
+//! Indexer of the safe_presale events.
+//!
+//! Events emitted with `emit_cpi!` are read from the inner instructions of confirmed
+//! transactions, stored as is in SQLite and projected into pools, purchase receipts, claims
+//! and refunds.
+pub mod events;
+pub mod source;
+pub mod store;
+pub mod transaction;
+
+pub use events::Event;
+pub use store::Store;
+pub use transaction::{extract_events, EventRecord};
@@ -0,0 +1,82 @@
+//! Indexes the safe_presale events of confirmed transactions into a SQLite database.
+use std::path::PathBuf;
+
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+use safe_presale_indexer::{extract_events, source, Store};
+use solana_transaction_status::EncodedConfirmedTransactionWithStatusMeta;
+
+#[derive(Parser)]
+#[command(
+    name = "safe-presale-indexer",
+    about = "Index safe_presale events into SQLite"
+)]
+struct Cli {
+    /// SQLite database, created when missing
+    #[arg(
+        long,
+        short = 'd',
+        env = "SAFE_PRESALE_INDEXER_DB",
+        default_value = "safe_presale.db"
+    )]
+    database: PathBuf,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Index the events of confirmed transactions
+    Ingest {
+        /// getTransaction results as a JSON array or JSON lines, `-` for stdin
+        #[arg(long, conflicts_with = "rpc", required_unless_present = "rpc")]
+        file: Option<PathBuf>,
+        /// RPC endpoint to fetch every transaction of the program from
+        #[arg(long)]
+        rpc: Option<String>,
+        /// Stop at this signature, the last one already indexed
+        #[arg(long, requires = "rpc")]
+        until: Option<String>,
+        /// Drop everything indexed before
+        #[arg(long)]
+        from_scratch: bool,
+    },
+    /// Rebuild the pools, receipts, claims and refunds from the stored events
+    Replay,
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let mut store = Store::open(&cli.database)?;
+
+    match cli.command {
+        Command::Ingest {
+            file,
+            rpc,
+            until,
+            from_scratch,
+        } => {
+            if from_scratch {
+                store.reset()?;
+            }
+            let mut transactions = 0;
+            let mut events = 0;
+            let mut ingest = |transaction: EncodedConfirmedTransactionWithStatusMeta| {
+                events += store.ingest(&extract_events(&transaction)?)?;
+                transactions += 1;
+                Ok(())
+            };
+            match (file, rpc) {
+                (Some(file), _) => source::read_file(&file, &mut ingest)?,
+                (None, Some(rpc)) => source::fetch_rpc(&rpc, until.as_deref(), &mut ingest)?,
+                (None, None) => unreachable!("clap requires a source"),
+            }
+            println!("{events} new events from {transactions} transactions");
+        }
+        Command::Replay => {
+            let events = store.replay()?;
+            println!("{events} events replayed");
+        }
+    }
+    Ok(())
+}
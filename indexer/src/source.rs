@@ -0,0 +1,105 @@
+//! Where the confirmed transactions come from.
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, Read},
+    path::Path,
+    str::FromStr,
+};
+
+use anyhow::{Context, Result};
+use solana_client::{
+    rpc_client::{GetConfirmedSignaturesForAddress2Config, RpcClient},
+    rpc_config::RpcTransactionConfig,
+};
+use solana_sdk::{commitment_config::CommitmentConfig, signature::Signature};
+use solana_transaction_status::{EncodedConfirmedTransactionWithStatusMeta, UiTransactionEncoding};
+
+/// Signatures requested per `getSignaturesForAddress` page, the RPC maximum.
+const SIGNATURES_PER_PAGE: usize = 1000;
+
+/// Reads a dump of `getTransaction` results, either a JSON array or one transaction per line.
+/// `-` reads from stdin, which lets a geyser plugin or another process stream transactions in.
+pub fn read_file(
+    path: &Path,
+    mut on_transaction: impl FnMut(EncodedConfirmedTransactionWithStatusMeta) -> Result<()>,
+) -> Result<()> {
+    let reader: Box<dyn Read> = if path == Path::new("-") {
+        Box::new(std::io::stdin())
+    } else {
+        Box::new(File::open(path).with_context(|| format!("opening {}", path.display()))?)
+    };
+    let mut reader = BufReader::new(reader);
+    let first = reader
+        .fill_buf()?
+        .iter()
+        .find(|byte| !byte.is_ascii_whitespace());
+    if first == Some(&b'[') {
+        let transactions: Vec<EncodedConfirmedTransactionWithStatusMeta> =
+            serde_json::from_reader(reader)
+                .with_context(|| format!("parsing {}", path.display()))?;
+        return transactions.into_iter().try_for_each(on_transaction);
+    }
+    for (number, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let transaction = serde_json::from_str(&line)
+            .with_context(|| format!("parsing line {} of {}", number + 1, path.display()))?;
+        on_transaction(transaction)?;
+    }
+    Ok(())
+}
+
+/// Fetches the transactions of the program, oldest first, stopping at `until` when given.
+pub fn fetch_rpc(
+    url: &str,
+    until: Option<&str>,
+    mut on_transaction: impl FnMut(EncodedConfirmedTransactionWithStatusMeta) -> Result<()>,
+) -> Result<()> {
+    let rpc = RpcClient::new_with_commitment(url.to_string(), CommitmentConfig::confirmed());
+    let until = until.map(Signature::from_str).transpose()?;
+    let mut signatures = Vec::new();
+    let mut before = None;
+    loop {
+        let page = rpc
+            .get_signatures_for_address_with_config(
+                &safe_presale::ID,
+                GetConfirmedSignaturesForAddress2Config {
+                    before,
+                    until,
+                    limit: Some(SIGNATURES_PER_PAGE),
+                    commitment: Some(CommitmentConfig::confirmed()),
+                },
+            )
+            .context("fetching the program signatures")?;
+        let Some(last) = page.last() else {
+            break;
+        };
+        before = Some(Signature::from_str(&last.signature)?);
+        let full_page = page.len() == SIGNATURES_PER_PAGE;
+        signatures.extend(
+            page.into_iter()
+                .filter(|status| status.err.is_none())
+                .map(|status| status.signature),
+        );
+        if !full_page {
+            break;
+        }
+    }
+
+    for signature in signatures.iter().rev() {
+        let transaction = rpc
+            .get_transaction_with_config(
+                &Signature::from_str(signature)?,
+                RpcTransactionConfig {
+                    encoding: Some(UiTransactionEncoding::Json),
+                    commitment: Some(CommitmentConfig::confirmed()),
+                    max_supported_transaction_version: Some(0),
+                },
+            )
+            .with_context(|| format!("fetching transaction {signature}"))?;
+        on_transaction(transaction)?;
+    }
+    Ok(())
+}
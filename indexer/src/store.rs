@@ -0,0 +1,899 @@
+//! SQLite database of the indexed events and of the pools, purchase receipts, claims and
+//! refunds projected from them.
+//!
+//! The raw events are the source of truth, the other tables can be rebuilt from them with
+//! [`Store::replay`]. Pool events are applied in `event_seq` order, an event at or below the
+//! last applied sequence number of its pool is stored but not applied again, and gaps are
+//! counted in `pools.missed_events` until a replay folds in the late events.
+use std::path::Path;
+
+use anchor_lang::prelude::Pubkey;
+use anyhow::{Context, Result};
+use rusqlite::{params, Connection, OptionalExtension, Transaction};
+use safe_presale::state::PoolStatus;
+
+use crate::{events::Event, transaction::EventRecord};
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS events (
+    signature TEXT NOT NULL,
+    event_index INTEGER NOT NULL,
+    slot INTEGER NOT NULL,
+    block_time INTEGER,
+    name TEXT NOT NULL,
+    pool TEXT,
+    event_seq INTEGER,
+    data BLOB NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+CREATE INDEX IF NOT EXISTS events_pool ON events (pool, event_seq);
+CREATE TABLE IF NOT EXISTS pools (
+    pool TEXT PRIMARY KEY,
+    authority TEXT,
+    delegate TEXT,
+    mint TEXT,
+    quote_mint TEXT,
+    lp_mint TEXT,
+    status TEXT,
+    presale_target INTEGER NOT NULL DEFAULT 0,
+    presale_time_limit INTEGER NOT NULL DEFAULT 0,
+    liquidity_collected INTEGER NOT NULL DEFAULT 0,
+    purchase_receipt_count INTEGER NOT NULL DEFAULT 0,
+    purchase_receipts_checked INTEGER NOT NULL DEFAULT 0,
+    lp_mint_distributed INTEGER NOT NULL DEFAULT 0,
+    initial_supply_distributed INTEGER NOT NULL DEFAULT 0,
+    vesting_started_at INTEGER,
+    claim_deadline INTEGER,
+    lp_mint_supply INTEGER NOT NULL DEFAULT 0,
+    lp_mint_supply_for_creator INTEGER NOT NULL DEFAULT 0,
+    lp_mint_claimed_by_creator INTEGER NOT NULL DEFAULT 0,
+    initial_supply_claimed_by_creator INTEGER NOT NULL DEFAULT 0,
+    frozen INTEGER NOT NULL DEFAULT 0,
+    dissolved INTEGER NOT NULL DEFAULT 0,
+    closed INTEGER NOT NULL DEFAULT 0,
+    event_seq INTEGER NOT NULL DEFAULT 0,
+    missed_events INTEGER NOT NULL DEFAULT 0
+);
+CREATE TABLE IF NOT EXISTS receipts (
+    pool TEXT NOT NULL,
+    original_mint TEXT NOT NULL,
+    payer TEXT,
+    amount INTEGER NOT NULL DEFAULT 0,
+    lp_elligible INTEGER,
+    mint_elligible INTEGER,
+    lp_claimed INTEGER NOT NULL DEFAULT 0,
+    mint_claimed INTEGER NOT NULL DEFAULT 0,
+    dissolved_lp_claimed INTEGER NOT NULL DEFAULT 0,
+    refunded INTEGER NOT NULL DEFAULT 0,
+    reclaimed INTEGER NOT NULL DEFAULT 0,
+    closed INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (pool, original_mint)
+);
+CREATE TABLE IF NOT EXISTS claims (
+    signature TEXT NOT NULL,
+    event_index INTEGER NOT NULL,
+    pool TEXT NOT NULL,
+    original_mint TEXT,
+    owner TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    amount INTEGER NOT NULL,
+    claimed_at INTEGER
+);
+CREATE INDEX IF NOT EXISTS claims_pool ON claims (pool);
+CREATE TABLE IF NOT EXISTS refunds (
+    signature TEXT NOT NULL,
+    event_index INTEGER NOT NULL,
+    pool TEXT NOT NULL,
+    original_mint TEXT NOT NULL,
+    owner TEXT NOT NULL,
+    amount INTEGER NOT NULL
+);
+CREATE INDEX IF NOT EXISTS refunds_pool ON refunds (pool);
+";
+
+const PROJECTIONS: [&str; 4] = ["pools", "receipts", "claims", "refunds"];
+
+/// What a claim paid out.
+#[derive(Clone, Copy)]
+enum ClaimKind {
+    Lp,
+    Reward,
+    DissolvedLp,
+    CreatorLp,
+    CreatorReward,
+}
+
+impl ClaimKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            ClaimKind::Lp => "lp",
+            ClaimKind::Reward => "reward",
+            ClaimKind::DissolvedLp => "dissolved_lp",
+            ClaimKind::CreatorLp => "creator_lp",
+            ClaimKind::CreatorReward => "creator_reward",
+        }
+    }
+}
+
+pub struct Store {
+    connection: Connection,
+}
+
+impl Store {
+    pub fn open(path: &Path) -> Result<Self> {
+        let connection =
+            Connection::open(path).with_context(|| format!("opening {}", path.display()))?;
+        Self::with_connection(connection)
+    }
+
+    pub fn open_in_memory() -> Result<Self> {
+        Self::with_connection(Connection::open_in_memory()?)
+    }
+
+    fn with_connection(connection: Connection) -> Result<Self> {
+        connection.execute_batch(SCHEMA)?;
+        Ok(Store { connection })
+    }
+
+    pub fn connection(&self) -> &Connection {
+        &self.connection
+    }
+
+    /// Drops every indexed event and projection.
+    pub fn reset(&mut self) -> Result<()> {
+        let transaction = self.connection.transaction()?;
+        transaction.execute("DELETE FROM events", [])?;
+        for table in PROJECTIONS {
+            transaction.execute(&format!("DELETE FROM {table}"), [])?;
+        }
+        transaction.commit()?;
+        Ok(())
+    }
+
+    /// Stores the events of a transaction and applies them, returns the number of events not
+    /// indexed before.
+    pub fn ingest(&mut self, records: &[EventRecord]) -> Result<usize> {
+        let transaction = self.connection.transaction()?;
+        let mut ingested = 0;
+        for record in records {
+            let (pool, event_seq) = record.event.pool().unzip();
+            let inserted = transaction.execute(
+                "INSERT OR IGNORE INTO events
+                    (signature, event_index, slot, block_time, name, pool, event_seq, data)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                params![
+                    record.signature,
+                    record.event_index,
+                    record.slot,
+                    record.block_time,
+                    record.event.name(),
+                    pool.map(|pool| pool.to_string()),
+                    event_seq,
+                    record.data,
+                ],
+            )?;
+            if inserted == 0 {
+                continue;
+            }
+            ingested += 1;
+            apply(
+                &transaction,
+                &record.signature,
+                record.event_index,
+                &record.event,
+            )?;
+        }
+        transaction.commit()?;
+        Ok(ingested)
+    }
+
+    /// Rebuilds the projections from the stored events, returns the number of events applied.
+    pub fn replay(&mut self) -> Result<usize> {
+        let transaction = self.connection.transaction()?;
+        for table in PROJECTIONS {
+            transaction.execute(&format!("DELETE FROM {table}"), [])?;
+        }
+        let mut applied = 0;
+        {
+            let mut statement = transaction.prepare(
+                "SELECT signature, event_index, data FROM events
+                 WHERE pool IS NOT NULL ORDER BY pool, event_seq, slot, rowid",
+            )?;
+            let mut rows = statement.query([])?;
+            while let Some(row) = rows.next()? {
+                let signature: String = row.get(0)?;
+                let event_index: u32 = row.get(1)?;
+                let data: Vec<u8> = row.get(2)?;
+                let event = Event::decode(&data)?
+                    .with_context(|| format!("unknown event {signature}:{event_index}"))?;
+                apply(&transaction, &signature, event_index, &event)?;
+                applied += 1;
+            }
+        }
+        transaction.commit()?;
+        Ok(applied)
+    }
+}
+
+/// Applies a pool event to the projections, global events are only kept in `events`.
+fn apply(
+    transaction: &Transaction,
+    signature: &str,
+    event_index: u32,
+    event: &Event,
+) -> Result<()> {
+    let Some((pool, event_seq)) = event.pool() else {
+        return Ok(());
+    };
+    let pool = pool.to_string();
+    transaction.execute("INSERT OR IGNORE INTO pools (pool) VALUES (?1)", [&pool])?;
+    let last_event_seq: u64 = transaction.query_row(
+        "SELECT event_seq FROM pools WHERE pool = ?1",
+        [&pool],
+        |row| row.get(0),
+    )?;
+    if event_seq <= last_event_seq {
+        return Ok(());
+    }
+    transaction.execute(
+        "UPDATE pools SET event_seq = ?2, missed_events = missed_events + ?3 WHERE pool = ?1",
+        params![pool, event_seq, event_seq - last_event_seq - 1],
+    )?;
+    let claim = |original_mint: Option<&Pubkey>,
+                 owner: &Pubkey,
+                 kind: ClaimKind,
+                 amount: u64,
+                 claimed_at: Option<i64>| {
+        insert_claim(
+            transaction,
+            signature,
+            event_index,
+            &pool,
+            original_mint,
+            owner,
+            kind,
+            amount,
+            claimed_at,
+        )
+    };
+
+    match event {
+        Event::InitializedPoolEvent(event) => {
+            transaction.execute(
+                "UPDATE pools SET authority = ?2, delegate = ?3, mint = ?4, quote_mint = ?5,
+                    presale_target = ?6, presale_time_limit = ?7, status = ?8
+                 WHERE pool = ?1",
+                params![
+                    pool,
+                    event.authority.to_string(),
+                    event.delegate.map(|delegate| delegate.to_string()),
+                    event.mint.to_string(),
+                    event.quote_mint.to_string(),
+                    event.presale_target,
+                    event.presale_time_limit,
+                    status_name(PoolStatus::Upcoming),
+                ],
+            )?;
+        }
+        Event::PurchasedPresaleEvent(event) => {
+            transaction.execute(
+                "UPDATE pools SET liquidity_collected = ?2, purchase_receipt_count = ?3
+                 WHERE pool = ?1",
+                params![
+                    pool,
+                    event.liquidity_collected,
+                    event.purchase_receipt_count
+                ],
+            )?;
+            let original_mint = event.original_mint.to_string();
+            insert_receipt(transaction, &pool, &original_mint)?;
+            transaction.execute(
+                "UPDATE receipts SET payer = ?3, amount = ?4 WHERE pool = ?1 AND original_mint = ?2",
+                params![pool, original_mint, event.payer.to_string(), event.receipt_amount],
+            )?;
+        }
+        Event::CheckClaimEvent(event) => {
+            update_distribution(
+                transaction,
+                &pool,
+                event.lp_mint_distributed,
+                event.initial_supply_distributed,
+                event.purchase_receipts_checked,
+            )?;
+            update_receipt(
+                transaction,
+                &pool,
+                &event.original_mint,
+                "lp_elligible = ?3, mint_elligible = ?4",
+                params![event.lp_elligible, event.mint_elligible],
+            )?;
+        }
+        Event::ClaimRewardEvent(event) => {
+            update_receipt(
+                transaction,
+                &pool,
+                &event.original_mint,
+                "mint_claimed = ?3",
+                params![event.total_mint_claimed],
+            )?;
+            claim(
+                Some(&event.original_mint),
+                &event.original_mint_owner,
+                ClaimKind::Reward,
+                event.mint_claimed,
+                Some(event.last_claimed_at),
+            )?;
+        }
+        Event::WithdrawLpTokenEvent(event) => {
+            update_receipt(
+                transaction,
+                &pool,
+                &event.original_mint,
+                "lp_claimed = ?3",
+                params![event.total_lp_claimed],
+            )?;
+            claim(
+                Some(&event.original_mint),
+                &event.original_mint_owner,
+                ClaimKind::Lp,
+                event.lp_claimed,
+                Some(event.last_claimed_at),
+            )?;
+        }
+        Event::ClaimAllEvent(event) => {
+            update_distribution(
+                transaction,
+                &pool,
+                event.lp_mint_distributed,
+                event.initial_supply_distributed,
+                event.purchase_receipts_checked,
+            )?;
+            update_receipt(
+                transaction,
+                &pool,
+                &event.original_mint,
+                "lp_elligible = ?3, mint_elligible = ?4, lp_claimed = ?5, mint_claimed = ?6",
+                params![
+                    event.lp_elligible,
+                    event.mint_elligible,
+                    event.total_lp_claimed,
+                    event.total_mint_claimed
+                ],
+            )?;
+            for (kind, amount) in [
+                (ClaimKind::Lp, event.lp_claimed),
+                (ClaimKind::Reward, event.mint_claimed),
+            ] {
+                if amount > 0 {
+                    claim(
+                        Some(&event.original_mint),
+                        &event.original_mint_owner,
+                        kind,
+                        amount,
+                        Some(event.last_claimed_at),
+                    )?;
+                }
+            }
+        }
+        Event::BatchClaimRewardEvent(event) => {
+            update_distribution(
+                transaction,
+                &pool,
+                event.lp_mint_distributed,
+                event.initial_supply_distributed,
+                event.purchase_receipts_checked,
+            )?;
+            for (original_mint, total_mint_claimed) in
+                event.original_mints.iter().zip(&event.total_mint_claimed)
+            {
+                let previous = receipt_amount(transaction, &pool, original_mint, "mint_claimed")?;
+                update_receipt(
+                    transaction,
+                    &pool,
+                    original_mint,
+                    "mint_claimed = ?3",
+                    params![total_mint_claimed],
+                )?;
+                claim(
+                    Some(original_mint),
+                    &event.original_mint_owner,
+                    ClaimKind::Reward,
+                    total_mint_claimed.saturating_sub(previous),
+                    Some(event.last_claimed_at),
+                )?;
+            }
+        }
+        Event::BatchWithdrawLpTokenEvent(event) => {
+            update_distribution(
+                transaction,
+                &pool,
+                event.lp_mint_distributed,
+                event.initial_supply_distributed,
+                event.purchase_receipts_checked,
+            )?;
+            for (original_mint, total_lp_claimed) in
+                event.original_mints.iter().zip(&event.total_lp_claimed)
+            {
+                let previous = receipt_amount(transaction, &pool, original_mint, "lp_claimed")?;
+                update_receipt(
+                    transaction,
+                    &pool,
+                    original_mint,
+                    "lp_claimed = ?3",
+                    params![total_lp_claimed],
+                )?;
+                claim(
+                    Some(original_mint),
+                    &event.original_mint_owner,
+                    ClaimKind::Lp,
+                    total_lp_claimed.saturating_sub(previous),
+                    Some(event.last_claimed_at),
+                )?;
+            }
+        }
+        Event::ClaimRewardForCreatorEvent(event) => {
+            transaction.execute(
+                "UPDATE pools SET initial_supply_claimed_by_creator = ?2 WHERE pool = ?1",
+                params![pool, event.initial_supply_claimed_by_creator],
+            )?;
+            claim(
+                None,
+                &event.payer,
+                ClaimKind::CreatorReward,
+                event.mint_claimed,
+                Some(event.last_claimed_at),
+            )?;
+        }
+        Event::WithdrawLpTokenForCreatorEvent(event) => {
+            transaction.execute(
+                "UPDATE pools SET lp_mint_claimed_by_creator = ?2 WHERE pool = ?1",
+                params![pool, event.lp_mint_claimed_by_creator],
+            )?;
+            claim(
+                None,
+                &event.payer,
+                ClaimKind::CreatorLp,
+                event.lp_claimed,
+                Some(event.last_claimed_at),
+            )?;
+        }
+        Event::ClaimDissolvedLpTokenEvent(event) => {
+            update_receipt(
+                transaction,
+                &pool,
+                &event.original_mint,
+                "dissolved_lp_claimed = ?3",
+                params![event.lp_claimed],
+            )?;
+            claim(
+                Some(&event.original_mint),
+                &event.original_mint_owner,
+                ClaimKind::DissolvedLp,
+                event.lp_claimed,
+                None,
+            )?;
+        }
+        Event::LaunchTokenAmmEvent(event) => {
+            transaction.execute(
+                "UPDATE pools SET lp_mint = ?2, vesting_started_at = ?3, claim_deadline = ?4,
+                    lp_mint_supply = ?5, lp_mint_supply_for_creator = ?6
+                 WHERE pool = ?1",
+                params![
+                    pool,
+                    event.lp_mint.to_string(),
+                    event.vesting_started_at,
+                    event.claim_deadline,
+                    event.lp_mint_supply,
+                    event.lp_mint_supply_for_creator,
+                ],
+            )?;
+        }
+        Event::WithdrawEvent(event) => {
+            transaction.execute(
+                "UPDATE pools SET purchase_receipt_count = ?2 WHERE pool = ?1",
+                params![pool, event.purchase_receipt_count],
+            )?;
+            refund(
+                transaction,
+                signature,
+                event_index,
+                &pool,
+                &event.original_mint,
+                &event.original_mint_owner,
+                Some(event.amount_withdrawn),
+                event.purchase_receipt_closed,
+            )?;
+        }
+        Event::BatchWithdrawEvent(event) => {
+            transaction.execute(
+                "UPDATE pools SET purchase_receipt_count = ?2 WHERE pool = ?1",
+                params![pool, event.purchase_receipt_count],
+            )?;
+            for original_mint in &event.original_mints {
+                refund(
+                    transaction,
+                    signature,
+                    event_index,
+                    &pool,
+                    original_mint,
+                    &event.original_mint_owner,
+                    None,
+                    event.purchase_receipts_closed,
+                )?;
+            }
+        }
+        Event::ReclaimPurchaseReceiptEvent(event) => {
+            update_distribution(
+                transaction,
+                &pool,
+                event.lp_mint_distributed,
+                event.initial_supply_distributed,
+                event.purchase_receipts_checked,
+            )?;
+            update_receipt(
+                transaction,
+                &pool,
+                &event.original_mint,
+                "reclaimed = 1",
+                params![],
+            )?;
+        }
+        Event::ClosePurchaseReceiptEvent(event) => {
+            transaction.execute(
+                "UPDATE pools SET purchase_receipt_count = ?2, purchase_receipts_checked = ?3
+                 WHERE pool = ?1",
+                params![
+                    pool,
+                    event.purchase_receipt_count,
+                    event.purchase_receipts_checked
+                ],
+            )?;
+            update_receipt(
+                transaction,
+                &pool,
+                &event.original_mint,
+                "closed = 1",
+                params![],
+            )?;
+        }
+        Event::MigratePurchaseReceiptEvent(event) => {
+            transaction.execute(
+                "UPDATE pools SET purchase_receipt_count = ?2 WHERE pool = ?1",
+                params![pool, event.purchase_receipt_count],
+            )?;
+            update_distribution(
+                transaction,
+                &pool,
+                event.lp_mint_distributed,
+                event.initial_supply_distributed,
+                event.purchase_receipts_checked,
+            )?;
+            insert_receipt(transaction, &pool, &event.original_mint.to_string())?;
+        }
+        Event::PoolStatusChanged(event) => set_status(transaction, &pool, event.status)?,
+        Event::MigratePoolEvent(event) => set_status(transaction, &pool, event.status)?,
+        Event::PoolDissolvedEvent(_) => {
+            transaction.execute("UPDATE pools SET dissolved = 1 WHERE pool = ?1", [&pool])?;
+        }
+        Event::ClosePoolEvent(_) => {
+            transaction.execute("UPDATE pools SET closed = 1 WHERE pool = ?1", [&pool])?;
+        }
+        Event::PoolFrozenEvent(event) => {
+            transaction.execute(
+                "UPDATE pools SET frozen = ?2 WHERE pool = ?1",
+                params![pool, event.frozen],
+            )?;
+        }
+        Event::PoolUpdatedEvent(event) => {
+            transaction.execute(
+                "UPDATE pools SET presale_target = ?2, presale_time_limit = ?3 WHERE pool = ?1",
+                params![pool, event.presale_target, event.presale_time_limit],
+            )?;
+        }
+        Event::DelegateUpdatedEvent(event) => {
+            transaction.execute(
+                "UPDATE pools SET delegate = ?2 WHERE pool = ?1",
+                params![pool, event.delegate.map(|delegate| delegate.to_string())],
+            )?;
+        }
+        Event::AuthorityTransferredEvent(event) => {
+            transaction.execute(
+                "UPDATE pools SET authority = ?2 WHERE pool = ?1",
+                params![pool, event.authority.to_string()],
+            )?;
+        }
+        // Only advance the sequence number of the pool
+        Event::CreatePurchaseAuthorisationEvent(_)
+        | Event::StakingProgramAddedEvent(_)
+        | Event::StakingProgramRemovedEvent(_)
+        | Event::DissolveVoteEvent(_)
+        | Event::AuthorityTransferProposedEvent(_)
+        | Event::SweepDustEvent(_)
+        | Event::BurnRewardSupplyEvent(_)
+        | Event::ClaimApprovalUpdatedEvent(_)
+        | Event::ClaimApprovalRevokedEvent(_)
+        | Event::InitializedConfigEvent(_)
+        | Event::ProgramPausedEvent(_) => {}
+    }
+    Ok(())
+}
+
+fn status_name(status: PoolStatus) -> String {
+    format!("{status:?}")
+}
+
+fn set_status(transaction: &Transaction, pool: &str, status: PoolStatus) -> Result<()> {
+    transaction.execute(
+        "UPDATE pools SET status = ?2 WHERE pool = ?1",
+        params![pool, status_name(status)],
+    )?;
+    Ok(())
+}
+
+fn update_distribution(
+    transaction: &Transaction,
+    pool: &str,
+    lp_mint_distributed: u64,
+    initial_supply_distributed: u64,
+    purchase_receipts_checked: u32,
+) -> Result<()> {
+    transaction.execute(
+        "UPDATE pools SET lp_mint_distributed = ?2, initial_supply_distributed = ?3,
+            purchase_receipts_checked = ?4
+         WHERE pool = ?1",
+        params![
+            pool,
+            lp_mint_distributed,
+            initial_supply_distributed,
+            purchase_receipts_checked
+        ],
+    )?;
+    Ok(())
+}
+
+/// Creates the receipt row if the purchase that opened it was not indexed.
+fn insert_receipt(transaction: &Transaction, pool: &str, original_mint: &str) -> Result<()> {
+    transaction.execute(
+        "INSERT OR IGNORE INTO receipts (pool, original_mint) VALUES (?1, ?2)",
+        [pool, original_mint],
+    )?;
+    Ok(())
+}
+
+/// Sets columns of a receipt, the assignments refer to the values as `?3` onwards.
+fn update_receipt(
+    transaction: &Transaction,
+    pool: &str,
+    original_mint: &Pubkey,
+    assignments: &str,
+    values: &[&dyn rusqlite::ToSql],
+) -> Result<()> {
+    let original_mint = original_mint.to_string();
+    insert_receipt(transaction, pool, &original_mint)?;
+    let mut all_values: Vec<&dyn rusqlite::ToSql> = vec![&pool, &original_mint];
+    all_values.extend_from_slice(values);
+    transaction.execute(
+        &format!("UPDATE receipts SET {assignments} WHERE pool = ?1 AND original_mint = ?2"),
+        all_values.as_slice(),
+    )?;
+    Ok(())
+}
+
+fn receipt_amount(
+    transaction: &Transaction,
+    pool: &str,
+    original_mint: &Pubkey,
+    column: &str,
+) -> Result<u64> {
+    Ok(transaction
+        .query_row(
+            &format!("SELECT {column} FROM receipts WHERE pool = ?1 AND original_mint = ?2"),
+            [pool, &original_mint.to_string()],
+            |row| row.get(0),
+        )
+        .optional()?
+        .unwrap_or_default())
+}
+
+#[allow(clippy::too_many_arguments)]
+fn insert_claim(
+    transaction: &Transaction,
+    signature: &str,
+    event_index: u32,
+    pool: &str,
+    original_mint: Option<&Pubkey>,
+    owner: &Pubkey,
+    kind: ClaimKind,
+    amount: u64,
+    claimed_at: Option<i64>,
+) -> Result<()> {
+    transaction.execute(
+        "INSERT INTO claims
+            (signature, event_index, pool, original_mint, owner, kind, amount, claimed_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        params![
+            signature,
+            event_index,
+            pool,
+            original_mint.map(|original_mint| original_mint.to_string()),
+            owner.to_string(),
+            kind.as_str(),
+            amount,
+            claimed_at,
+        ],
+    )?;
+    Ok(())
+}
+
+/// Records the refund of a receipt, batch withdrawals refund the whole receipt amount.
+#[allow(clippy::too_many_arguments)]
+fn refund(
+    transaction: &Transaction,
+    signature: &str,
+    event_index: u32,
+    pool: &str,
+    original_mint: &Pubkey,
+    owner: &Pubkey,
+    amount: Option<u64>,
+    closed: bool,
+) -> Result<()> {
+    let amount = match amount {
+        Some(amount) => amount,
+        None => receipt_amount(transaction, pool, original_mint, "amount")?,
+    };
+    update_receipt(
+        transaction,
+        pool,
+        original_mint,
+        "refunded = 1, closed = ?3",
+        params![closed],
+    )?;
+    transaction.execute(
+        "INSERT INTO refunds (signature, event_index, pool, original_mint, owner, amount)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![
+            signature,
+            event_index,
+            pool,
+            original_mint.to_string(),
+            owner.to_string(),
+            amount
+        ],
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use safe_presale::state::{ClaimRewardEvent, PurchasedPresaleEvent, WithdrawEvent};
+
+    fn record(signature: &str, event: &impl anchor_lang::Event) -> EventRecord {
+        let data = event.data();
+        EventRecord {
+            signature: signature.to_string(),
+            event_index: 0,
+            slot: 1,
+            block_time: None,
+            event: Event::decode(&data).unwrap().unwrap(),
+            data,
+        }
+    }
+
+    fn purchased(pool: Pubkey, original_mint: Pubkey, event_seq: u64) -> PurchasedPresaleEvent {
+        PurchasedPresaleEvent {
+            payer: Pubkey::new_unique(),
+            amount: 10,
+            pool,
+            original_mint,
+            receipt_amount: 10,
+            liquidity_collected: 10 * event_seq,
+            purchase_receipt_count: event_seq as u32,
+            event_seq,
+        }
+    }
+
+    fn pool_row(store: &Store, pool: &Pubkey) -> (u64, u64, u64) {
+        store
+            .connection()
+            .query_row(
+                "SELECT liquidity_collected, event_seq, missed_events FROM pools WHERE pool = ?1",
+                [pool.to_string()],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .unwrap()
+    }
+
+    fn count(store: &Store, table: &str) -> u64 {
+        store
+            .connection()
+            .query_row(&format!("SELECT COUNT(*) FROM {table}"), [], |row| {
+                row.get(0)
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn projects_purchases_claims_and_refunds() {
+        let mut store = Store::open_in_memory().unwrap();
+        let pool = Pubkey::new_unique();
+        let (first, second) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let owner = Pubkey::new_unique();
+        let claim = ClaimRewardEvent {
+            payer: owner,
+            pool,
+            original_mint: first,
+            original_mint_owner: owner,
+            mint_claimed: 7,
+            last_claimed_at: 100,
+            total_mint_claimed: 7,
+            event_seq: 3,
+        };
+        let withdraw = WithdrawEvent {
+            payer: owner,
+            pool,
+            original_mint: second,
+            amount_withdrawn: 10,
+            original_mint_owner: owner,
+            purchase_receipt: Pubkey::new_unique(),
+            purchase_receipt_closed: true,
+            purchase_receipt_count: 1,
+            event_seq: 4,
+        };
+        let records = [
+            record("a", &purchased(pool, first, 1)),
+            record("b", &purchased(pool, second, 2)),
+            record("c", &claim),
+            record("d", &withdraw),
+        ];
+        assert_eq!(store.ingest(&records).unwrap(), 4);
+        assert_eq!(store.ingest(&records).unwrap(), 0);
+
+        assert_eq!(pool_row(&store, &pool), (20, 4, 0));
+        assert_eq!(count(&store, "receipts"), 2);
+        let (kind, amount): (String, u64) = store
+            .connection()
+            .query_row("SELECT kind, amount FROM claims", [], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })
+            .unwrap();
+        assert_eq!((kind.as_str(), amount), ("reward", 7));
+        let (refunded, closed): (bool, bool) = store
+            .connection()
+            .query_row(
+                "SELECT refunded, closed FROM receipts WHERE original_mint = ?1",
+                [second.to_string()],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert!(refunded && closed);
+        assert_eq!(count(&store, "refunds"), 1);
+    }
+
+    #[test]
+    fn replay_folds_in_late_events() {
+        let mut store = Store::open_in_memory().unwrap();
+        let pool = Pubkey::new_unique();
+        store
+            .ingest(&[record("a", &purchased(pool, Pubkey::new_unique(), 1))])
+            .unwrap();
+        store
+            .ingest(&[record("c", &purchased(pool, Pubkey::new_unique(), 3))])
+            .unwrap();
+        assert_eq!(pool_row(&store, &pool), (30, 3, 1));
+
+        // Arrives after its successor, stored but not applied
+        store
+            .ingest(&[record("b", &purchased(pool, Pubkey::new_unique(), 2))])
+            .unwrap();
+        assert_eq!(count(&store, "receipts"), 2);
+
+        assert_eq!(store.replay().unwrap(), 3);
+        assert_eq!(pool_row(&store, &pool), (30, 3, 0));
+        assert_eq!(count(&store, "receipts"), 3);
+
+        store.reset().unwrap();
+        assert_eq!(count(&store, "events"), 0);
+        assert_eq!(count(&store, "pools"), 0);
+    }
+}
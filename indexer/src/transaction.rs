@@ -0,0 +1,217 @@
+//! Extraction of the events emitted by a confirmed transaction.
+use anchor_lang::prelude::Pubkey;
+use anyhow::{anyhow, bail, Context, Result};
+use solana_transaction_status::{
+    EncodedConfirmedTransactionWithStatusMeta, EncodedTransaction, UiInstruction,
+    UiLoadedAddresses, UiMessage, UiTransaction,
+};
+
+use crate::events::{event_data, Event};
+
+/// Event as stored by the indexer, the data is kept undecoded so it can be replayed.
+pub struct EventRecord {
+    pub signature: String,
+    /// Position of the event among the events of the transaction
+    pub event_index: u32,
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    /// Discriminator and Borsh serialization of the event
+    pub data: Vec<u8>,
+    pub event: Event,
+}
+
+/// Events emitted by the program in the transaction, in emission order. Failed transactions
+/// emit nothing.
+pub fn extract_events(
+    transaction: &EncodedConfirmedTransactionWithStatusMeta,
+) -> Result<Vec<EventRecord>> {
+    let Some(meta) = &transaction.transaction.meta else {
+        bail!(
+            "transaction at slot {} has no status meta",
+            transaction.slot
+        );
+    };
+    if meta.err.is_some() {
+        return Ok(Vec::new());
+    }
+    let (signature, mut account_keys) = signature_and_keys(&transaction.transaction.transaction)?;
+    let loaded_addresses: Option<&UiLoadedAddresses> = meta.loaded_addresses.as_ref().into();
+    if let Some(loaded_addresses) = loaded_addresses {
+        for key in loaded_addresses
+            .writable
+            .iter()
+            .chain(&loaded_addresses.readonly)
+        {
+            account_keys.push(key.parse().with_context(|| format!("account key {key}"))?);
+        }
+    }
+
+    let inner_instructions: Option<&Vec<_>> = meta.inner_instructions.as_ref().into();
+    let mut events = Vec::new();
+    for instruction in inner_instructions
+        .into_iter()
+        .flatten()
+        .flat_map(|inner| &inner.instructions)
+    {
+        let UiInstruction::Compiled(instruction) = instruction else {
+            bail!("transaction {signature} has parsed inner instructions, fetch it as json");
+        };
+        let program_id = account_keys
+            .get(instruction.program_id_index as usize)
+            .ok_or_else(|| anyhow!("transaction {signature} has an invalid program index"))?;
+        if *program_id != safe_presale::ID {
+            continue;
+        }
+        let instruction_data = bs58::decode(&instruction.data)
+            .into_vec()
+            .with_context(|| format!("inner instruction data of {signature}"))?;
+        let Some(data) = event_data(&instruction_data) else {
+            continue;
+        };
+        let Some(event) = Event::decode(data).with_context(|| format!("event of {signature}"))?
+        else {
+            continue;
+        };
+        events.push(EventRecord {
+            signature: signature.clone(),
+            event_index: events.len() as u32,
+            slot: transaction.slot,
+            block_time: transaction.block_time,
+            data: data.to_vec(),
+            event,
+        });
+    }
+    Ok(events)
+}
+
+fn signature_and_keys(transaction: &EncodedTransaction) -> Result<(String, Vec<Pubkey>)> {
+    if let EncodedTransaction::Json(UiTransaction {
+        signatures,
+        message: UiMessage::Raw(message),
+    }) = transaction
+    {
+        let signature = signatures
+            .first()
+            .ok_or_else(|| anyhow!("transaction without signature"))?;
+        let account_keys = message
+            .account_keys
+            .iter()
+            .map(|key| key.parse().with_context(|| format!("account key {key}")))
+            .collect::<Result<_>>()?;
+        return Ok((signature.clone(), account_keys));
+    }
+    let transaction = transaction
+        .decode()
+        .ok_or_else(|| anyhow!("unsupported transaction encoding, fetch it as json or base64"))?;
+    let signature = transaction
+        .signatures
+        .first()
+        .ok_or_else(|| anyhow!("transaction without signature"))?;
+    Ok((
+        signature.to_string(),
+        transaction.message.static_account_keys().to_vec(),
+    ))
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use anchor_lang::{event::EVENT_IX_TAG_LE, Event as _};
+    use safe_presale::state::{ClosePoolEvent, ProgramPausedEvent};
+    use serde_json::json;
+
+    /// Confirmed transaction in the json encoding of `getTransaction`, emitting the events
+    /// from the first instruction.
+    pub fn transaction_json(
+        signature: &str,
+        slot: u64,
+        events: &[Vec<u8>],
+        err: Option<serde_json::Value>,
+    ) -> serde_json::Value {
+        let payer = Pubkey::new_unique().to_string();
+        let other_program = Pubkey::new_unique().to_string();
+        let mut instructions: Vec<_> = events
+            .iter()
+            .map(|event| {
+                let mut data = EVENT_IX_TAG_LE.to_vec();
+                data.extend(event);
+                json!({
+                    "programIdIndex": 1,
+                    "accounts": [2],
+                    "data": bs58::encode(data).into_string(),
+                    "stackHeight": 2,
+                })
+            })
+            .collect();
+        instructions.insert(
+            0,
+            json!({ "programIdIndex": 3, "accounts": [], "data": "3Bxs", "stackHeight": 2 }),
+        );
+        json!({
+            "slot": slot,
+            "blockTime": 1_700_000_000 + slot,
+            "transaction": {
+                "signatures": [signature],
+                "message": {
+                    "accountKeys": [payer, safe_presale::ID.to_string()],
+                    "header": {
+                        "numRequiredSignatures": 1,
+                        "numReadonlySignedAccounts": 0,
+                        "numReadonlyUnsignedAccounts": 1,
+                    },
+                    "recentBlockhash": "11111111111111111111111111111111",
+                    "instructions": [{ "programIdIndex": 1, "accounts": [0], "data": "" }],
+                },
+            },
+            "meta": {
+                "err": err,
+                "status": { "Ok": null },
+                "fee": 5000,
+                "preBalances": [],
+                "postBalances": [],
+                "innerInstructions": [{ "index": 0, "instructions": instructions }],
+                "loadedAddresses": {
+                    "writable": [anchor_lang::solana_program::sysvar::instructions::ID.to_string()],
+                    "readonly": [other_program],
+                },
+            },
+            "version": 0,
+        })
+    }
+
+    #[test]
+    fn extracts_events_of_the_program() {
+        let close_pool = ClosePoolEvent {
+            payer: Pubkey::new_unique(),
+            pool: Pubkey::new_unique(),
+            event_seq: 4,
+        };
+        let paused = ProgramPausedEvent {
+            admin: Pubkey::new_unique(),
+            paused: false,
+        };
+        let json = transaction_json("sig", 7, &[close_pool.data(), paused.data()], None);
+        let transaction = serde_json::from_value(json).unwrap();
+        let events = extract_events(&transaction).unwrap();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].signature, "sig");
+        assert_eq!(events[0].slot, 7);
+        assert_eq!(events[0].event.name(), "ClosePoolEvent");
+        assert_eq!(events[0].event.pool(), Some((close_pool.pool, 4)));
+        assert_eq!(events[1].event_index, 1);
+        assert_eq!(events[1].event.name(), "ProgramPausedEvent");
+    }
+
+    #[test]
+    fn skips_failed_transactions() {
+        let close_pool = ClosePoolEvent {
+            payer: Pubkey::new_unique(),
+            pool: Pubkey::new_unique(),
+            event_seq: 4,
+        };
+        let err = json!({ "InstructionError": [0, { "Custom": 6000 }] });
+        let json = transaction_json("sig", 7, &[close_pool.data()], Some(err));
+        let transaction = serde_json::from_value(json).unwrap();
+        assert!(extract_events(&transaction).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,66 @@
+//! Account inputs a caller controls: too few remaining accounts for the launch and random
+//! nft metadata account data for a collection gated buy.
+//!
+//! Both are rejected with an error, a panic is a bug.
+#![no_main]
+
+use anchor_lang::{prelude::*, solana_program::system_program};
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+use safe_presale::{
+    error::CustomError,
+    utils::{collection_allowed, CpmmInitializeAccounts},
+};
+
+/// Accounts read by `CpmmInitializeAccounts`.
+const CPMM_INITIALIZE_ACCOUNTS: usize = 10;
+
+#[derive(Arbitrary, Debug)]
+struct Input {
+    remaining_accounts: u8,
+    metadata_data: Vec<u8>,
+    mint: [u8; 32],
+    collection_mint: [u8; 32],
+}
+
+fuzz_target!(|input: Input| {
+    let count = usize::from(input.remaining_accounts % 16);
+    let keys: Vec<Pubkey> = (0..count).map(|_| Pubkey::new_unique()).collect();
+    let mut lamports = vec![0u64; count];
+    let mut data = vec![vec![]; count];
+    let infos: Vec<AccountInfo> = keys
+        .iter()
+        .zip(lamports.iter_mut())
+        .zip(data.iter_mut())
+        .map(|((key, lamports), data)| {
+            AccountInfo::new(
+                key,
+                false,
+                true,
+                lamports,
+                data,
+                &system_program::ID,
+                false,
+                0,
+            )
+        })
+        .collect();
+    match CpmmInitializeAccounts::from_remaining_accounts(&infos) {
+        Ok(cpmm_accounts) => {
+            assert!(count >= CPMM_INITIALIZE_ACCOUNTS);
+            assert_eq!(cpmm_accounts.observation_state.key(), keys[9]);
+        }
+        Err(err) => {
+            assert!(count < CPMM_INITIALIZE_ACCOUNTS);
+            assert_eq!(err, error!(CustomError::InvalidRemainingAccounts));
+        }
+    }
+
+    if let Err(err) = collection_allowed(
+        &input.metadata_data,
+        &Pubkey::new_from_array(input.mint),
+        &Pubkey::new_from_array(input.collection_mint),
+    ) {
+        assert_eq!(err, error!(CustomError::InvalidMintMetadata));
+    }
+});
@@ -0,0 +1,326 @@
+//! Random pool configurations driven through random sequences of buys, withdrawals and
+//! claims, with the arithmetic of the program.
+//!
+//! Every step either succeeds or fails with an error, a panic is a bug. Contributors are
+//! never allocated more than the supplies, and each allocation is within one unit of the
+//! exact pro rata amount.
+#![no_main]
+
+use arbitrary::Arbitrary;
+use bytemuck::Zeroable;
+use libfuzzer_sys::fuzz_target;
+use safe_presale::{
+    state::{Pool, PoolStatus},
+    utils::{Calculator, VestingSchedule},
+};
+
+/// Contributors the actions are spread over.
+const CONTRIBUTORS: usize = 8;
+/// The presale opens at 0 and ends at `PRESALE_TIME_LIMIT`.
+const PRESALE_TIME_LIMIT: i64 = 3600;
+
+#[derive(Arbitrary, Debug)]
+struct PoolConfig {
+    decimals: u8,
+    liquidity_pool_supply: u64,
+    initial_supply: u64,
+    creator_fee_basis_points: u16,
+    presale_target: u64,
+    max_amount_per_purchase: Option<u64>,
+    vesting_period: u32,
+    reward_vesting_period: u32,
+}
+
+#[derive(Arbitrary, Debug)]
+enum Action {
+    Buy {
+        contributor: u8,
+        amount: u64,
+    },
+    /// Refund of a failed presale, moves the clock to the end of the presale
+    Withdraw {
+        contributor: u8,
+    },
+    /// Check and claim of both allocations `elapsed` seconds into the vesting, the pool is
+    /// launched first when it is filled
+    Claim {
+        contributor: u8,
+        elapsed: u32,
+    },
+}
+
+#[derive(Arbitrary, Debug)]
+struct Input {
+    config: PoolConfig,
+    actions: Vec<Action>,
+}
+
+#[derive(Clone, Copy, Default)]
+struct Receipt {
+    amount: u64,
+    lp_elligible: Option<u64>,
+    mint_elligible: Option<u64>,
+    lp_claimed: u64,
+    mint_claimed: u64,
+    refunded: bool,
+}
+
+/// The pool and its receipts, each instruction is applied to copies and only kept when it
+/// succeeds, like a transaction.
+struct Presale {
+    pool: Pool,
+    receipts: [Receipt; CONTRIBUTORS],
+    now: i64,
+    refunded: u64,
+}
+
+impl Presale {
+    /// Same validation and supplies as `init_pool`, `None` when it fails.
+    fn init(config: &PoolConfig) -> Option<Self> {
+        if config.creator_fee_basis_points > 10000 {
+            return None;
+        }
+        let mut pool = Pool::zeroed();
+        pool.liquidity_pool_supply =
+            Calculator::to_base_units(config.liquidity_pool_supply, config.decimals).ok()?;
+        pool.initial_supply =
+            Calculator::to_base_units(config.initial_supply, config.decimals).ok()?;
+        pool.initial_supply_for_creator =
+            Calculator::basis_points_of(pool.initial_supply, config.creator_fee_basis_points)
+                .ok()?;
+        // Both supplies are minted to the pool
+        pool.liquidity_pool_supply
+            .checked_add(pool.initial_supply)?;
+        pool.creator_fee_basis_points = config.creator_fee_basis_points;
+        pool.presale_target = config.presale_target;
+        pool.presale_time_limit = PRESALE_TIME_LIMIT;
+        pool.max_amount_per_purchase = config.max_amount_per_purchase.into();
+        pool.vesting_period = config.vesting_period;
+        pool.reward_vesting_period = config.reward_vesting_period;
+        pool.status = PoolStatus::Upcoming as u8;
+        Some(Presale {
+            pool,
+            receipts: [Receipt::default(); CONTRIBUTORS],
+            now: 0,
+            refunded: 0,
+        })
+    }
+
+    fn buy(&mut self, contributor: usize, amount: u64) -> Option<()> {
+        if amount == 0 || !self.pool.status_at(self.now).accepts_purchases() {
+            return None;
+        }
+        let liquidity_collected = self.pool.liquidity_collected.checked_add(amount)?;
+        if liquidity_collected > self.pool.presale_target {
+            return None;
+        }
+        let receipt_amount = self.receipts[contributor].amount.checked_add(amount)?;
+        if let Some(max_amount_per_purchase) = self.pool.max_amount_per_purchase.get() {
+            if receipt_amount > max_amount_per_purchase {
+                return None;
+            }
+        }
+        self.pool.liquidity_collected = liquidity_collected;
+        self.receipts[contributor].amount = receipt_amount;
+        Some(())
+    }
+
+    fn withdraw(&mut self, contributor: usize) -> Option<()> {
+        self.now = self.now.max(PRESALE_TIME_LIMIT);
+        self.pool.check_refundable(self.now).ok()?;
+        let receipt = self.receipts[contributor];
+        if receipt.amount == 0 || receipt.refunded {
+            return None;
+        }
+        self.refunded = self.refunded.checked_add(receipt.amount)?;
+        self.receipts[contributor].refunded = true;
+        Some(())
+    }
+
+    /// Same accounting as `launch_token_amm`, the CPMM receives both supplies.
+    fn launch(&mut self) -> Option<()> {
+        self.now = self.now.max(PRESALE_TIME_LIMIT);
+        if self.pool.status_at(self.now) != PoolStatus::Filled {
+            return None;
+        }
+        let lp_mint_supply = Calculator::lp_tokens_received(
+            self.pool.liquidity_pool_supply,
+            self.pool.liquidity_collected,
+        )
+        .ok()?;
+        let lp_mint_supply_for_creator =
+            Calculator::basis_points_of(lp_mint_supply, self.pool.creator_fee_basis_points).ok()?;
+        self.pool.vesting_started_at = Some(self.now).into();
+        self.pool.lp_mint_supply = Some(lp_mint_supply).into();
+        self.pool.lp_mint_supply_for_creator = Some(lp_mint_supply_for_creator).into();
+        Some(())
+    }
+
+    /// `check_claim_ellgibility` when the receipt is not checked yet, then
+    /// `withdraw_lp_tokens` and `claim_reward_token`.
+    fn claim(&mut self, contributor: usize, elapsed: u32) -> Option<()> {
+        if self.pool.vesting_started_at.get().is_none() {
+            self.launch()?;
+        }
+        let vesting_started_at = self.pool.vesting_started_at.get()?;
+        self.now = self
+            .now
+            .max(vesting_started_at.checked_add(elapsed.into())?);
+        let mut pool = self.pool;
+        let mut receipt = self.receipts[contributor];
+        if receipt.amount == 0 {
+            return None;
+        }
+        if receipt.lp_elligible.is_none() {
+            let lp_elligible = pool.lp_entitlement(receipt.amount).ok()?;
+            let mint_elligible = pool.mint_entitlement(receipt.amount).ok()?;
+            pool.record_checked_receipt(receipt.amount, lp_elligible, mint_elligible)
+                .ok()?;
+            receipt.lp_elligible = Some(lp_elligible);
+            receipt.mint_elligible = Some(mint_elligible);
+        }
+        let lp_claimable = VestingSchedule::new(
+            vesting_started_at,
+            pool.vesting_period,
+            receipt.lp_elligible?,
+        )
+        .claimable(self.now, receipt.lp_claimed)
+        .ok()?;
+        let mint_claimable = VestingSchedule::new(
+            vesting_started_at,
+            pool.reward_vesting_period,
+            receipt.mint_elligible?,
+        )
+        .claimable(self.now, receipt.mint_claimed)
+        .ok()?;
+        receipt.lp_claimed = receipt.lp_claimed.checked_add(lp_claimable)?;
+        receipt.mint_claimed = receipt.mint_claimed.checked_add(mint_claimable)?;
+        self.pool = pool;
+        self.receipts[contributor] = receipt;
+        Some(())
+    }
+
+    /// Lp and reward tokens split between the contributors, once launched.
+    fn contributor_supplies(&self) -> Option<(u64, u64)> {
+        let lp_mint_supply = self.pool.lp_mint_supply.get()?;
+        let lp_mint_supply_for_creator = self.pool.lp_mint_supply_for_creator.get().unwrap();
+        assert!(lp_mint_supply_for_creator <= lp_mint_supply);
+        Some((
+            lp_mint_supply - lp_mint_supply_for_creator,
+            self.pool.initial_supply - self.pool.initial_supply_for_creator,
+        ))
+    }
+
+    fn check_invariants(&self) {
+        let pool = &self.pool;
+        assert!(pool.liquidity_collected <= pool.presale_target);
+        assert!(pool.initial_supply_for_creator <= pool.initial_supply);
+        assert!(self.refunded <= pool.liquidity_collected);
+        let Some((lp_for_contributors, mint_for_contributors)) = self.contributor_supplies() else {
+            assert!(self
+                .receipts
+                .iter()
+                .all(|receipt| receipt.lp_elligible.is_none()));
+            return;
+        };
+        assert!(pool.lp_mint_distributed <= lp_for_contributors);
+        assert!(pool.initial_supply_distributed <= mint_for_contributors);
+
+        let (mut lp_elligible_total, mut mint_elligible_total) = (0u128, 0u128);
+        let (mut lp_unchecked_total, mut mint_unchecked_total) = (0u128, 0u128);
+        for receipt in &self.receipts {
+            let (Some(lp_elligible), Some(mint_elligible)) =
+                (receipt.lp_elligible, receipt.mint_elligible)
+            else {
+                lp_unchecked_total += u128::from(pool.lp_entitlement(receipt.amount).unwrap());
+                mint_unchecked_total += u128::from(pool.mint_entitlement(receipt.amount).unwrap());
+                continue;
+            };
+            assert_pro_rata(
+                lp_elligible,
+                receipt.amount,
+                lp_for_contributors,
+                pool.liquidity_collected,
+            );
+            assert_pro_rata(
+                mint_elligible,
+                receipt.amount,
+                mint_for_contributors,
+                pool.liquidity_collected,
+            );
+            assert!(receipt.lp_claimed <= lp_elligible);
+            assert!(receipt.mint_claimed <= mint_elligible);
+            lp_elligible_total += u128::from(lp_elligible);
+            mint_elligible_total += u128::from(mint_elligible);
+        }
+        assert_eq!(lp_elligible_total, u128::from(pool.lp_mint_distributed));
+        assert_eq!(
+            mint_elligible_total,
+            u128::from(pool.initial_supply_distributed)
+        );
+
+        // The sweep never takes what unchecked receipts are still owed
+        let (lp_dust, mint_dust) = pool.dust().unwrap();
+        assert!(
+            lp_elligible_total + lp_unchecked_total + u128::from(lp_dust)
+                <= u128::from(lp_for_contributors)
+        );
+        assert!(
+            mint_elligible_total + mint_unchecked_total + u128::from(mint_dust)
+                <= u128::from(mint_for_contributors)
+        );
+    }
+}
+
+/// `share` of `supply` for `amount` out of `liquidity_collected` is the exact pro rata
+/// amount rounded down, so it is never above it and less than one unit below it.
+fn assert_pro_rata(share: u64, amount: u64, supply: u64, liquidity_collected: u64) {
+    let exact = u128::from(amount) * u128::from(supply);
+    let liquidity_collected = u128::from(liquidity_collected);
+    assert!(u128::from(share) * liquidity_collected <= exact);
+    assert!(exact < (u128::from(share) + 1) * liquidity_collected);
+}
+
+fuzz_target!(|input: Input| {
+    let Some(mut presale) = Presale::init(&input.config) else {
+        return;
+    };
+    for action in &input.actions {
+        match *action {
+            Action::Buy {
+                contributor,
+                amount,
+            } => presale.buy(usize::from(contributor) % CONTRIBUTORS, amount),
+            Action::Withdraw { contributor } => {
+                presale.withdraw(usize::from(contributor) % CONTRIBUTORS)
+            }
+            Action::Claim {
+                contributor,
+                elapsed,
+            } => presale.claim(usize::from(contributor) % CONTRIBUTORS, elapsed),
+        };
+        presale.check_invariants();
+    }
+
+    // Settle the presale: refund every contributor or claim everything once fully vested
+    for contributor in 0..CONTRIBUTORS {
+        presale.withdraw(contributor);
+        presale.claim(contributor, u32::MAX);
+    }
+    presale.check_invariants();
+
+    let receipts = presale.receipts.iter().filter(|receipt| receipt.amount > 0);
+    if let Some((lp_for_contributors, mint_for_contributors)) = presale.contributor_supplies() {
+        for receipt in receipts.clone() {
+            assert_eq!(receipt.lp_elligible, Some(receipt.lp_claimed));
+            assert_eq!(receipt.mint_elligible, Some(receipt.mint_claimed));
+        }
+        // Every contributor rounds down by less than one unit
+        let contributors = receipts.count() as u64;
+        assert!(lp_for_contributors - presale.pool.lp_mint_distributed < contributors);
+        assert!(mint_for_contributors - presale.pool.initial_supply_distributed < contributors);
+    } else if presale.pool.check_refundable(presale.now).is_ok() {
+        assert!(receipts.clone().all(|receipt| receipt.refunded));
+        assert_eq!(presale.refunded, presale.pool.liquidity_collected);
+    }
+});
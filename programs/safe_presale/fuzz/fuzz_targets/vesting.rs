@@ -0,0 +1,45 @@
+//! Random vesting schedules claimed at random times.
+//!
+//! The vested amount only grows, stays within one unit of the exact linear amount and the
+//! claims add up to the total once the schedule ends.
+#![no_main]
+
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+use safe_presale::utils::VestingSchedule;
+
+#[derive(Arbitrary, Debug)]
+struct Input {
+    start: i64,
+    duration: u32,
+    total: u64,
+    claims: Vec<i64>,
+}
+
+fuzz_target!(|input: Input| {
+    let schedule = VestingSchedule::new(input.start, input.duration, input.total);
+    let Ok(end) = schedule.end() else {
+        return;
+    };
+    let mut times = input.claims;
+    times.push(end);
+    times.sort_unstable();
+
+    let (mut vested_before, mut claimed) = (0u64, 0u64);
+    for now in times {
+        let vested = schedule.vested(now).unwrap();
+        assert!(vested_before <= vested && vested <= schedule.total);
+        if schedule.start < now && now < end {
+            // Rounded down from elapsed * total / duration
+            let elapsed = u128::try_from(now - schedule.start).unwrap();
+            let exact = elapsed * u128::from(schedule.total);
+            let duration = u128::from(schedule.duration);
+            assert!(u128::from(vested) * duration <= exact);
+            assert!(exact < (u128::from(vested) + 1) * duration);
+        }
+        claimed += schedule.claimable(now, claimed).unwrap();
+        assert_eq!(claimed, vested);
+        vested_before = vested;
+    }
+    assert_eq!(claimed, schedule.total);
+});
@@ -58,4 +58,80 @@ pub enum CustomError {
     PurchaseAuthorisationRecordMissing,
     #[msg("Collection is not authorised")]
     UnauthorisedCollection,
+    #[msg("Dissolve quorum basis points cannot exceed 10000")]
+    DissolveQuorumExceedMaximumAmount,
+    #[msg("Dissolve vote window cannot be zero when a quorum is set")]
+    InvalidDissolveVoteWindow,
+    #[msg("Dissolve vote is not enabled for this pool")]
+    DissolveVoteDisabled,
+    #[msg("Already voted in the current dissolve round")]
+    AlreadyVoted,
+    #[msg("Pool has been dissolved by contributors")]
+    PoolDissolved,
+    #[msg("Pool has not been dissolved")]
+    PoolNotDissolved,
+    #[msg("No authority transfer is pending for this signer")]
+    AuthorityTransferNotPending,
+    #[msg("Program is paused")]
+    ProgramPaused,
+    #[msg("Pool is frozen")]
+    PoolFrozen,
+    #[msg("Signer must be the program upgrade authority")]
+    InvalidUpgradeAuthority,
+    #[msg("Pool can only be updated while the presale is open and before the first purchase")]
+    PoolHasPurchases,
+    #[msg("Token account balance must be zero")]
+    TokenAccountNotEmpty,
+    #[msg("Entitlements must be fully claimed")]
+    EntitlementNotFullyClaimed,
+    #[msg("Pool still has open purchase receipts")]
+    PoolHasOpenReceipts,
+    #[msg("Pool is still active")]
+    PoolIsStillActive,
+    #[msg("Every purchase receipt must be checked first, or the claim deadline must have passed")]
+    ReceiptsNotChecked,
+    #[msg("Dust has already been swept")]
+    DustAlreadySwept,
+    #[msg("Destination token accounts are required unless the dust is burned")]
+    MissingDustDestination,
+    #[msg("Reward supply has already been burned")]
+    RewardSupplyBurned,
+    #[msg("Claim period cannot be zero")]
+    InvalidClaimPeriod,
+    #[msg("Claim deadline has passed")]
+    ClaimDeadlinePassed,
+    #[msg("Claim deadline has not been reached")]
+    ClaimDeadlineNotReached,
+    #[msg("Purchase receipt has been reclaimed")]
+    ReceiptReclaimed,
+    #[msg("Claim token accounts are required to close a checked receipt")]
+    MissingClaimTokenAccounts,
+    #[msg("Purchase receipt is claimed directly from the pool, use claim_all")]
+    DirectClaimReceipt,
+    #[msg("Purchase receipt was checked into escrow, use the escrow claim instructions")]
+    EscrowedClaimReceipt,
+    #[msg("Remaining accounts must be complete groups of purchase receipt accounts")]
+    InvalidRemainingAccounts,
+    #[msg("Purchase receipt is repeated in the batch")]
+    DuplicatePurchaseReceipt,
+    #[msg("Escrow seed exceeds the maximum length")]
+    InvalidEscrowSeed,
+    #[msg("Claim approval has expired")]
+    ClaimApprovalExpired,
+    #[msg("Claim approval does not cover this purchase receipt")]
+    ClaimApprovalScopeMismatch,
+    #[msg("Purchase receipt was already claimed through a claim approval at this time")]
+    ApprovedClaimRepeated,
+    #[msg("Pool was migrated from the unversioned layout, its receipts are not all tracked")]
+    ReceiptsNotTracked,
+    #[msg("Account does not have a layout that can be migrated")]
+    InvalidAccountLayout,
+    #[msg("Account already uses the current layout")]
+    AccountAlreadyMigrated,
+    #[msg("Liquidity is too low to launch the pool")]
+    InsufficientLiquidity,
+    #[msg("Launch payer is required to close the lp token account")]
+    MissingLaunchPayer,
+    #[msg("Batch instructions must be signed by the nft owner, delegates claim one by one")]
+    BatchRequiresNftOwner,
 }
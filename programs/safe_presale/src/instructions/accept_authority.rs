@@ -0,0 +1,40 @@
+use crate::error::CustomError;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct AcceptAuthorityCtx<'info> {
+    #[account(
+        mut,
+        constraint = pool.load()?.pending_authority.get() == Some(new_authority.key()) @CustomError::AuthorityTransferNotPending,
+        constraint = !pool.load()?.frozen.get() @CustomError::PoolFrozen,
+    )]
+    pub pool: AccountLoader<'info, Pool>,
+
+    #[account(
+        seeds = [CONFIG_PREFIX.as_bytes()],
+        bump = config.bump,
+        constraint = !config.paused @CustomError::ProgramPaused,
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    /// Can be a PDA of another program (e.g. a multisig vault) signing through CPI.
+    pub new_authority: Signer<'info>,
+}
+
+pub(crate) fn handler(ctx: Context<AcceptAuthorityCtx>) -> Result<()> {
+    let mut pool = ctx.accounts.pool.load_mut()?;
+    let previous_authority = pool.authority;
+    pool.authority = ctx.accounts.new_authority.key();
+    pool.pending_authority = None.into();
+
+    emit_cpi!(AuthorityTransferredEvent {
+        previous_authority,
+        authority: pool.authority,
+        pool: ctx.accounts.pool.key(),
+        event_seq: pool.next_event_seq(),
+    });
+
+    Ok(())
+}
@@ -0,0 +1,65 @@
+use crate::error::CustomError;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(staking_program:Pubkey)]
+pub struct AddStakingProgramCtx<'info> {
+    #[account(
+        init,
+        space = STAKING_PROGRAM_RECORD_SIZE,
+        payer = authority,
+        seeds = [STAKING_PROGRAM_PREFIX.as_bytes(), pool.key().as_ref(), staking_program.as_ref()],
+        bump,
+    )]
+    pub staking_program_record: Box<Account<'info, StakingProgramRecord>>,
+
+    #[account(
+        mut,
+        constraint = pool.load()?.authority == authority.key() @CustomError::InvalidSigner,
+        constraint = !pool.load()?.frozen.get() @CustomError::PoolFrozen,
+    )]
+    pub pool: AccountLoader<'info, Pool>,
+
+    #[account(
+        seeds = [CONFIG_PREFIX.as_bytes()],
+        bump = config.bump,
+        constraint = !config.paused @CustomError::ProgramPaused,
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub(crate) fn handler(
+    ctx: Context<AddStakingProgramCtx>,
+    staking_program: Pubkey,
+    escrow_seed: Option<Vec<u8>>,
+) -> Result<()> {
+    if let Some(escrow_seed) = &escrow_seed {
+        require!(
+            !escrow_seed.is_empty() && escrow_seed.len() <= MAX_ESCROW_SEED_LEN,
+            CustomError::InvalidEscrowSeed
+        );
+    }
+
+    let staking_program_record = &mut ctx.accounts.staking_program_record;
+    staking_program_record.bump = ctx.bumps.staking_program_record;
+    staking_program_record.pool = ctx.accounts.pool.key();
+    staking_program_record.staking_program = staking_program;
+    staking_program_record.escrow_seed = escrow_seed.clone();
+
+    emit_cpi!(StakingProgramAddedEvent {
+        authority: ctx.accounts.authority.key(),
+        pool: ctx.accounts.pool.key(),
+        staking_program,
+        escrow_seed,
+        event_seq: ctx.accounts.pool.load_mut()?.next_event_seq(),
+    });
+
+    Ok(())
+}
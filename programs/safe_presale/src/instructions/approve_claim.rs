@@ -0,0 +1,71 @@
+use crate::error::CustomError;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct ApproveClaimArgs {
+    pub purchase_receipt: Option<Pubkey>,
+    pub expires_at: Option<i64>,
+    /// Most lp tokens the delegate can claim at once, unlimited when `None`
+    pub max_lp_per_claim: Option<u64>,
+    /// Most reward tokens the delegate can claim at once, unlimited when `None`
+    pub max_mint_per_claim: Option<u64>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(args: ApproveClaimArgs)]
+pub struct ApproveClaimCtx<'info> {
+    /// Changing an approval means revoking it first
+    #[account(
+        init,
+        payer = owner,
+        space = CLAIM_APPROVAL_SIZE,
+        seeds = [CLAIM_APPROVAL_PREFIX.as_bytes(), owner.key().as_ref(), delegate.key().as_ref(), ClaimApproval::scope_seed(&args.purchase_receipt)],
+        bump,
+    )]
+    pub claim_approval: Box<Account<'info, ClaimApproval>>,
+
+    ///CHECK: Only used as the approved key
+    pub delegate: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub(crate) fn handler(ctx: Context<ApproveClaimCtx>, args: ApproveClaimArgs) -> Result<()> {
+    require!(
+        ctx.accounts.delegate.key() != ctx.accounts.owner.key(),
+        CustomError::InvalidSigner
+    );
+    require!(
+        args.max_lp_per_claim != Some(0),
+        CustomError::NumberCannotBeZero
+    );
+    require!(
+        args.max_mint_per_claim != Some(0),
+        CustomError::NumberCannotBeZero
+    );
+
+    let claim_approval = &mut ctx.accounts.claim_approval;
+    claim_approval.bump = ctx.bumps.claim_approval;
+    claim_approval.owner = ctx.accounts.owner.key();
+    claim_approval.delegate = ctx.accounts.delegate.key();
+    claim_approval.purchase_receipt = args.purchase_receipt;
+    claim_approval.expires_at = args.expires_at;
+    claim_approval.max_lp_per_claim = args.max_lp_per_claim;
+    claim_approval.max_mint_per_claim = args.max_mint_per_claim;
+
+    emit_cpi!(ClaimApprovalUpdatedEvent {
+        owner: claim_approval.owner,
+        delegate: claim_approval.delegate,
+        purchase_receipt: claim_approval.purchase_receipt,
+        expires_at: claim_approval.expires_at,
+        max_lp_per_claim: claim_approval.max_lp_per_claim,
+        max_mint_per_claim: claim_approval.max_mint_per_claim,
+    });
+
+    Ok(())
+}
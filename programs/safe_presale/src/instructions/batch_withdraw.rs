@@ -0,0 +1,123 @@
+use crate::error::CustomError;
+use crate::state::*;
+use crate::utils::purchase_receipt_refunds;
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+/// Remaining accounts: `(purchase_receipt, nft_owner_nft_token_account, receipt_payer)` triples,
+/// every receipt's rent goes back to whoever paid for it.
+///
+/// Only the nft owner can sign a batch, with every nft in their wallet. Staking programs act
+/// through `withdraw` instead, one receipt at a time.
+#[event_cpi]
+#[derive(Accounts)]
+pub struct BatchWithdrawCtx<'info> {
+    #[account(mut)]
+    pub pool: AccountLoader<'info, Pool>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = quote_mint,
+        associated_token::authority = nft_owner,
+    )]
+    pub nft_owner_quote_mint_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = pool_quote_mint_token_account.owner == pool.key(),
+        constraint = pool_quote_mint_token_account.mint == quote_mint.key(),
+    )]
+    pub pool_quote_mint_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        constraint = quote_mint.key() == pool.load()?.quote_mint,
+    )]
+    pub quote_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    ///CHECK: Contraint is checked by other accounts
+    pub nft_owner: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+pub(crate) fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, BatchWithdrawCtx<'info>>,
+) -> Result<()> {
+    if ctx.accounts.nft_owner.key() != ctx.accounts.payer.key() {
+        return Err(error!(CustomError::BatchRequiresNftOwner));
+    }
+
+    let pool_key = ctx.accounts.pool.key();
+    let mut pool = ctx.accounts.pool.load_mut()?;
+    // Withdrawal criteria, see Pool::check_refundable
+    let current_time = Clock::get()?.unix_timestamp;
+    pool.check_refundable(current_time)?;
+    if let Some(status_changed) = pool.transition(pool_key, current_time) {
+        emit_cpi!(status_changed);
+    }
+
+    let purchase_receipts = purchase_receipt_refunds(
+        ctx.remaining_accounts,
+        &pool_key,
+        &ctx.accounts.nft_owner.key(),
+    )?;
+    let mut amount_withdrawn = 0u64;
+    let mut original_mints = Vec::with_capacity(purchase_receipts.len());
+    for (purchase_receipt, receipt_payer) in purchase_receipts {
+        amount_withdrawn = amount_withdrawn
+            .checked_add(purchase_receipt.amount)
+            .ok_or(CustomError::IntegerOverflow)?;
+        original_mints.push(purchase_receipt.original_mint);
+        purchase_receipt.close(receipt_payer.clone())?;
+        pool.purchase_receipt_count = pool
+            .purchase_receipt_count
+            .checked_sub(1)
+            .ok_or(CustomError::IntegerOverflow)?;
+    }
+
+    let batch_withdraw_event = BatchWithdrawEvent {
+        payer: ctx.accounts.payer.key(),
+        pool: pool_key,
+        amount_withdrawn,
+        original_mints,
+        original_mint_owner: ctx.accounts.nft_owner.key(),
+        purchase_receipts_closed: true,
+        purchase_receipt_count: pool.purchase_receipt_count,
+        event_seq: pool.next_event_seq(),
+    };
+    let (pool_mint, pool_bump) = (pool.mint, pool.bump);
+    drop(pool);
+    let pool_seed = &[POOL_PREFIX.as_bytes(), pool_mint.as_ref(), &[pool_bump]];
+    let signer = &[&pool_seed[..]];
+
+    transfer_checked(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                mint: ctx.accounts.quote_mint.to_account_info(),
+                from: ctx.accounts.pool_quote_mint_token_account.to_account_info(),
+                to: ctx
+                    .accounts
+                    .nft_owner_quote_mint_token_account
+                    .to_account_info(),
+                authority: ctx.accounts.pool.to_account_info(),
+            },
+        )
+        .with_signer(signer),
+        amount_withdrawn,
+        ctx.accounts.quote_mint.decimals,
+    )?;
+
+    emit_cpi!(batch_withdraw_event);
+
+    Ok(())
+}
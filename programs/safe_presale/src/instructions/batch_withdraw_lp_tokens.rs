@@ -0,0 +1,145 @@
+use crate::error::CustomError;
+use crate::state::*;
+use crate::utils::{purchase_receipt_pairs, VestingSchedule};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+/// Remaining accounts: `(purchase_receipt, nft_owner_nft_token_account)` pairs.
+///
+/// Every receipt is paid from the pool vault, receipts escrowed by `check_claim_ellgibility`
+/// are rejected and have to be claimed one by one with `withdraw_lp_tokens`.
+///
+/// Only the nft owner can sign a batch, with every nft in their wallet. Staking programs and
+/// claim approval delegates act through `withdraw_lp_tokens` instead, one receipt at a time.
+#[event_cpi]
+#[derive(Accounts)]
+pub struct BatchWithdrawLpCtx<'info> {
+    #[account(
+        mut,
+        constraint = pool.load()?.current_status()?.has_launched() @CustomError::PresaleIsStillOngoing,
+    )]
+    pub pool: AccountLoader<'info, Pool>,
+
+    #[account(
+        mut,
+        constraint = pool_lp_token_account.owner == pool.key(),
+        constraint = pool_lp_token_account.mint == lp_mint.key(),
+    )]
+    pub pool_lp_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = lp_mint,
+        associated_token::authority = nft_owner,
+    )]
+    pub nft_owner_lp_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    ///CHECK: Contraint is checked by other accounts
+    pub nft_owner: AccountInfo<'info>,
+
+    #[account(
+        constraint = pool.load()?.lp_mint.get() == Some(lp_mint.key()) @CustomError::InvalidLpMint
+    )]
+    pub lp_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        seeds = [CONFIG_PREFIX.as_bytes()],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+pub(crate) fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, BatchWithdrawLpCtx<'info>>,
+) -> Result<()> {
+    if ctx.accounts.nft_owner.key() != ctx.accounts.payer.key() {
+        return Err(error!(CustomError::BatchRequiresNftOwner));
+    }
+
+    let pool_key = ctx.accounts.pool.key();
+    let mut pool = ctx.accounts.pool.load_mut()?;
+    let current_time = Clock::get()?.unix_timestamp;
+    let purchase_receipts = purchase_receipt_pairs(
+        ctx.remaining_accounts,
+        &pool_key,
+        &ctx.accounts.nft_owner.key(),
+    )?;
+    // Batch Withdraw Lp Criteria
+    // 1. Every receipt is settled like claim_all, escrowed receipts are rejected.
+    // 2. The vested amounts are summed and paid from the pool vault with a single transfer.
+    let mut lp_claimable = 0u64;
+    let mut original_mints = Vec::with_capacity(purchase_receipts.len());
+    let mut total_lp_claimed = Vec::with_capacity(purchase_receipts.len());
+    for mut purchase_receipt in purchase_receipts {
+        purchase_receipt.settle_direct_claim(&mut pool, &ctx.accounts.config, current_time)?;
+        let claimable = VestingSchedule::new(
+            pool.vesting_started_at.get().unwrap(),
+            pool.vesting_period,
+            purchase_receipt.lp_elligible.unwrap(),
+        )
+        .claimable(current_time, purchase_receipt.lp_claimed)?;
+        purchase_receipt.lp_claimed = purchase_receipt
+            .lp_claimed
+            .checked_add(claimable)
+            .ok_or(CustomError::IntegerOverflow)?;
+        purchase_receipt.last_claimed_at = Some(current_time);
+        purchase_receipt.exit(&crate::ID)?;
+        lp_claimable = lp_claimable
+            .checked_add(claimable)
+            .ok_or(CustomError::IntegerOverflow)?;
+        original_mints.push(purchase_receipt.original_mint);
+        total_lp_claimed.push(purchase_receipt.lp_claimed);
+    }
+    if lp_claimable == 0 {
+        return Err(error!(CustomError::MaximumAmountClaimed));
+    }
+
+    let batch_withdraw_event = BatchWithdrawLpTokenEvent {
+        payer: ctx.accounts.payer.key(),
+        pool: pool_key,
+        lp_claimed: lp_claimable,
+        last_claimed_at: current_time,
+        original_mints,
+        original_mint_owner: ctx.accounts.nft_owner.key(),
+        total_lp_claimed,
+        lp_mint_distributed: pool.lp_mint_distributed,
+        initial_supply_distributed: pool.initial_supply_distributed,
+        purchase_receipts_checked: pool.purchase_receipts_checked,
+        event_seq: pool.next_event_seq(),
+    };
+    let (pool_mint, pool_bump) = (pool.mint, pool.bump);
+    drop(pool);
+    let pool_seed = &[POOL_PREFIX.as_bytes(), pool_mint.as_ref(), &[pool_bump]];
+    let signer = &[&pool_seed[..]];
+
+    //transfer to nft owner from the pool vault
+    transfer_checked(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                mint: ctx.accounts.lp_mint.to_account_info(),
+                from: ctx.accounts.pool_lp_token_account.to_account_info(),
+                to: ctx.accounts.nft_owner_lp_token_account.to_account_info(),
+                authority: ctx.accounts.pool.to_account_info(),
+            },
+        )
+        .with_signer(signer),
+        lp_claimable,
+        ctx.accounts.lp_mint.decimals,
+    )?;
+
+    emit_cpi!(batch_withdraw_event);
+
+    Ok(())
+}
@@ -0,0 +1,85 @@
+use crate::error::CustomError;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{burn, Burn, Mint, TokenAccount, TokenInterface};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct BurnRewardSupplyCtx<'info> {
+    #[account(
+        mut,
+        constraint = pool.load()?.reward_supply_burned_at.is_none() @CustomError::RewardSupplyBurned,
+        constraint = !pool.load()?.frozen.get() @CustomError::PoolFrozen,
+    )]
+    pub pool: AccountLoader<'info, Pool>,
+
+    #[account(
+        seeds = [CONFIG_PREFIX.as_bytes()],
+        bump = config.bump,
+        constraint = !config.paused @CustomError::ProgramPaused,
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(
+        mut,
+        constraint = pool_reward_token_account.owner == pool.key(),
+        constraint = pool_reward_token_account.mint == reward_mint.key(),
+    )]
+    pub pool_reward_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = reward_mint.key() == pool.load()?.mint @CustomError::InvalidRewardMint,
+    )]
+    pub reward_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    pub payer: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+pub(crate) fn handler(ctx: Context<BurnRewardSupplyCtx>) -> Result<()> {
+    let pool_key = ctx.accounts.pool.key();
+    let mut pool = ctx.accounts.pool.load_mut()?;
+    // Burn Criteria
+    // 1. The presale has ended without reaching its target (Failed), or
+    // 2. The target was reached but the creator failed to launch within the grace period (Expired).
+    // Either way the pool can never launch again, so the minted supply is burned and the pool is terminal.
+    let current_time = Clock::get()?.unix_timestamp;
+    pool.check_refundable(current_time)?;
+    if let Some(status_changed) = pool.transition(pool_key, current_time) {
+        emit_cpi!(status_changed);
+    }
+
+    let amount_burned = ctx.accounts.pool_reward_token_account.amount;
+    pool.reward_supply_burned_at = Some(current_time).into();
+
+    let burn_event = BurnRewardSupplyEvent {
+        payer: ctx.accounts.payer.key(),
+        pool: pool_key,
+        amount_burned,
+        burned_at: current_time,
+        event_seq: pool.next_event_seq(),
+    };
+    let (pool_mint, pool_bump) = (pool.mint, pool.bump);
+    drop(pool);
+    let pool_seed = &[POOL_PREFIX.as_bytes(), pool_mint.as_ref(), &[pool_bump]];
+    let signer = &[&pool_seed[..]];
+
+    burn(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Burn {
+                mint: ctx.accounts.reward_mint.to_account_info(),
+                from: ctx.accounts.pool_reward_token_account.to_account_info(),
+                authority: ctx.accounts.pool.to_account_info(),
+            },
+        )
+        .with_signer(signer),
+        amount_burned,
+    )?;
+
+    emit_cpi!(burn_event);
+
+    Ok(())
+}
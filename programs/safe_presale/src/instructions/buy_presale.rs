@@ -1,11 +1,14 @@
 use std::str::FromStr;
 
-use crate::{error::CustomError, state::*};
+use crate::{
+    error::CustomError,
+    state::*,
+    utils::{collection_allowed, lock_metadata},
+};
 use anchor_lang::prelude::*;
 use anchor_spl::token_interface::{
     transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked,
 };
-use mpl_token_metadata::accounts::Metadata;
 #[event_cpi]
 #[derive(Accounts)]
 pub struct BuyPresaleCtx<'info> {
@@ -20,9 +23,17 @@ pub struct BuyPresaleCtx<'info> {
 
     #[account(
         mut,
-        constraint = Clock::get()?.unix_timestamp < pool.presale_time_limit @CustomError::PresaleHasEnded,
+        constraint = pool.load()?.current_status()?.accepts_purchases() @CustomError::PresaleHasEnded,
+        constraint = !pool.load()?.frozen.get() @CustomError::PoolFrozen,
+    )]
+    pub pool: AccountLoader<'info, Pool>,
+
+    #[account(
+        seeds = [CONFIG_PREFIX.as_bytes()],
+        bump = config.bump,
+        constraint = !config.paused @CustomError::ProgramPaused,
     )]
-    pub pool: Box<Account<'info, Pool>>,
+    pub config: Box<Account<'info, Config>>,
 
     #[account(
         mut,
@@ -32,7 +43,7 @@ pub struct BuyPresaleCtx<'info> {
     pub pool_quote_mint_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
-        constraint = quote_mint.key() == pool.quote_mint,
+        constraint = quote_mint.key() == pool.load()?.quote_mint,
     )]
     pub quote_mint: Box<InterfaceAccount<'info, Mint>>,
     #[account(
@@ -70,41 +81,43 @@ pub struct BuyPresaleCtx<'info> {
     )]
     pub fee_collector_quote_mint_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
+    /// CHECK: Checked by cpi, made immutable at the first purchase
+    #[account(
+        mut,
+        seeds = ["metadata".as_bytes(), mpl_token_metadata::ID.as_ref(), pool.load()?.mint.as_ref()],
+        bump,
+        seeds::program = mpl_token_metadata::ID
+    )]
+    pub reward_mint_metadata: UncheckedAccount<'info>,
+
+    /// CHECK: Checked by cpi
+    #[account(address = mpl_token_metadata::ID)]
+    pub mpl_token_program: AccountInfo<'info>,
+
     pub system_program: Program<'info, System>,
 
     pub token_program: Interface<'info, TokenInterface>,
 }
 
-pub fn handler(ctx: Context<BuyPresaleCtx>, amount: u64) -> Result<()> {
+pub(crate) fn handler(ctx: Context<BuyPresaleCtx>, amount: u64) -> Result<()> {
     require!(amount > 0, CustomError::NumberCannotBeZero);
 
     let purchase_receipt = &mut ctx.accounts.purchase_receipt;
-    let pool = &mut ctx.accounts.pool;
+    let pool_key = ctx.accounts.pool.key();
+    let mut pool = ctx.accounts.pool.load_mut()?;
     let mut allowed = true;
-    if pool.requires_collection {
+    if pool.requires_collection.get() {
         if let Some(authorization_record) = &ctx.accounts.purchase_authorisation_record {
             if !ctx.accounts.nft_metadata.data_is_empty() {
-                let mint_metadata_data = ctx
-                    .accounts
-                    .nft_metadata
-                    .try_borrow_mut_data()
-                    .expect("Failed to borrow data");
                 if ctx.accounts.nft_metadata.to_account_info().owner.key() != mpl_token_metadata::ID
                 {
                     return Err(error!(CustomError::InvalidMintMetadataOwner));
                 }
-                let original_mint_metadata =
-                    Metadata::deserialize(&mut mint_metadata_data.as_ref())
-                        .expect("Failed to deserialize metadata");
-                if original_mint_metadata.mint != ctx.accounts.nft.key() {
-                    return Err(error!(CustomError::InvalidMintMetadata));
-                }
-
-                if original_mint_metadata.collection.is_some() {
-                    let collection = original_mint_metadata.collection.unwrap();
-                    allowed = collection.verified
-                        && collection.key == authorization_record.collection_mint;
-                }
+                allowed = collection_allowed(
+                    &ctx.accounts.nft_metadata.try_borrow_data()?,
+                    &ctx.accounts.nft.key(),
+                    &authorization_record.collection_mint,
+                )?;
             }
         } else {
             return Err(error!(CustomError::PurchaseAuthorisationRecordMissing));
@@ -121,25 +134,39 @@ pub fn handler(ctx: Context<BuyPresaleCtx>, amount: u64) -> Result<()> {
         pool.liquidity_collected <= pool.presale_target,
         CustomError::PresaleTargetExceeded
     );
+    let first_purchase = pool.liquidity_collected == amount;
 
     if !purchase_receipt.is_initialized {
+        purchase_receipt.version = PURCHASE_RECEIPT_VERSION;
         purchase_receipt.bump = ctx.bumps.purchase_receipt;
-        purchase_receipt.pool = pool.key();
+        purchase_receipt.pool = pool_key;
         purchase_receipt.original_mint = ctx.accounts.nft.key();
         purchase_receipt.amount = amount;
         purchase_receipt.lp_claimed = 0;
-        purchase_receipt.mint_claimed = false;
+        purchase_receipt.mint_claimed = 0;
+        purchase_receipt.dissolved_lp_claimed = false;
+        purchase_receipt.reclaimed = false;
+        purchase_receipt.direct_claim = false;
+        purchase_receipt.lp_approved_claim_at = None;
+        purchase_receipt.mint_approved_claim_at = None;
+        purchase_receipt.payer = ctx.accounts.payer.key();
+        purchase_receipt.claim_payer = None;
         purchase_receipt.is_initialized = true;
+        pool.purchase_receipt_count = pool
+            .purchase_receipt_count
+            .checked_add(1)
+            .ok_or(CustomError::IntegerOverflow)?;
     } else {
         purchase_receipt.amount = purchase_receipt
             .amount
             .checked_add(amount)
             .ok_or(CustomError::IntegerOverflow)?;
     }
-    if pool.max_amount_per_purchase.is_some()
-        && purchase_receipt.amount > pool.max_amount_per_purchase.unwrap()
-    {
-        return Err(error!(CustomError::AmountPurchaseExceeded));
+    if let Some(max_amount_per_purchase) = pool.max_amount_per_purchase.get() {
+        require!(
+            purchase_receipt.amount <= max_amount_per_purchase,
+            CustomError::AmountPurchaseExceeded
+        );
     }
 
     transfer_checked(
@@ -158,7 +185,9 @@ pub fn handler(ctx: Context<BuyPresaleCtx>, amount: u64) -> Result<()> {
                 authority: ctx.accounts.payer.to_account_info(),
             },
         ),
-        amount.checked_div(100).unwrap(),
+        amount
+            .checked_div(100)
+            .ok_or(CustomError::IntegerOverflow)?,
         ctx.accounts.quote_mint.decimals,
     )?;
 
@@ -181,10 +210,31 @@ pub fn handler(ctx: Context<BuyPresaleCtx>, amount: u64) -> Result<()> {
 
     emit_cpi!(PurchasedPresaleEvent {
         payer: ctx.accounts.payer.key(),
-        amount: amount,
-        pool: pool.key(),
+        amount,
+        pool: pool_key,
         original_mint: ctx.accounts.nft.key(),
+        receipt_amount: purchase_receipt.amount,
+        liquidity_collected: pool.liquidity_collected,
+        purchase_receipt_count: pool.purchase_receipt_count,
+        event_seq: pool.next_event_seq(),
     });
 
+    if let Some(status_changed) = pool.transition(pool_key, Clock::get()?.unix_timestamp) {
+        emit_cpi!(status_changed);
+    }
+
+    // update_pool can only change the metadata before the first purchase, lock it for good
+    if first_purchase {
+        let (pool_mint, pool_bump) = (pool.mint, pool.bump);
+        drop(pool);
+        let pool_seed = &[POOL_PREFIX.as_bytes(), pool_mint.as_ref(), &[pool_bump]];
+        lock_metadata(
+            ctx.accounts.mpl_token_program.to_account_info(),
+            ctx.accounts.reward_mint_metadata.to_account_info(),
+            ctx.accounts.pool.to_account_info(),
+            &[&pool_seed[..]],
+        )?;
+    }
+
     Ok(())
 }
@@ -1,9 +1,10 @@
 use crate::error::CustomError;
 use crate::state::*;
-use crate::utils::U128;
 use anchor_lang::prelude::*;
 use anchor_spl::associated_token::AssociatedToken;
-use anchor_spl::token_interface::{TokenAccount, TokenInterface, Mint, transfer_checked, TransferChecked};
+use anchor_spl::token_interface::{
+    transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
 
 #[event_cpi]
 #[derive(Accounts)]
@@ -12,12 +13,13 @@ pub struct CheckClaimCtx<'info> {
         mut,
         constraint = purchase_receipt.lp_elligible.is_none() @CustomError::ClaimedAlreadyChecked,
         constraint = purchase_receipt.mint_elligible.is_none() @CustomError::ClaimedAlreadyChecked,
+        constraint = !purchase_receipt.reclaimed @CustomError::ReceiptReclaimed,
     )]
     pub purchase_receipt: Box<Account<'info, PurchaseReceipt>>,
 
     #[account(
         init_if_needed,
-        payer = payer,  
+        payer = payer,
         associated_token::mint = lp_mint,
         associated_token::authority = purchase_receipt,
     )]
@@ -26,18 +28,18 @@ pub struct CheckClaimCtx<'info> {
     #[account(
         mut,
         constraint = pool_lp_token_account.owner == pool.key(),
-        constraint = pool_lp_token_account.mint == pool.lp_mint.unwrap(),
+        constraint = pool_lp_token_account.mint == pool.load()?.lp_mint.get().unwrap(),
     )]
     pub pool_lp_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
-        constraint = lp_mint.key() == pool.lp_mint.unwrap() @CustomError::InvalidLpMint,
+        constraint = lp_mint.key() == pool.load()?.lp_mint.get().unwrap() @CustomError::InvalidLpMint,
     )]
     pub lp_mint: Box<InterfaceAccount<'info, Mint>>,
 
     #[account(
         init_if_needed,
-        payer = payer,  
+        payer = payer,
         associated_token::mint = reward_mint,
         associated_token::authority = purchase_receipt,
     )]
@@ -46,20 +48,29 @@ pub struct CheckClaimCtx<'info> {
     #[account(
         mut,
         constraint = pool_reward_token_account.owner == pool.key(),
-        constraint = pool_reward_token_account.mint == pool.mint,
+        constraint = pool_reward_token_account.mint == pool.load()?.mint,
     )]
     pub pool_reward_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
-        constraint = reward_mint.key() == pool.mint @CustomError::InvalidRewardMint,
+        constraint = reward_mint.key() == pool.load()?.mint @CustomError::InvalidRewardMint,
     )]
     pub reward_mint: Box<InterfaceAccount<'info, Mint>>,
 
     #[account(
+        mut,
         constraint = pool.key() == purchase_receipt.pool @CustomError::InvalidPool,
-        constraint = pool.vesting_started_at.is_some() @CustomError::PresaleIsStillOngoing,
+        constraint = pool.load()?.current_status()?.has_launched() @CustomError::PresaleIsStillOngoing,
+        constraint = !pool.load()?.frozen.get() @CustomError::PoolFrozen,
+    )]
+    pub pool: AccountLoader<'info, Pool>,
+
+    #[account(
+        seeds = [CONFIG_PREFIX.as_bytes()],
+        bump = config.bump,
+        constraint = !config.paused @CustomError::ProgramPaused,
     )]
-    pub pool: Box<Account<'info, Pool>>,
+    pub config: Box<Account<'info, Config>>,
 
     #[account(mut)]
     pub payer: Signer<'info>,
@@ -69,38 +80,41 @@ pub struct CheckClaimCtx<'info> {
     pub system_program: Program<'info, System>,
 }
 
-pub fn handler(ctx: Context<CheckClaimCtx>) -> Result<()> {
-    let pool = &ctx.accounts.pool;
-    let lp_mint_supply_after_creator_fees = pool.lp_mint_supply.unwrap().checked_sub(pool.lp_mint_supply_for_creator.unwrap()).unwrap();
-    let initial_mint_supply_after_creator_fees = pool.initial_supply.checked_sub(pool.initial_supply_for_creator).unwrap(); 
-    let liquidity_collected = pool.liquidity_collected; 
+pub(crate) fn handler(ctx: Context<CheckClaimCtx>) -> Result<()> {
+    let pool_key = ctx.accounts.pool.key();
+    let mut pool = ctx.accounts.pool.load_mut()?;
     let purchase_receipt = &mut ctx.accounts.purchase_receipt;
-
-    let lp_elligible =
-        match U128::from(purchase_receipt.amount).checked_mul(U128::from(lp_mint_supply_after_creator_fees)) {
-            Some(result) => result
-                .checked_div(U128::from(liquidity_collected))
-                .ok_or(CustomError::IntegerOverflow)?,
-            None => return Err(error!(CustomError::IntegerOverflow)),
-        };
-
-    purchase_receipt.lp_elligible = Some(lp_elligible.as_u64());
-
-    let mint_elligible =
-        match U128::from(purchase_receipt.amount).checked_mul(U128::from(initial_mint_supply_after_creator_fees)) {
-            Some(result) => result
-                .checked_div(U128::from(liquidity_collected))
-                .ok_or(CustomError::IntegerOverflow)?,
-            None => return Err(error!(CustomError::IntegerOverflow)),
-        };
-
-    purchase_receipt.mint_elligible = Some(mint_elligible.as_u64());
-
-    let pool_seed = &[
-        POOL_PREFIX.as_bytes(),
-        pool.mint.as_ref(),
-        &[pool.bump],
-    ];
+    if let Some(claim_deadline) = pool.claim_deadline.get() {
+        require!(
+            Clock::get()?.unix_timestamp < claim_deadline,
+            CustomError::ClaimDeadlinePassed
+        );
+    }
+
+    let lp_elligible = pool.lp_entitlement(purchase_receipt.amount)?;
+    purchase_receipt.lp_elligible = Some(lp_elligible);
+
+    let mint_elligible = pool.mint_entitlement(purchase_receipt.amount)?;
+    purchase_receipt.mint_elligible = Some(mint_elligible);
+    purchase_receipt.claim_payer = Some(ctx.accounts.payer.key());
+
+    pool.record_checked_receipt(purchase_receipt.amount, lp_elligible, mint_elligible)?;
+    let check_claim_event = CheckClaimEvent {
+        payer: ctx.accounts.payer.key(),
+        pool: pool_key,
+        original_mint: purchase_receipt.original_mint.key(),
+        lp_elligible,
+        mint_elligible,
+        lp_mint_distributed: pool.lp_mint_distributed,
+        initial_supply_distributed: pool.initial_supply_distributed,
+        purchase_receipts_checked: pool.purchase_receipts_checked,
+        event_seq: pool.next_event_seq(),
+    };
+
+    let (pool_mint, pool_bump) = (pool.mint, pool.bump);
+    drop(pool);
+
+    let pool_seed = &[POOL_PREFIX.as_bytes(), pool_mint.as_ref(), &[pool_bump]];
     let signer = &[&pool_seed[..]];
 
     //transfer lp
@@ -110,12 +124,15 @@ pub fn handler(ctx: Context<CheckClaimCtx>) -> Result<()> {
             TransferChecked {
                 mint: ctx.accounts.lp_mint.to_account_info(),
                 from: ctx.accounts.pool_lp_token_account.to_account_info(),
-                to: ctx.accounts.purchase_receipt_lp_token_account.to_account_info(),
-                authority: pool.to_account_info(),
+                to: ctx
+                    .accounts
+                    .purchase_receipt_lp_token_account
+                    .to_account_info(),
+                authority: ctx.accounts.pool.to_account_info(),
             },
         )
         .with_signer(signer),
-        lp_elligible.as_u64(),
+        lp_elligible,
         ctx.accounts.lp_mint.decimals,
     )?;
 
@@ -124,24 +141,21 @@ pub fn handler(ctx: Context<CheckClaimCtx>) -> Result<()> {
         CpiContext::new(
             ctx.accounts.token_program.to_account_info(),
             TransferChecked {
-            mint: ctx.accounts.reward_mint.to_account_info(),
-            from: ctx.accounts.pool_reward_token_account.to_account_info(),
-            to: ctx.accounts.purchase_receipt_reward_token_account.to_account_info(),
-            authority: pool.to_account_info(),
-        },
-    )
+                mint: ctx.accounts.reward_mint.to_account_info(),
+                from: ctx.accounts.pool_reward_token_account.to_account_info(),
+                to: ctx
+                    .accounts
+                    .purchase_receipt_reward_token_account
+                    .to_account_info(),
+                authority: ctx.accounts.pool.to_account_info(),
+            },
+        )
         .with_signer(signer),
-        mint_elligible.as_u64(),
+        mint_elligible,
         ctx.accounts.reward_mint.decimals,
     )?;
 
-    emit_cpi!(CheckClaimEvent {
-        payer: ctx.accounts.payer.key(),
-        pool: pool.key(),
-        original_mint: purchase_receipt.original_mint.key(),
-        lp_elligible: purchase_receipt.lp_elligible.unwrap(),
-        mint_elligible: purchase_receipt.mint_elligible.unwrap(),
-    });
+    emit_cpi!(check_claim_event);
 
     Ok(())
 }
@@ -0,0 +1,195 @@
+use crate::error::CustomError;
+use crate::state::*;
+use crate::utils::VestingSchedule;
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ClaimAllCtx<'info> {
+    #[account(
+        mut,
+        constraint = purchase_receipt.original_mint == nft_owner_nft_token_account.mint @ CustomError::MintNotAllowed,
+    )]
+    pub purchase_receipt: Box<Account<'info, PurchaseReceipt>>,
+
+    #[account(
+        mut,
+        constraint = pool.key() == purchase_receipt.pool @CustomError::InvalidPool,
+        constraint = pool.load()?.current_status()?.has_launched() @CustomError::PresaleIsStillOngoing,
+    )]
+    pub pool: AccountLoader<'info, Pool>,
+
+    #[account(
+        mut,
+        constraint = pool_lp_token_account.owner == pool.key(),
+        constraint = pool_lp_token_account.mint == lp_mint.key(),
+    )]
+    pub pool_lp_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = pool_reward_token_account.owner == pool.key(),
+        constraint = pool_reward_token_account.mint == reward_mint.key(),
+    )]
+    pub pool_reward_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        constraint = nft_owner_nft_token_account.amount == 1,
+        constraint = nft_owner_nft_token_account.owner == nft_owner.key()
+    )]
+    pub nft_owner_nft_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = lp_mint,
+        associated_token::authority = nft_owner,
+    )]
+    pub nft_owner_lp_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = reward_mint,
+        associated_token::authority = nft_owner,
+    )]
+    pub nft_owner_reward_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    ///CHECK: Contraint is checked by other accounts
+    pub nft_owner: AccountInfo<'info>,
+
+    #[account(
+        constraint = pool.load()?.lp_mint.get() == Some(lp_mint.key()) @CustomError::InvalidLpMint,
+    )]
+    pub lp_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        constraint = pool.load()?.mint == reward_mint.key() @CustomError::InvalidRewardMint,
+    )]
+    pub reward_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        seeds = [CONFIG_PREFIX.as_bytes()],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+pub(crate) fn handler(ctx: Context<ClaimAllCtx>) -> Result<()> {
+    if ctx.accounts.nft_owner.key() != ctx.accounts.payer.key() {
+        return Err(error!(CustomError::InvalidSigner));
+    }
+
+    let pool_key = ctx.accounts.pool.key();
+    let mut pool = ctx.accounts.pool.load_mut()?;
+    let purchase_receipt = &mut ctx.accounts.purchase_receipt;
+    let current_time = Clock::get()?.unix_timestamp;
+    // Claim All Criteria
+    // 1. The entitlement is settled on the first call, under the same deadline, pause and freeze as check_claim_elligibility.
+    // 2. Tokens are paid straight from the pool vaults, so receipts already checked into escrow are rejected.
+    // 3. Both the reward tokens and the lp tokens vested so far are paid out in one go.
+    purchase_receipt.settle_direct_claim(&mut pool, &ctx.accounts.config, current_time)?;
+
+    let vesting_started_at = pool.vesting_started_at.get().unwrap();
+    let lp_claimable = VestingSchedule::new(
+        vesting_started_at,
+        pool.vesting_period,
+        purchase_receipt.lp_elligible.unwrap(),
+    )
+    .claimable(current_time, purchase_receipt.lp_claimed)?;
+    let mint_claimable = VestingSchedule::new(
+        vesting_started_at,
+        pool.reward_vesting_period,
+        purchase_receipt.mint_elligible.unwrap(),
+    )
+    .claimable(current_time, purchase_receipt.mint_claimed)?;
+    if lp_claimable == 0 && mint_claimable == 0 {
+        return Err(error!(CustomError::MaximumAmountClaimed));
+    }
+
+    purchase_receipt.lp_claimed = purchase_receipt
+        .lp_claimed
+        .checked_add(lp_claimable)
+        .ok_or(CustomError::IntegerOverflow)?;
+    purchase_receipt.mint_claimed = purchase_receipt
+        .mint_claimed
+        .checked_add(mint_claimable)
+        .ok_or(CustomError::IntegerOverflow)?;
+    purchase_receipt.last_claimed_at = Some(current_time);
+
+    let claim_all_event = ClaimAllEvent {
+        payer: ctx.accounts.payer.key(),
+        pool: pool_key,
+        lp_claimed: lp_claimable,
+        mint_claimed: mint_claimable,
+        last_claimed_at: current_time,
+        original_mint: purchase_receipt.original_mint,
+        original_mint_owner: ctx.accounts.nft_owner.key(),
+        lp_elligible: purchase_receipt.lp_elligible.unwrap(),
+        mint_elligible: purchase_receipt.mint_elligible.unwrap(),
+        total_lp_claimed: purchase_receipt.lp_claimed,
+        total_mint_claimed: purchase_receipt.mint_claimed,
+        lp_mint_distributed: pool.lp_mint_distributed,
+        initial_supply_distributed: pool.initial_supply_distributed,
+        purchase_receipts_checked: pool.purchase_receipts_checked,
+        event_seq: pool.next_event_seq(),
+    };
+    let (pool_mint, pool_bump) = (pool.mint, pool.bump);
+    drop(pool);
+    let pool_seed = &[POOL_PREFIX.as_bytes(), pool_mint.as_ref(), &[pool_bump]];
+    let signer = &[&pool_seed[..]];
+
+    //transfer lp
+    if lp_claimable > 0 {
+        transfer_checked(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    mint: ctx.accounts.lp_mint.to_account_info(),
+                    from: ctx.accounts.pool_lp_token_account.to_account_info(),
+                    to: ctx.accounts.nft_owner_lp_token_account.to_account_info(),
+                    authority: ctx.accounts.pool.to_account_info(),
+                },
+            )
+            .with_signer(signer),
+            lp_claimable,
+            ctx.accounts.lp_mint.decimals,
+        )?;
+    }
+
+    //transfer mint
+    if mint_claimable > 0 {
+        transfer_checked(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    mint: ctx.accounts.reward_mint.to_account_info(),
+                    from: ctx.accounts.pool_reward_token_account.to_account_info(),
+                    to: ctx
+                        .accounts
+                        .nft_owner_reward_token_account
+                        .to_account_info(),
+                    authority: ctx.accounts.pool.to_account_info(),
+                },
+            )
+            .with_signer(signer),
+            mint_claimable,
+            ctx.accounts.reward_mint.decimals,
+        )?;
+    }
+
+    emit_cpi!(claim_all_event);
+
+    Ok(())
+}
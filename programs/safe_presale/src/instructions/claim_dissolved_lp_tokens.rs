@@ -0,0 +1,116 @@
+use crate::error::CustomError;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ClaimDissolvedLpCtx<'info> {
+    #[account(
+        mut,
+        constraint = !purchase_receipt.dissolved_lp_claimed @CustomError::MaximumAmountClaimed,
+        constraint = !purchase_receipt.reclaimed @CustomError::ReceiptReclaimed,
+        constraint = purchase_receipt.original_mint == nft_owner_nft_token_account.mint @ CustomError::MintNotAllowed,
+    )]
+    pub purchase_receipt: Box<Account<'info, PurchaseReceipt>>,
+
+    #[account(
+        mut,
+        constraint = pool_lp_token_account.owner == pool.key(),
+        constraint = pool_lp_token_account.mint == lp_mint.key(),
+    )]
+    pub pool_lp_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = pool.key() == purchase_receipt.pool @CustomError::InvalidPool,
+        constraint = pool.load()?.current_status()? == PoolStatus::Cancelled @CustomError::PoolNotDissolved,
+    )]
+    pub pool: AccountLoader<'info, Pool>,
+
+    #[account(
+        constraint = nft_owner_nft_token_account.amount == 1,
+        constraint = nft_owner_nft_token_account.owner == nft_owner.key()
+    )]
+    pub nft_owner_nft_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = lp_mint,
+        associated_token::authority = nft_owner,
+    )]
+    pub nft_owner_lp_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    ///CHECK: Contraint is checked by other accounts
+    pub nft_owner: AccountInfo<'info>,
+
+    #[account(
+        constraint = pool.load()?.lp_mint.get() == Some(lp_mint.key()) @ CustomError::InvalidLpMint
+    )]
+    pub lp_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        seeds = [CONFIG_PREFIX.as_bytes()],
+        bump = config.bump,
+        constraint = !config.paused @CustomError::ProgramPaused,
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+pub(crate) fn handler(ctx: Context<ClaimDissolvedLpCtx>) -> Result<()> {
+    if ctx.accounts.nft_owner.key() != ctx.accounts.payer.key() {
+        return Err(error!(CustomError::InvalidSigner));
+    }
+
+    let pool_key = ctx.accounts.pool.key();
+    let mut pool = ctx.accounts.pool.load_mut()?;
+    let purchase_receipt = &mut ctx.accounts.purchase_receipt;
+    let lp_claimable = pool.dissolved_lp_entitlement(purchase_receipt.amount)?;
+
+    purchase_receipt.dissolved_lp_claimed = true;
+    pool.record_dissolved_lp_claim(purchase_receipt.amount, lp_claimable)?;
+
+    let claim_event = ClaimDissolvedLpTokenEvent {
+        payer: ctx.accounts.payer.key(),
+        pool: pool_key,
+        lp_claimed: lp_claimable,
+        original_mint: purchase_receipt.original_mint,
+        original_mint_owner: ctx.accounts.nft_owner.key(),
+        event_seq: pool.next_event_seq(),
+    };
+    let (pool_mint, pool_bump) = (pool.mint, pool.bump);
+    drop(pool);
+    let pool_seed = &[POOL_PREFIX.as_bytes(), pool_mint.as_ref(), &[pool_bump]];
+    let signer = &[&pool_seed[..]];
+
+    //transfer to nft owner
+    transfer_checked(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                mint: ctx.accounts.lp_mint.to_account_info(),
+                from: ctx.accounts.pool_lp_token_account.to_account_info(),
+                to: ctx.accounts.nft_owner_lp_token_account.to_account_info(),
+                authority: ctx.accounts.pool.to_account_info(),
+            },
+        )
+        .with_signer(signer),
+        lp_claimable,
+        ctx.accounts.lp_mint.decimals,
+    )?;
+
+    emit_cpi!(claim_event);
+
+    Ok(())
+}
@@ -1,5 +1,8 @@
 use crate::error::CustomError;
 use crate::state::*;
+use crate::utils::{
+    cap_approved_claim, check_claim_approval, check_delegated_claim, VestingSchedule,
+};
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
@@ -11,8 +14,8 @@ use anchor_spl::{
 pub struct ClaimRewardCtx<'info> {
     #[account(
         mut,
-        constraint = !purchase_receipt.mint_claimed, 
         constraint = purchase_receipt.mint_elligible.is_some() @CustomError::CheckClaimFirstBeforeClaiming,
+        constraint = !purchase_receipt.direct_claim @CustomError::DirectClaimReceipt,
         constraint = purchase_receipt.original_mint == nft_owner_nft_token_account.mint @ CustomError::MintNotAllowed,
     )]
     pub purchase_receipt: Box<Account<'info, PurchaseReceipt>>,
@@ -20,18 +23,18 @@ pub struct ClaimRewardCtx<'info> {
     #[account(
         mut,
         constraint = purchase_receipt_reward_token_account.owner == purchase_receipt.key(),
-        constraint = purchase_receipt_reward_token_account.mint == pool.mint,
+        constraint = purchase_receipt_reward_token_account.mint == pool.load()?.mint,
     )]
     pub purchase_receipt_reward_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
+        mut,
         constraint = pool.key() == purchase_receipt.pool @CustomError::InvalidPool,
     )]
-    pub pool: Box<Account<'info, Pool>>,
+    pub pool: AccountLoader<'info, Pool>,
 
     #[account(
         constraint = nft_owner_nft_token_account.amount == 1,
-        constraint = nft_owner_nft_token_account.owner == nft_owner.key()
     )]
     pub nft_owner_nft_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
@@ -55,7 +58,22 @@ pub struct ClaimRewardCtx<'info> {
     pub nft_metadata: AccountInfo<'info>,
 
     #[account(
-        constraint =  pool.mint == reward_mint.key() @ CustomError::InvalidRewardMint
+        seeds = [STAKING_PROGRAM_PREFIX.as_bytes(), pool.key().as_ref(), staking_program_record.staking_program.as_ref()],
+        bump = staking_program_record.bump,
+    )]
+    pub staking_program_record: Option<Box<Account<'info, StakingProgramRecord>>>,
+
+    ///CHECK: Frozen delegate or escrow vault of the staking program, checked in the handler
+    pub staking_authority: Option<AccountInfo<'info>>,
+
+    #[account(
+        seeds = [CLAIM_APPROVAL_PREFIX.as_bytes(), nft_owner.key().as_ref(), payer.key().as_ref(), ClaimApproval::scope_seed(&claim_approval.purchase_receipt)],
+        bump = claim_approval.bump,
+    )]
+    pub claim_approval: Option<Box<Account<'info, ClaimApproval>>>,
+
+    #[account(
+        constraint =  pool.load()?.mint == reward_mint.key() @ CustomError::InvalidRewardMint
     )]
     pub reward_mint: Box<InterfaceAccount<'info, Mint>>,
 
@@ -67,38 +85,59 @@ pub struct ClaimRewardCtx<'info> {
     pub system_program: Program<'info, System>,
 }
 
-pub fn handler(ctx: Context<ClaimRewardCtx>) -> Result<()> {
+pub(crate) fn handler(ctx: Context<ClaimRewardCtx>) -> Result<()> {
     let purchase_receipt = &mut ctx.accounts.purchase_receipt;
+    let current_time = Clock::get()?.unix_timestamp;
     // Delegated Claim Criteria
-    // 1. Only allow delegated claiming if the nfts are frozen to the owner's wallet.
-    let allowed = ctx.accounts.nft_owner.key() == ctx.accounts.payer.key();
-    // if !ctx.accounts.nft_metadata.data_is_empty() {
-    //     let mint_metadata_data = ctx
-    //         .accounts
-    //         .nft_metadata
-    //         .try_borrow_mut_data()
-    //         .expect("Failed to borrow data");
-    //     if ctx.accounts.nft_metadata.to_account_info().owner.key() != mpl_token_metadata::ID {
-    //         return Err(error!(CustomError::InvalidMintMetadataOwner));
-    //     }
-    //     let original_mint_metadata = Metadata::deserialize(&mut mint_metadata_data.as_ref())
-    //         .expect("Failed to deserialize metadata");
-    //     if original_mint_metadata.mint != purchase_receipt.original_mint {
-    //         return Err(error!(CustomError::InvalidMintMetadata));
-    //     }
-
-    //     if original_mint_metadata.collection.is_some() {
-    //         let collection = original_mint_metadata.collection.unwrap();
-    //         if collection.verified && &collection.key == &collection::id() && ctx.accounts.nft_owner_nft_token_account.is_frozen(){ // only allow staked nfts to do delegated claiming
-    //             allowed = true;
-    //         }
-    //     }
-    // }
-    if !allowed {
-        return Err(error!(CustomError::InvalidSigner));
+    // 1. A delegate approved by the owner can claim, within the approval's expiry and per-claim limit.
+    // 2. Otherwise only allow delegated claiming if the nft is frozen or escrowed for the owner by an allowlisted staking program.
+    match &ctx.accounts.claim_approval {
+        Some(claim_approval) => {
+            check_claim_approval(
+                claim_approval,
+                &ctx.accounts.nft_owner,
+                &ctx.accounts.nft_owner_nft_token_account,
+                &purchase_receipt.key(),
+                current_time,
+            )?;
+        }
+        None => {
+            check_delegated_claim(
+                &ctx.accounts.nft_owner,
+                &ctx.accounts.payer.to_account_info(),
+                &ctx.accounts.nft_owner_nft_token_account,
+                ctx.accounts.staking_program_record.as_deref(),
+                ctx.accounts.staking_authority.as_ref(),
+            )?;
+        }
+    }
+    let mut pool = ctx.accounts.pool.load_mut()?;
+    let vesting_schedule = VestingSchedule::new(
+        pool.vesting_started_at.get().unwrap(),
+        pool.reward_vesting_period,
+        purchase_receipt.mint_elligible.unwrap(),
+    );
+
+    let mut mint_claimable =
+        vesting_schedule.claimable(current_time, purchase_receipt.mint_claimed)?;
+    if let Some(claim_approval) = &ctx.accounts.claim_approval {
+        mint_claimable = cap_approved_claim(
+            claim_approval.max_mint_per_claim,
+            &mut purchase_receipt.mint_approved_claim_at,
+            mint_claimable,
+            current_time,
+        )?;
+    }
+    if mint_claimable == 0 {
+        return Err(error!(CustomError::MaximumAmountClaimed));
     }
-    let mint_elligible = purchase_receipt.mint_elligible.unwrap();
-    purchase_receipt.mint_claimed = true;
+
+    //update mint_claimed
+    purchase_receipt.mint_claimed = purchase_receipt
+        .mint_claimed
+        .checked_add(mint_claimable)
+        .ok_or(CustomError::IntegerOverflow)?;
+
     let purchase_seed = &[
         PURCHASE_RECEIPT_PREFIX.as_bytes(),
         purchase_receipt.pool.as_ref(),
@@ -117,21 +156,27 @@ pub fn handler(ctx: Context<ClaimRewardCtx>) -> Result<()> {
                     .accounts
                     .purchase_receipt_reward_token_account
                     .to_account_info(),
-                to: ctx.accounts.nft_owner_reward_token_account.to_account_info(),
+                to: ctx
+                    .accounts
+                    .nft_owner_reward_token_account
+                    .to_account_info(),
                 authority: purchase_receipt.to_account_info(),
             },
         )
         .with_signer(signer),
-        mint_elligible,
+        mint_claimable,
         ctx.accounts.reward_mint.decimals,
     )?;
 
     emit_cpi!(ClaimRewardEvent {
         payer: ctx.accounts.payer.key(),
         pool: purchase_receipt.pool,
-        mint_elligible: mint_elligible,
+        mint_claimed: mint_claimable,
+        last_claimed_at: current_time,
         original_mint_owner: ctx.accounts.nft_owner.key(),
         original_mint: purchase_receipt.original_mint,
+        total_mint_claimed: purchase_receipt.mint_claimed,
+        event_seq: pool.next_event_seq(),
     });
 
     Ok(())
@@ -1,5 +1,6 @@
 use crate::error::CustomError;
 use crate::state::*;
+use crate::utils::VestingSchedule;
 use anchor_lang::prelude::*;
 use anchor_spl::associated_token::AssociatedToken;
 use anchor_spl::token_interface::{
@@ -12,7 +13,7 @@ pub struct ClaimRewardCreatorCtx<'info> {
     #[account(
         mut,
         constraint = pool_reward_token_account.owner == pool.key(),
-        constraint = pool_reward_token_account.mint == pool.mint,
+        constraint = pool_reward_token_account.mint == pool.load()?.mint,
     )]
     pub pool_reward_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
@@ -20,20 +21,23 @@ pub struct ClaimRewardCreatorCtx<'info> {
         init_if_needed,
         payer = payer,
         associated_token::mint = reward_mint,
-        associated_token::authority = payer,
+        associated_token::authority = authority,
     )]
     pub pool_authority_reward_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
-        constraint = reward_mint.key() == pool.mint @CustomError::InvalidRewardMint,
+        constraint = reward_mint.key() == pool.load()?.mint @CustomError::InvalidRewardMint,
     )]
     pub reward_mint: Box<InterfaceAccount<'info, Mint>>,
 
     #[account(
-        constraint = payer.key() == pool.authority @CustomError::InvalidSigner,
-        constraint = pool.vesting_started_at.is_some() @CustomError::PresaleIsStillOngoing,
+        mut,
+        constraint = authority.key() == pool.load()?.authority @CustomError::InvalidSigner,
+        constraint = pool.load()?.current_status()?.has_launched() @CustomError::PresaleIsStillOngoing,
     )]
-    pub pool: Box<Account<'info, Pool>>,
+    pub pool: AccountLoader<'info, Pool>,
+
+    pub authority: Signer<'info>,
 
     #[account(mut)]
     pub payer: Signer<'info>,
@@ -43,9 +47,41 @@ pub struct ClaimRewardCreatorCtx<'info> {
     pub system_program: Program<'info, System>,
 }
 
-pub fn handler(ctx: Context<ClaimRewardCreatorCtx>) -> Result<()> {
-    let pool = &ctx.accounts.pool;
-    let pool_seed = &[POOL_PREFIX.as_bytes(), pool.mint.as_ref(), &[pool.bump]];
+pub(crate) fn handler(ctx: Context<ClaimRewardCreatorCtx>) -> Result<()> {
+    let pool_key = ctx.accounts.pool.key();
+    let mut pool = ctx.accounts.pool.load_mut()?;
+    let vesting_schedule = VestingSchedule::new(
+        pool.vesting_started_at.get().unwrap(),
+        pool.creator_reward_vesting_period,
+        pool.initial_supply_for_creator,
+    );
+
+    let current_time = Clock::get()?.unix_timestamp;
+    let mint_claimable =
+        vesting_schedule.claimable(current_time, pool.initial_supply_claimed_by_creator)?;
+    if mint_claimable == 0 {
+        return Err(error!(CustomError::MaximumAmountClaimed));
+    }
+
+    //update initial_supply_claimed_by_creator
+    pool.initial_supply_claimed_by_creator = pool
+        .initial_supply_claimed_by_creator
+        .checked_add(mint_claimable)
+        .ok_or(CustomError::IntegerOverflow)?;
+    //update initial_supply_last_claimed_by_creator
+    pool.initial_supply_last_claimed_by_creator = Some(current_time).into();
+
+    let claim_event = ClaimRewardForCreatorEvent {
+        payer: ctx.accounts.payer.key(),
+        pool: pool_key,
+        mint_claimed: mint_claimable,
+        last_claimed_at: current_time,
+        initial_supply_claimed_by_creator: pool.initial_supply_claimed_by_creator,
+        event_seq: pool.next_event_seq(),
+    };
+    let (pool_mint, pool_bump) = (pool.mint, pool.bump);
+    drop(pool);
+    let pool_seed = &[POOL_PREFIX.as_bytes(), pool_mint.as_ref(), &[pool_bump]];
     let signer = &[&pool_seed[..]];
 
     //transfer mint
@@ -59,19 +95,15 @@ pub fn handler(ctx: Context<ClaimRewardCreatorCtx>) -> Result<()> {
                     .accounts
                     .pool_authority_reward_token_account
                     .to_account_info(),
-                authority: pool.to_account_info(),
+                authority: ctx.accounts.pool.to_account_info(),
             },
         )
         .with_signer(signer),
-        pool.initial_supply_for_creator,
+        mint_claimable,
         ctx.accounts.reward_mint.decimals,
     )?;
 
-    emit_cpi!(ClaimRewardForCreatorEvent {
-        payer: ctx.accounts.payer.key(),
-        pool: pool.key(),
-        mint_elligible: pool.initial_supply_for_creator,
-    });
+    emit_cpi!(claim_event);
 
     Ok(())
 }
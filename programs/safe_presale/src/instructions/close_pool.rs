@@ -0,0 +1,148 @@
+use crate::error::CustomError;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{close_account, CloseAccount, TokenAccount, TokenInterface};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ClosePoolCtx<'info> {
+    #[account(
+        mut,
+        close = pool_payer,
+        constraint = pool.load()?.authority == authority.key() @CustomError::InvalidSigner,
+        constraint = pool.load()?.purchase_receipt_count == 0 @CustomError::PoolHasOpenReceipts,
+        constraint = pool.load()?.receipts_tracked.get() @CustomError::ReceiptsNotTracked,
+        constraint = !pool.load()?.frozen.get() @CustomError::PoolFrozen,
+    )]
+    pub pool: AccountLoader<'info, Pool>,
+
+    #[account(
+        seeds = [CONFIG_PREFIX.as_bytes()],
+        bump = config.bump,
+        constraint = !config.paused @CustomError::ProgramPaused,
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(
+        mut,
+        constraint = pool_reward_token_account.owner == pool.key(),
+        constraint = pool_reward_token_account.mint == pool.load()?.mint,
+        constraint = pool_reward_token_account.amount == 0 @CustomError::TokenAccountNotEmpty,
+    )]
+    pub pool_reward_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Created outside of the program, usually by the first buyer, its rent goes to the pool payer
+    #[account(
+        mut,
+        constraint = pool_quote_token_account.owner == pool.key(),
+        constraint = pool_quote_token_account.mint == pool.load()?.quote_mint,
+        constraint = pool_quote_token_account.amount == 0 @CustomError::TokenAccountNotEmpty,
+    )]
+    pub pool_quote_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    #[account(
+        mut,
+        constraint = pool_lp_token_account.owner == pool.key(),
+        constraint = pool.load()?.lp_mint.get() == Some(pool_lp_token_account.mint) @CustomError::InvalidLpMint,
+        constraint = pool_lp_token_account.amount == 0 @CustomError::TokenAccountNotEmpty,
+    )]
+    pub pool_lp_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    ///CHECK: Receives the rent, must be whoever paid for the pool
+    #[account(
+        mut,
+        address = pool.load()?.payer,
+    )]
+    pub pool_payer: AccountInfo<'info>,
+
+    ///CHECK: Receives the lp token account rent, must be whoever launched the pool. Only required for launched pools
+    #[account(
+        mut,
+        constraint = pool.load()?.launch_payer.get() == Some(launch_payer.key()),
+    )]
+    pub launch_payer: Option<AccountInfo<'info>>,
+
+    pub authority: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+pub(crate) fn handler(ctx: Context<ClosePoolCtx>) -> Result<()> {
+    let pool_key = ctx.accounts.pool.key();
+    let mut pool = ctx.accounts.pool.load_mut()?;
+    // Close Criteria
+    // 1. Launched pools can only close once the creator has claimed every allocation.
+    // 2. Pools that never launched can only close once the presale failed or expired.
+    // 3. Every purchase receipt and every pool token account must be empty.
+    // 4. The lp token account rent goes to whoever launched the pool, the rest to the pool payer.
+    let status = pool.current_status()?;
+    if status.has_launched() {
+        require!(
+            status == PoolStatus::Cancelled
+                || pool.lp_mint_supply_for_creator.get() == Some(pool.lp_mint_claimed_by_creator),
+            CustomError::EntitlementNotFullyClaimed
+        );
+        require!(
+            pool.initial_supply_for_creator == pool.initial_supply_claimed_by_creator,
+            CustomError::EntitlementNotFullyClaimed
+        );
+        require!(
+            ctx.accounts.pool_lp_token_account.is_some(),
+            CustomError::InvalidLpMint
+        );
+        require!(
+            ctx.accounts.launch_payer.is_some(),
+            CustomError::MissingLaunchPayer
+        );
+    } else {
+        require!(
+            matches!(status, PoolStatus::Failed | PoolStatus::Expired),
+            CustomError::PoolIsStillActive
+        );
+    }
+
+    let close_pool_event = ClosePoolEvent {
+        payer: ctx.accounts.authority.key(),
+        pool: pool_key,
+        event_seq: pool.next_event_seq(),
+    };
+    let (pool_mint, pool_bump) = (pool.mint, pool.bump);
+    drop(pool);
+    let pool_seed = &[POOL_PREFIX.as_bytes(), pool_mint.as_ref(), &[pool_bump]];
+    let signer = &[&pool_seed[..]];
+
+    let pool_payer = ctx.accounts.pool_payer.to_account_info();
+    let mut token_accounts = vec![(
+        ctx.accounts.pool_reward_token_account.to_account_info(),
+        pool_payer.clone(),
+    )];
+    if let Some(pool_quote_token_account) = &ctx.accounts.pool_quote_token_account {
+        token_accounts.push((pool_quote_token_account.to_account_info(), pool_payer));
+    }
+    if let (Some(pool_lp_token_account), Some(launch_payer)) = (
+        &ctx.accounts.pool_lp_token_account,
+        &ctx.accounts.launch_payer,
+    ) {
+        token_accounts.push((
+            pool_lp_token_account.to_account_info(),
+            launch_payer.clone(),
+        ));
+    }
+    for (token_account, destination) in token_accounts {
+        close_account(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                CloseAccount {
+                    account: token_account,
+                    destination,
+                    authority: ctx.accounts.pool.to_account_info(),
+                },
+            )
+            .with_signer(signer),
+        )?;
+    }
+
+    emit_cpi!(close_pool_event);
+
+    Ok(())
+}
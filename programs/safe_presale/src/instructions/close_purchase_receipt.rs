@@ -0,0 +1,130 @@
+use crate::error::CustomError;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{close_account, CloseAccount, TokenAccount, TokenInterface};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ClosePurchaseReceiptCtx<'info> {
+    #[account(
+        mut,
+        close = receipt_payer,
+        constraint = purchase_receipt.pool == pool.key() @CustomError::InvalidPool,
+        constraint = purchase_receipt.reclaimed || purchase_receipt.lp_elligible.is_some() @CustomError::CheckClaimFirstBeforeClaiming,
+        constraint = purchase_receipt.reclaimed || purchase_receipt.lp_elligible == Some(purchase_receipt.lp_claimed) @CustomError::EntitlementNotFullyClaimed,
+        constraint = purchase_receipt.reclaimed || purchase_receipt.mint_elligible == Some(purchase_receipt.mint_claimed) @CustomError::EntitlementNotFullyClaimed,
+        constraint = purchase_receipt.reclaimed || pool.load()?.current_status()? != PoolStatus::Cancelled || purchase_receipt.dissolved_lp_claimed @CustomError::EntitlementNotFullyClaimed,
+    )]
+    pub purchase_receipt: Box<Account<'info, PurchaseReceipt>>,
+
+    /// Not required for reclaimed or directly claimed receipts, they never had claim token accounts
+    #[account(
+        mut,
+        constraint = purchase_receipt_lp_token_account.owner == purchase_receipt.key(),
+        constraint = purchase_receipt_lp_token_account.amount == 0 @CustomError::TokenAccountNotEmpty,
+    )]
+    pub purchase_receipt_lp_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// Not required for reclaimed or directly claimed receipts, they never had claim token accounts
+    #[account(
+        mut,
+        constraint = purchase_receipt_reward_token_account.owner == purchase_receipt.key(),
+        constraint = purchase_receipt_reward_token_account.amount == 0 @CustomError::TokenAccountNotEmpty,
+    )]
+    pub purchase_receipt_reward_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    #[account(
+        mut,
+        constraint = !pool.load()?.frozen.get() @CustomError::PoolFrozen,
+    )]
+    pub pool: AccountLoader<'info, Pool>,
+
+    #[account(
+        seeds = [CONFIG_PREFIX.as_bytes()],
+        bump = config.bump,
+        constraint = !config.paused @CustomError::ProgramPaused,
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    ///CHECK: Receives the receipt rent, must be whoever paid for the purchase receipt
+    #[account(
+        mut,
+        address = purchase_receipt.payer,
+    )]
+    pub receipt_payer: AccountInfo<'info>,
+
+    ///CHECK: Receives the token account rent, must be whoever paid for the claim token accounts. Not required for reclaimed or directly claimed receipts
+    #[account(
+        mut,
+        constraint = purchase_receipt.claim_payer == Some(claim_payer.key()),
+    )]
+    pub claim_payer: Option<AccountInfo<'info>>,
+
+    pub payer: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+pub(crate) fn handler(ctx: Context<ClosePurchaseReceiptCtx>) -> Result<()> {
+    let purchase_receipt = &ctx.accounts.purchase_receipt;
+    let purchase_seed = &[
+        PURCHASE_RECEIPT_PREFIX.as_bytes(),
+        purchase_receipt.pool.as_ref(),
+        purchase_receipt.original_mint.as_ref(),
+        &[purchase_receipt.bump],
+    ];
+    let signer = &[&purchase_seed[..]];
+
+    if !purchase_receipt.reclaimed && !purchase_receipt.direct_claim {
+        let (
+            Some(purchase_receipt_lp_token_account),
+            Some(purchase_receipt_reward_token_account),
+            Some(claim_payer),
+        ) = (
+            &ctx.accounts.purchase_receipt_lp_token_account,
+            &ctx.accounts.purchase_receipt_reward_token_account,
+            &ctx.accounts.claim_payer,
+        )
+        else {
+            return Err(error!(CustomError::MissingClaimTokenAccounts));
+        };
+        for token_account in [
+            purchase_receipt_lp_token_account.to_account_info(),
+            purchase_receipt_reward_token_account.to_account_info(),
+        ] {
+            close_account(
+                CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    CloseAccount {
+                        account: token_account,
+                        destination: claim_payer.to_account_info(),
+                        authority: purchase_receipt.to_account_info(),
+                    },
+                )
+                .with_signer(signer),
+            )?;
+        }
+    }
+
+    let pool_key = ctx.accounts.pool.key();
+    let mut pool = ctx.accounts.pool.load_mut()?;
+    pool.purchase_receipt_count = pool
+        .purchase_receipt_count
+        .checked_sub(1)
+        .ok_or(CustomError::IntegerOverflow)?;
+    pool.purchase_receipts_checked = pool
+        .purchase_receipts_checked
+        .checked_sub(1)
+        .ok_or(CustomError::IntegerOverflow)?;
+
+    emit_cpi!(ClosePurchaseReceiptEvent {
+        payer: ctx.accounts.payer.key(),
+        pool: pool_key,
+        original_mint: purchase_receipt.original_mint,
+        purchase_receipt_count: pool.purchase_receipt_count,
+        purchase_receipts_checked: pool.purchase_receipts_checked,
+        event_seq: pool.next_event_seq(),
+    });
+
+    Ok(())
+}
@@ -16,11 +16,20 @@ pub struct CreatePurchaseAuthorizationCtx<'info> {
     pub purchase_authorisation_record: Box<Account<'info, PurchaseAuthorisationRecord>>,
 
     #[account(
-        constraint = pool.vesting_started_at.is_none() @CustomError::TokenHasLaunched,
-        constraint = pool.requires_collection,
-        constraint = pool.authority == payer.key(),
+        mut,
+        constraint = !pool.load()?.current_status()?.has_launched() @CustomError::TokenHasLaunched,
+        constraint = pool.load()?.requires_collection.get(),
+        constraint = pool.load()?.authority == payer.key(),
+        constraint = !pool.load()?.frozen.get() @CustomError::PoolFrozen,
     )]
-    pub pool: Box<Account<'info, Pool>>,
+    pub pool: AccountLoader<'info, Pool>,
+
+    #[account(
+        seeds = [CONFIG_PREFIX.as_bytes()],
+        bump = config.bump,
+        constraint = !config.paused @CustomError::ProgramPaused,
+    )]
+    pub config: Box<Account<'info, Config>>,
 
     #[account(mut)]
     pub payer: Signer<'info>,
@@ -29,7 +38,7 @@ pub struct CreatePurchaseAuthorizationCtx<'info> {
     pub system_program: Program<'info, System>,
 }
 
-pub fn handler(
+pub(crate) fn handler(
     ctx: Context<CreatePurchaseAuthorizationCtx>,
     collection_mint: Pubkey,
 ) -> Result<()> {
@@ -41,7 +50,8 @@ pub fn handler(
     emit_cpi!(CreatePurchaseAuthorisationEvent {
         payer: ctx.accounts.payer.key(),
         pool: ctx.accounts.pool.key(),
-        collection_mint: collection_mint,
+        collection_mint,
+        event_seq: ctx.accounts.pool.load_mut()?.next_event_seq(),
     });
 
     Ok(())
@@ -0,0 +1,46 @@
+use crate::error::CustomError;
+use crate::program::SafePresale;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct InitConfigCtx<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = CONFIG_SIZE,
+        seeds = [CONFIG_PREFIX.as_bytes()],
+        bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(
+        constraint = safe_presale_program.programdata_address()? == Some(program_data.key()),
+    )]
+    pub safe_presale_program: Program<'info, SafePresale>,
+
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(payer.key()) @CustomError::InvalidUpgradeAuthority,
+    )]
+    pub program_data: Box<Account<'info, ProgramData>>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub(crate) fn handler(ctx: Context<InitConfigCtx>, admin: Pubkey) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    config.bump = ctx.bumps.config;
+    config.admin = admin;
+    config.paused = false;
+
+    emit_cpi!(InitializedConfigEvent {
+        payer: ctx.accounts.payer.key(),
+        admin: config.admin,
+    });
+
+    Ok(())
+}
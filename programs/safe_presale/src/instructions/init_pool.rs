@@ -1,7 +1,20 @@
+use crate::{
+    error::CustomError,
+    state::{
+        Config, InitializedPoolEvent, Pool, PoolStatus, CONFIG_PREFIX, MINT_PREFIX, POOL_PREFIX,
+        POOL_SIZE, POOL_VERSION,
+    },
+    utils::Calculator,
+};
 use anchor_lang::prelude::*;
-use anchor_spl::{associated_token::AssociatedToken, token_2022::spl_token_2022::instruction::AuthorityType, token_interface::{mint_to, set_authority, Mint, MintTo, SetAuthority, TokenAccount, TokenInterface}};
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_2022::spl_token_2022::instruction::AuthorityType,
+    token_interface::{
+        mint_to, set_authority, Mint, MintTo, SetAuthority, TokenAccount, TokenInterface,
+    },
+};
 use mpl_token_metadata::{instructions::CreateMetadataAccountV3CpiBuilder, types::DataV2};
-use crate::{error::CustomError, state::{InitializedPoolEvent, Pool, MINT_PREFIX, POOL_PREFIX, POOL_SIZE}, utils::U128};
 
 #[derive(AnchorSerialize, AnchorDeserialize)]
 pub struct InitPoolArgs {
@@ -9,9 +22,15 @@ pub struct InitPoolArgs {
     pub symbol: String,
     pub uri: String,
     pub decimals: u8,
-    pub presale_target: u64, 
+    pub presale_target: u64,
     pub presale_duration: u32,
     pub vesting_period: u32,
+    pub reward_vesting_period: u32,
+    pub creator_reward_vesting_period: u32,
+    pub dissolve_quorum_basis_points: u16,
+    pub dissolve_vote_window: u32,
+    pub claim_period: Option<u32>,
+    pub reclaim_destination: Option<Pubkey>,
     pub max_amount_per_purchase: Option<u64>,
     pub liquidity_pool_supply: u64,
     pub initial_supply: u64,
@@ -42,15 +61,22 @@ pub struct InitPoolCtx<'info> {
         seeds = [POOL_PREFIX.as_bytes(), reward_mint.key().as_ref()],
         bump,
     )]
-    pub pool: Box<Account<'info, Pool>>,
+    pub pool: AccountLoader<'info, Pool>,
+
+    #[account(
+        seeds = [CONFIG_PREFIX.as_bytes()],
+        bump = config.bump,
+        constraint = !config.paused @CustomError::ProgramPaused,
+    )]
+    pub config: Box<Account<'info, Config>>,
 
     /// CHECK: Checked by cpi
     #[account(mut)]
     pub reward_mint_metadata: UncheckedAccount<'info>,
 
-    #[account( 
+    #[account(
         init_if_needed,
-        payer = payer,  
+        payer = payer,
         associated_token::mint = reward_mint,
         associated_token::authority = pool,
     )]
@@ -71,98 +97,145 @@ pub struct InitPoolCtx<'info> {
     pub mpl_token_program: AccountInfo<'info>,
 }
 
-pub fn handler(ctx: Context<InitPoolCtx>, args: InitPoolArgs) -> Result<()> {
-    require!(args.creator_fee_basis_points <= 10000, CustomError::CreatorBasisPointsExceedMaximumAmount);
+pub(crate) fn handler(ctx: Context<InitPoolCtx>, args: InitPoolArgs) -> Result<()> {
+    require!(
+        args.creator_fee_basis_points <= 10000,
+        CustomError::CreatorBasisPointsExceedMaximumAmount
+    );
+    require!(
+        args.dissolve_quorum_basis_points <= 10000,
+        CustomError::DissolveQuorumExceedMaximumAmount
+    );
+    require!(
+        args.dissolve_quorum_basis_points == 0 || args.dissolve_vote_window > 0,
+        CustomError::InvalidDissolveVoteWindow
+    );
+    require!(
+        args.claim_period != Some(0),
+        CustomError::InvalidClaimPeriod
+    );
 
-    let pool = &mut ctx.accounts.pool;
     let current_time = Clock::get()?.unix_timestamp;
+    let mut pool = ctx.accounts.pool.load_init()?;
+    pool.version = POOL_VERSION;
     pool.bump = ctx.bumps.pool;
     pool.mint = ctx.accounts.reward_mint.key();
     pool.authority = ctx.accounts.payer.key();
-    pool.liquidity_collected = 0;
-    pool.liquidity_pool_supply = args.liquidity_pool_supply.checked_mul(10u64.checked_pow(args.decimals.into()).unwrap()).unwrap();
-    pool.initial_supply = args.initial_supply.checked_mul(10u64.checked_pow(args.decimals.into()).unwrap()).unwrap();
-    pool.presale_time_limit = current_time.checked_add(args.presale_duration.try_into().unwrap()).ok_or(CustomError::IntegerOverflow)?;
+    pool.liquidity_pool_supply =
+        Calculator::to_base_units(args.liquidity_pool_supply, args.decimals)?;
+    pool.initial_supply = Calculator::to_base_units(args.initial_supply, args.decimals)?;
+    pool.presale_time_limit = current_time
+        .checked_add(args.presale_duration.into())
+        .ok_or(CustomError::IntegerOverflow)?;
     pool.vesting_period = args.vesting_period;
+    pool.reward_vesting_period = args.reward_vesting_period;
+    pool.creator_reward_vesting_period = args.creator_reward_vesting_period;
+    pool.dissolve_quorum_basis_points = args.dissolve_quorum_basis_points;
+    pool.dissolve_vote_window = args.dissolve_vote_window;
+    pool.payer = ctx.accounts.payer.key();
+    pool.claim_period = args.claim_period.into();
+    pool.reclaim_destination = args.reclaim_destination.into();
+    pool.status = PoolStatus::Upcoming as u8;
+    pool.receipts_tracked = true.into();
     pool.creator_fee_basis_points = args.creator_fee_basis_points;
     pool.presale_target = args.presale_target;
-    pool.delegate = args.delegate;
-    pool.max_amount_per_purchase = args.max_amount_per_purchase;
-    pool.requires_collection = args.requires_collection;
+    pool.delegate = args.delegate.into();
+    pool.max_amount_per_purchase = args.max_amount_per_purchase.into();
+    pool.requires_collection = args.requires_collection.into();
     pool.quote_mint = args.quote_mint;
-    pool.initial_supply_for_creator = U128::from(pool.initial_supply)
-    .checked_mul(args.creator_fee_basis_points.try_into().unwrap())
-    .and_then(|result| result.checked_div(U128::from(10000)))
-    .and_then(|result| Some(result.as_u64()))
-    .ok_or(CustomError::IntegerOverflow)?;
-
+    pool.initial_supply_for_creator =
+        Calculator::basis_points_of(pool.initial_supply, args.creator_fee_basis_points)?;
+    let total_supply = pool
+        .liquidity_pool_supply
+        .checked_add(pool.initial_supply)
+        .ok_or(CustomError::IntegerOverflow)?;
+    // The discriminator is only written on exit, the pool can not be loaded again after the CPIs
+    let initialized_pool_event = InitializedPoolEvent {
+        delegate: pool.delegate.get(),
+        authority: pool.authority,
+        pool: ctx.accounts.pool.key(),
+        mint: pool.mint,
+        decimal: args.decimals,
+        presale_target: pool.presale_target,
+        presale_time_limit: pool.presale_time_limit,
+        creator_fee_basis_points: pool.creator_fee_basis_points,
+        liquidity_pool_supply: pool.liquidity_pool_supply,
+        initial_supply: pool.initial_supply,
+        initial_supply_for_creator: pool.initial_supply_for_creator,
+        vesting_period: pool.vesting_period,
+        reward_vesting_period: pool.reward_vesting_period,
+        creator_reward_vesting_period: pool.creator_reward_vesting_period,
+        dissolve_quorum_basis_points: pool.dissolve_quorum_basis_points,
+        dissolve_vote_window: pool.dissolve_vote_window,
+        claim_period: pool.claim_period.get(),
+        reclaim_destination: pool.reclaim_destination.get(),
+        max_amount_per_purchase: pool.max_amount_per_purchase.get(),
+        requires_collection: args.requires_collection,
+        quote_mint: pool.quote_mint,
+        event_seq: pool.next_event_seq(),
+    };
+    let pool_bump = pool.bump;
+    drop(pool);
 
+    let reward_mint_key = ctx.accounts.reward_mint.key();
     let seeds = &[
         POOL_PREFIX.as_bytes(),
-        pool.mint.as_ref(),
-        &[pool.bump],
+        reward_mint_key.as_ref(),
+        &[pool_bump],
     ];
     let signer = &[&seeds[..]];
 
     //mint all supply to pool then revoke mint authority for token
     mint_to(
-        CpiContext::new(ctx.accounts.token_program.to_account_info(), MintTo {
-            mint: ctx.accounts.reward_mint.to_account_info(),
-            to: ctx
-                .accounts
-                .pool_reward_mint_token_account
-                .to_account_info(),
-            authority: pool.to_account_info(),
-        })
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            MintTo {
+                mint: ctx.accounts.reward_mint.to_account_info(),
+                to: ctx
+                    .accounts
+                    .pool_reward_mint_token_account
+                    .to_account_info(),
+                authority: ctx.accounts.pool.to_account_info(),
+            },
+        )
         .with_signer(signer),
-        pool.liquidity_pool_supply.checked_add(pool.initial_supply).ok_or(CustomError::IntegerOverflow)?
+        total_supply,
     )?;
 
     CreateMetadataAccountV3CpiBuilder::new(&ctx.accounts.mpl_token_program.to_account_info())
-    .system_program(&ctx.accounts.system_program.to_account_info())
-    .mint(&ctx.accounts.reward_mint.to_account_info())
-    .mint_authority(&pool.to_account_info())
-    .metadata(&ctx.accounts.reward_mint_metadata.to_account_info())
-    .is_mutable(false)
-    .payer(&ctx.accounts.payer)
-    .update_authority(&ctx.accounts.payer,true)
-    .data(DataV2{
-        name: args.name,
-        symbol: args.symbol,
-        uri: args.uri,
-        seller_fee_basis_points: 0,
-        creators: None,
-        collection: None,
-        uses: None,
-    }).invoke_signed(signer)?;
+        .system_program(&ctx.accounts.system_program.to_account_info())
+        .mint(&ctx.accounts.reward_mint.to_account_info())
+        .mint_authority(&ctx.accounts.pool.to_account_info())
+        .metadata(&ctx.accounts.reward_mint_metadata.to_account_info())
+        .is_mutable(true)
+        .payer(&ctx.accounts.payer)
+        .update_authority(&ctx.accounts.pool.to_account_info(), true)
+        .data(DataV2 {
+            name: args.name,
+            symbol: args.symbol,
+            uri: args.uri,
+            seller_fee_basis_points: 0,
+            creators: None,
+            collection: None,
+            uses: None,
+        })
+        .invoke_signed(signer)?;
 
     //set mint authority to none
-    set_authority(CpiContext::new(ctx.accounts.token_program.to_account_info(), SetAuthority{
-        current_authority: pool.to_account_info(),
-        account_or_mint: ctx.accounts.reward_mint.to_account_info(),
-    }).with_signer(signer), 
-    AuthorityType::MintTokens, 
-    None
+    set_authority(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            SetAuthority {
+                current_authority: ctx.accounts.pool.to_account_info(),
+                account_or_mint: ctx.accounts.reward_mint.to_account_info(),
+            },
+        )
+        .with_signer(signer),
+        AuthorityType::MintTokens,
+        None,
     )?;
 
-    // Emit the Initialzed pool event
-    emit_cpi!(InitializedPoolEvent {
-        delegate: pool.delegate,
-        authority: pool.authority,
-        pool: pool.key(),
-        mint: pool.mint,
-        decimal: args.decimals,
-        presale_target: pool.presale_target,
-        presale_time_limit: pool.presale_time_limit,
-        creator_fee_basis_points: pool.creator_fee_basis_points,
-        liquidity_pool_supply: pool.liquidity_pool_supply,
-        initial_supply: pool.initial_supply,
-        initial_supply_for_creator: pool.initial_supply_for_creator,
-        vesting_period: pool.vesting_period,
-        max_amount_per_purchase: pool.max_amount_per_purchase,
-        requires_collection: args.requires_collection,
-        quote_mint: pool.quote_mint,
-    });
+    emit_cpi!(initialized_pool_event);
 
     Ok(())
 }
@@ -2,7 +2,7 @@ use std::str::FromStr;
 
 use crate::error::CustomError;
 use crate::state::*;
-use crate::utils::U128;
+use crate::utils::{lock_metadata, Calculator, CpmmInitializeAccounts};
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program;
 use anchor_spl::associated_token;
@@ -16,14 +16,22 @@ use anchor_spl::{
 #[derive(Accounts)]
 pub struct LaunchTokenAmmCtx<'info> {
     #[account(mut,
-        constraint = pool.presale_target == pool.liquidity_collected @CustomError::PresaleTargetNotMet,
-        constraint = pool.vesting_started_at.is_none() @CustomError::TokenHasLaunched,
-        constraint = pool.mint == amm_coin_mint.key(),
+        constraint = pool.load()?.reward_supply_burned_at.is_none() @CustomError::RewardSupplyBurned,
+        constraint = pool.load()?.mint == amm_coin_mint.key(),
+        constraint = !pool.load()?.frozen.get() @CustomError::PoolFrozen,
     )]
-    pub pool: Box<Account<'info, Pool>>,
+    pub pool: AccountLoader<'info, Pool>,
+
+    #[account(
+        seeds = [CONFIG_PREFIX.as_bytes()],
+        bump = config.bump,
+        constraint = !config.paused @CustomError::ProgramPaused,
+    )]
+    pub config: Box<Account<'info, Config>>,
+
     /// Pays to mint the position
     #[account(mut,
-        constraint = pool.authority == user_wallet.key() || (pool.delegate.is_some() && pool.delegate.unwrap() == user_wallet.key()),
+        constraint = pool.load()?.authority == user_wallet.key() || pool.load()?.delegate.get() == Some(user_wallet.key()),
     )]
     pub user_wallet: Signer<'info>,
     #[account(
@@ -64,7 +72,7 @@ pub struct LaunchTokenAmmCtx<'info> {
     pub amm_coin_mint: Box<InterfaceAccount<'info, Mint>>,
     /// CHECK: Checked by cpi
     #[account(
-        constraint = amm_pc_mint.key() == pool.quote_mint,
+        constraint = amm_pc_mint.key() == pool.load()?.quote_mint,
     )]
     pub amm_pc_mint: Box<InterfaceAccount<'info, Mint>>,
     /// CHECK: Checked by cpi
@@ -72,48 +80,89 @@ pub struct LaunchTokenAmmCtx<'info> {
         address = Pubkey::from_str(RAYDIUM_CPMM_V4_MAINNET).unwrap()
     )]
     pub raydium_amm_program: AccountInfo<'info>,
+    /// CHECK: Checked by cpi, made immutable at launch unless the first purchase already did
+    #[account(
+        mut,
+        seeds = ["metadata".as_bytes(), mpl_token_metadata::ID.as_ref(), amm_coin_mint.key().as_ref()],
+        bump,
+        seeds::program = mpl_token_metadata::ID
+    )]
+    pub reward_mint_metadata: UncheckedAccount<'info>,
+    /// CHECK: Checked by cpi
+    #[account(address = mpl_token_metadata::ID)]
+    pub mpl_token_program: AccountInfo<'info>,
 }
-pub fn handler<'a, 'b, 'c: 'info, 'info>(
+pub(crate) fn handler<'a, 'b, 'c: 'info, 'info>(
     ctx: Context<'a, 'b, 'c, 'info, LaunchTokenAmmCtx<'info>>,
     open_time: u64,
 ) -> Result<()> {
     let current_time = Clock::get()?.unix_timestamp;
-    let pool = &mut ctx.accounts.pool;
-    let remaining_accounts = ctx.remaining_accounts.as_ref();
+    let pool_key = ctx.accounts.pool.key();
+    let mut pool = ctx.accounts.pool.load_mut()?;
+    let remaining_accounts = ctx.remaining_accounts;
     let pool_token_pc = ctx.accounts.pool_token_pc.as_ref();
     let pool_token_coin = ctx.accounts.pool_token_coin.as_ref();
     let user_token_coin = ctx.accounts.user_token_coin.as_ref();
     let user_token_pc = ctx.accounts.user_token_pc.as_ref();
-    let amm_lp_mint = remaining_accounts.get(0).unwrap().to_account_info();
-    let user_token_lp = remaining_accounts.get(1).unwrap().to_account_info();
-    let pool_token_lp = remaining_accounts.get(2).unwrap().to_account_info();
+    let cpmm_accounts = CpmmInitializeAccounts::from_remaining_accounts(remaining_accounts)?;
+    let amm_lp_mint = cpmm_accounts.lp_mint.clone();
+    let user_token_lp = cpmm_accounts.user_token_lp.clone();
+    let pool_token_lp = cpmm_accounts.pool_token_lp.clone();
     let user_wallet = ctx.accounts.user_wallet.as_ref();
     let system_program = ctx.accounts.system_program.as_ref();
     let associated_token_program = ctx.accounts.associated_token_program.as_ref();
     let token_program = ctx.accounts.token_program.as_ref();
     // Launch Criteria
-    // 1. Only allow launch after presale has ended
-    // 2. Do not allow project to launch after the 7 day grace period
-    // 3. Presale target must be met
+    // 1. Presale target must be met and the 7 day grace period not over
+    // 2. Only allow launch after presale has ended
+    // 3. The reward mint metadata can't be changed once the token trades
+    match pool.status_at(current_time) {
+        PoolStatus::Filled => {}
+        PoolStatus::Upcoming | PoolStatus::Open | PoolStatus::Failed => {
+            return Err(error!(CustomError::PresaleTargetNotMet))
+        }
+        PoolStatus::Expired => return Err(error!(CustomError::PoolHasExpired)),
+        PoolStatus::Launched | PoolStatus::Cancelled => {
+            return Err(error!(CustomError::TokenHasLaunched))
+        }
+    }
     if current_time < pool.presale_time_limit {
         return Err(error!(CustomError::UnauthorizedAtCurrentTime));
     }
-    if pool.presale_time_limit + GRACE_PERIOD < current_time {
-        return Err(error!(CustomError::PoolHasExpired));
-    }
-    pool.vesting_started_at = Some(current_time);
-    pool.lp_mint = Some(amm_lp_mint.key());
-
-    let pool_seed = &[POOL_PREFIX.as_bytes(), pool.mint.as_ref(), &[pool.bump]];
-    let signer = &[&pool_seed[..]];
+    pool.vesting_started_at = Some(current_time).into();
+    pool.claim_deadline = pool
+        .claim_period
+        .get()
+        .map(|claim_period| {
+            current_time
+                .checked_add(claim_period.into())
+                .ok_or(CustomError::IntegerOverflow)
+        })
+        .transpose()?
+        .into();
+    pool.lp_mint = Some(amm_lp_mint.key()).into();
+    pool.launch_payer = Some(user_wallet.key()).into();
+    let status_changed = pool.transition(pool_key, current_time);
     let amount_coin_in_pool = pool.liquidity_pool_supply;
     let amount_pc_in_pool = pool.liquidity_collected;
 
+    let (pool_mint, pool_bump) = (pool.mint, pool.bump);
+    drop(pool);
+    let pool_seed = &[POOL_PREFIX.as_bytes(), pool_mint.as_ref(), &[pool_bump]];
+    let signer = &[&pool_seed[..]];
+
+    lock_metadata(
+        ctx.accounts.mpl_token_program.to_account_info(),
+        ctx.accounts.reward_mint_metadata.to_account_info(),
+        ctx.accounts.pool.to_account_info(),
+        signer,
+    )?;
+
     transfer_amount(
         token_program.to_account_info(),
         pool_token_coin.to_account_info(),
         user_token_coin.to_account_info(),
-        pool.to_account_info(),
+        ctx.accounts.pool.to_account_info(),
         ctx.accounts.amm_coin_mint.to_account_info(),
         signer,
         amount_coin_in_pool,
@@ -123,7 +172,7 @@ pub fn handler<'a, 'b, 'c: 'info, 'info>(
         token_program.to_account_info(),
         pool_token_pc.to_account_info(),
         user_token_pc.to_account_info(),
-        pool.to_account_info(),
+        ctx.accounts.pool.to_account_info(),
         ctx.accounts.amm_pc_mint.to_account_info(),
         signer,
         amount_pc_in_pool,
@@ -153,19 +202,19 @@ pub fn handler<'a, 'b, 'c: 'info, 'info>(
     };
     cpi_initialize(
         user_wallet.to_account_info(),
-        remaining_accounts.get(3).unwrap().to_account_info(),
-        remaining_accounts.get(4).unwrap().to_account_info(),
-        remaining_accounts.get(5).unwrap().to_account_info(),
+        cpmm_accounts.amm_config,
+        cpmm_accounts.amm_authority,
+        cpmm_accounts.amm_pool,
         token_0_mint,
         token_1_mint,
         amm_lp_mint.to_account_info(),
         user_token_0_mint.to_account_info(),
         user_token_1_mint.to_account_info(),
         user_token_lp.to_account_info(),
-        remaining_accounts.get(6).unwrap().to_account_info(),
-        remaining_accounts.get(7).unwrap().to_account_info(),
-        remaining_accounts.get(8).unwrap().to_account_info(),
-        remaining_accounts.get(9).unwrap().to_account_info(),
+        cpmm_accounts.token_0_vault,
+        cpmm_accounts.token_1_vault,
+        cpmm_accounts.create_fee_destination,
+        cpmm_accounts.observation_state,
         token_program.to_account_info(),
         token_program.to_account_info(),
         token_program.to_account_info(),
@@ -178,19 +227,12 @@ pub fn handler<'a, 'b, 'c: 'info, 'info>(
         open_time,
     )?;
 
-    let liquidity = U128::from(init_0_amount)
-        .checked_mul(init_1_amount.into())
-        .unwrap()
-        .integer_sqrt()
-        .as_u64();
-    let lock_lp_amount = 100;
-    let user_lp_amount = liquidity.checked_sub(lock_lp_amount).unwrap();
+    let user_lp_amount = Calculator::lp_tokens_received(init_0_amount, init_1_amount)?;
 
-    pool.lp_mint_supply = Some(user_lp_amount);
     transfer_lp_token(
         user_wallet.to_account_info(),
         associated_token_program.to_account_info(),
-        pool.to_account_info(),
+        ctx.accounts.pool.to_account_info(),
         amm_lp_mint.to_account_info(),
         system_program.to_account_info(),
         token_program.to_account_info(),
@@ -200,27 +242,33 @@ pub fn handler<'a, 'b, 'c: 'info, 'info>(
         9,
     )?;
 
-    pool.lp_mint_supply_for_creator = Some(
-        U128::from(user_lp_amount)
-            .checked_mul(pool.creator_fee_basis_points.try_into().unwrap())
-            .and_then(|result| result.checked_div(U128::from(10000)))
-            .and_then(|result| Some(result.as_u64()))
-            .ok_or(CustomError::IntegerOverflow)?,
-    );
+    let mut pool = ctx.accounts.pool.load_mut()?;
+    let lp_mint_supply_for_creator =
+        Calculator::basis_points_of(user_lp_amount, pool.creator_fee_basis_points)?;
+    pool.lp_mint_supply = Some(user_lp_amount).into();
+    pool.lp_mint_supply_for_creator = Some(lp_mint_supply_for_creator).into();
 
+    if let Some(status_changed) = status_changed {
+        emit_cpi!(status_changed);
+    }
     emit_cpi!(LaunchTokenAmmEvent {
         payer: user_wallet.key(),
-        pool: pool.key(),
+        pool: pool_key,
         amount_coin: amount_coin_in_pool,
         amount_pc: amount_pc_in_pool,
         amount_lp_received: user_lp_amount,
-        lp_mint: pool.lp_mint.unwrap(),
-        vesting_started_at: pool.vesting_started_at.unwrap(),
+        lp_mint: amm_lp_mint.key(),
+        vesting_started_at: current_time,
+        claim_deadline: pool.claim_deadline.get(),
+        lp_mint_supply: user_lp_amount,
+        lp_mint_supply_for_creator,
+        event_seq: pool.next_event_seq(),
     });
     Ok(())
 }
 
 #[inline(never)]
+#[allow(clippy::too_many_arguments)]
 fn transfer_lp_token<'info>(
     user_wallet: AccountInfo<'info>,
     associated_token_program: AccountInfo<'info>,
@@ -261,6 +309,7 @@ fn transfer_lp_token<'info>(
 }
 
 #[inline(never)]
+#[allow(clippy::too_many_arguments)]
 fn cpi_initialize<'a, 'b, 'c: 'info, 'info>(
     user_wallet: AccountInfo<'info>,
     amm_config: AccountInfo<'info>,
@@ -338,18 +387,19 @@ fn cpi_initialize<'a, 'b, 'c: 'info, 'info>(
         rent,
     ];
 
-    let _invoke = solana_program::program::invoke(
+    solana_program::program::invoke(
         &solana_program::instruction::Instruction {
             program_id: amm_program.key(),
             accounts,
             data: bytes_data,
         },
         &account_infos[..],
-    );
+    )?;
     Ok(())
 }
 
 #[inline(never)]
+#[allow(clippy::too_many_arguments)]
 fn transfer_amount<'info>(
     token_program: AccountInfo<'info>,
     from: AccountInfo<'info>,
@@ -0,0 +1,57 @@
+use crate::error::CustomError;
+use crate::state::*;
+use crate::utils::{legacy_account, realloc_account};
+use anchor_lang::{prelude::*, Discriminator};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct MigratePoolCtx<'info> {
+    /// CHECK: Earlier pool layout, checked by discriminator and size in the handler
+    #[account(
+        mut,
+        owner = crate::ID,
+    )]
+    pub pool: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [CONFIG_PREFIX.as_bytes()],
+        bump = config.bump,
+        constraint = !config.paused @CustomError::ProgramPaused,
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub(crate) fn handler(ctx: Context<MigratePoolCtx>) -> Result<()> {
+    // Migrate Pool Criteria
+    // 1. Pool is stored with the unversioned layout
+    // 2. Payer covers the rent of the grown account
+    let pool_info = ctx.accounts.pool.to_account_info();
+    let now = Clock::get()?.unix_timestamp;
+    let legacy_pool: LegacyPool =
+        legacy_account(&pool_info, Pool::DISCRIMINATOR, POOL_SIZE, LEGACY_POOL_SIZE)?;
+    let mut pool = legacy_pool.migrate(now);
+    let event_seq = pool.next_event_seq();
+
+    realloc_account(
+        &pool_info,
+        &ctx.accounts.payer.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        POOL_SIZE,
+    )?;
+    pool_info.try_borrow_mut_data()?[8..].copy_from_slice(bytemuck::bytes_of(&pool));
+
+    emit_cpi!(MigratePoolEvent {
+        pool: pool_info.key(),
+        version: pool.version,
+        status: pool.status(),
+        payer: ctx.accounts.payer.key(),
+        event_seq,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,98 @@
+use crate::error::CustomError;
+use crate::state::*;
+use crate::utils::{legacy_account, realloc_account};
+use anchor_lang::{prelude::*, Discriminator};
+use anchor_spl::token_interface::TokenAccount;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct MigrateReceiptCtx<'info> {
+    /// CHECK: Unversioned purchase receipt layout, checked by discriminator and size in the handler
+    #[account(
+        mut,
+        owner = crate::ID,
+    )]
+    pub purchase_receipt: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = pool.load()?.version == POOL_VERSION @CustomError::InvalidAccountLayout,
+    )]
+    pub pool: AccountLoader<'info, Pool>,
+
+    #[account(
+        seeds = [CONFIG_PREFIX.as_bytes()],
+        bump = config.bump,
+        constraint = !config.paused @CustomError::ProgramPaused,
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(
+        constraint = nft_owner_nft_token_account.amount == 1,
+        constraint = nft_owner_nft_token_account.owner == payer.key() @CustomError::InvalidSigner,
+    )]
+    pub nft_owner_nft_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub(crate) fn handler(ctx: Context<MigrateReceiptCtx>) -> Result<()> {
+    // Migrate Receipt Criteria
+    // 1. Purchase receipt is stored with the unversioned layout
+    // 2. Purchase receipt belongs to the pool, which is already migrated
+    // 3. Payer holds the NFT of the purchase receipt and becomes its rent recipient
+    let purchase_receipt_info = ctx.accounts.purchase_receipt.to_account_info();
+    let legacy_purchase_receipt: LegacyPurchaseReceipt = legacy_account(
+        &purchase_receipt_info,
+        PurchaseReceipt::DISCRIMINATOR,
+        PURCHASE_RECEIPT_SIZE,
+        LEGACY_PURCHASE_RECEIPT_SIZE,
+    )?;
+    require!(
+        legacy_purchase_receipt.pool == ctx.accounts.pool.key(),
+        CustomError::InvalidPool
+    );
+    require!(
+        legacy_purchase_receipt.original_mint == ctx.accounts.nft_owner_nft_token_account.mint,
+        CustomError::MintNotAllowed
+    );
+    let purchase_receipt = legacy_purchase_receipt.migrate(ctx.accounts.payer.key());
+
+    let mut pool = ctx.accounts.pool.load_mut()?;
+    pool.purchase_receipt_count = pool
+        .purchase_receipt_count
+        .checked_add(1)
+        .ok_or(CustomError::IntegerOverflow)?;
+    if let (Some(lp_elligible), Some(mint_elligible)) = (
+        purchase_receipt.lp_elligible,
+        purchase_receipt.mint_elligible,
+    ) {
+        pool.record_checked_receipt(purchase_receipt.amount, lp_elligible, mint_elligible)?;
+    }
+
+    realloc_account(
+        &purchase_receipt_info,
+        &ctx.accounts.payer.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        PURCHASE_RECEIPT_SIZE,
+    )?;
+    purchase_receipt.try_serialize(&mut &mut purchase_receipt_info.try_borrow_mut_data()?[..])?;
+
+    emit_cpi!(MigratePurchaseReceiptEvent {
+        purchase_receipt: purchase_receipt_info.key(),
+        pool: purchase_receipt.pool,
+        version: purchase_receipt.version,
+        original_mint: purchase_receipt.original_mint,
+        payer: ctx.accounts.payer.key(),
+        purchase_receipt_count: pool.purchase_receipt_count,
+        lp_mint_distributed: pool.lp_mint_distributed,
+        initial_supply_distributed: pool.initial_supply_distributed,
+        purchase_receipts_checked: pool.purchase_receipts_checked,
+        event_seq: pool.next_event_seq(),
+    });
+
+    Ok(())
+}
@@ -1,21 +1,75 @@
+pub mod accept_authority;
+pub mod add_staking_program;
+pub mod approve_claim;
+pub mod batch_claim_reward_token;
+pub mod batch_withdraw;
+pub mod batch_withdraw_lp_tokens;
+pub mod burn_reward_supply;
 pub mod buy_presale;
 pub mod check_claim_elligibility;
+pub mod claim_all;
+pub mod claim_dissolved_lp_tokens;
 pub mod claim_reward_token;
 pub mod claim_reward_token_for_creator;
+pub mod close_pool;
+pub mod close_purchase_receipt;
 pub mod create_purchase_authorisation;
+pub mod init_config;
 pub mod init_pool;
 pub mod launch_token_amm;
+pub mod migrate_pool;
+pub mod migrate_receipt;
+pub mod propose_authority;
+pub mod quote_pool;
+pub mod quote_purchase_receipt;
+pub mod reclaim_purchase_receipt;
+pub mod remove_staking_program;
+pub mod revoke_claim_approval;
+pub mod set_delegate;
+pub mod set_paused;
+pub mod set_pool_frozen;
+pub mod sweep_dust;
+pub mod sync_pool_status;
+pub mod update_pool;
+pub mod vote_dissolve;
 pub mod withdraw;
 pub mod withdraw_lp_tokens;
 pub mod withdraw_lp_tokens_for_creator;
 
+pub use accept_authority::*;
+pub use add_staking_program::*;
+pub use approve_claim::*;
+pub use batch_claim_reward_token::*;
+pub use batch_withdraw::*;
+pub use batch_withdraw_lp_tokens::*;
+pub use burn_reward_supply::*;
 pub use buy_presale::*;
 pub use check_claim_elligibility::*;
+pub use claim_all::*;
+pub use claim_dissolved_lp_tokens::*;
 pub use claim_reward_token::*;
 pub use claim_reward_token_for_creator::*;
+pub use close_pool::*;
+pub use close_purchase_receipt::*;
 pub use create_purchase_authorisation::*;
+pub use init_config::*;
 pub use init_pool::*;
 pub use launch_token_amm::*;
+pub use migrate_pool::*;
+pub use migrate_receipt::*;
+pub use propose_authority::*;
+pub use quote_pool::*;
+pub use quote_purchase_receipt::*;
+pub use reclaim_purchase_receipt::*;
+pub use remove_staking_program::*;
+pub use revoke_claim_approval::*;
+pub use set_delegate::*;
+pub use set_paused::*;
+pub use set_pool_frozen::*;
+pub use sweep_dust::*;
+pub use sync_pool_status::*;
+pub use update_pool::*;
+pub use vote_dissolve::*;
 pub use withdraw::*;
 pub use withdraw_lp_tokens::*;
 pub use withdraw_lp_tokens_for_creator::*;
@@ -0,0 +1,42 @@
+use crate::error::CustomError;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ProposeAuthorityCtx<'info> {
+    #[account(
+        mut,
+        constraint = pool.load()?.authority == authority.key() @CustomError::InvalidSigner,
+        constraint = !pool.load()?.frozen.get() @CustomError::PoolFrozen,
+    )]
+    pub pool: AccountLoader<'info, Pool>,
+
+    #[account(
+        seeds = [CONFIG_PREFIX.as_bytes()],
+        bump = config.bump,
+        constraint = !config.paused @CustomError::ProgramPaused,
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    pub authority: Signer<'info>,
+}
+
+pub(crate) fn handler(
+    ctx: Context<ProposeAuthorityCtx>,
+    new_authority: Option<Pubkey>,
+) -> Result<()> {
+    // The transfer only completes once the new authority signs `accept_authority`, so a
+    // mistyped key can be overwritten or cleared (None) by the current authority.
+    let mut pool = ctx.accounts.pool.load_mut()?;
+    pool.pending_authority = new_authority.into();
+
+    emit_cpi!(AuthorityTransferProposedEvent {
+        authority: ctx.accounts.authority.key(),
+        pool: ctx.accounts.pool.key(),
+        pending_authority: pool.pending_authority.get(),
+        event_seq: pool.next_event_seq(),
+    });
+
+    Ok(())
+}
@@ -0,0 +1,45 @@
+use crate::state::*;
+use crate::utils::VestingSchedule;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct QuotePoolCtx<'info> {
+    pub pool: AccountLoader<'info, Pool>,
+}
+
+pub(crate) fn handler(ctx: Context<QuotePoolCtx>) -> Result<PoolQuote> {
+    let pool = ctx.accounts.pool.load()?;
+    let current_time = Clock::get()?.unix_timestamp;
+
+    let mut quote = PoolQuote {
+        status: pool.status_at(current_time),
+        liquidity_collected: pool.liquidity_collected,
+        presale_target: pool.presale_target,
+        presale_time_limit: pool.presale_time_limit,
+        vesting_started_at: pool.vesting_started_at.get(),
+        claim_deadline: pool.claim_deadline.get(),
+        creator_lp_claimable: 0,
+        creator_mint_claimable: 0,
+    };
+    let Some(vesting_started_at) = pool.vesting_started_at.get() else {
+        return Ok(quote);
+    };
+
+    // Dissolved pools hand the creator's unvested lp tokens to the contributors.
+    if pool.dissolved_at.is_none() {
+        quote.creator_lp_claimable = VestingSchedule::new(
+            vesting_started_at,
+            pool.vesting_period,
+            pool.lp_mint_supply_for_creator.get().unwrap(),
+        )
+        .claimable(current_time, pool.lp_mint_claimed_by_creator)?;
+    }
+    quote.creator_mint_claimable = VestingSchedule::new(
+        vesting_started_at,
+        pool.creator_reward_vesting_period,
+        pool.initial_supply_for_creator,
+    )
+    .claimable(current_time, pool.initial_supply_claimed_by_creator)?;
+
+    Ok(quote)
+}
@@ -0,0 +1,78 @@
+use crate::error::CustomError;
+use crate::state::*;
+use crate::utils::VestingSchedule;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct QuotePurchaseReceiptCtx<'info> {
+    #[account(
+        constraint = purchase_receipt.pool == pool.key() @CustomError::InvalidPool,
+    )]
+    pub purchase_receipt: Box<Account<'info, PurchaseReceipt>>,
+
+    pub pool: AccountLoader<'info, Pool>,
+}
+
+pub(crate) fn handler(ctx: Context<QuotePurchaseReceiptCtx>) -> Result<PurchaseReceiptQuote> {
+    let pool = ctx.accounts.pool.load()?;
+    let purchase_receipt = &ctx.accounts.purchase_receipt;
+    let current_time = Clock::get()?.unix_timestamp;
+    // Quote Criteria
+    // 1. Nothing is mutated, amounts are what the claim instructions would pay if sent now.
+    // 2. Unchecked receipts are quoted with the entitlement check_claim_elligibility would settle, until the claim deadline.
+    let refundable = if pool.check_refundable(current_time).is_ok() {
+        purchase_receipt.amount
+    } else {
+        0
+    };
+
+    let mut quote = PurchaseReceiptQuote {
+        status: pool.status_at(current_time),
+        refundable,
+        lp_elligible: 0,
+        mint_elligible: 0,
+        lp_claimable: 0,
+        mint_claimable: 0,
+        dissolved_lp_claimable: 0,
+    };
+    let Some(vesting_started_at) = pool.vesting_started_at.get() else {
+        return Ok(quote);
+    };
+    if purchase_receipt.reclaimed {
+        return Ok(quote);
+    }
+
+    let claim_deadline_passed = pool
+        .claim_deadline
+        .get()
+        .is_some_and(|claim_deadline| current_time >= claim_deadline);
+    match (
+        purchase_receipt.lp_elligible,
+        purchase_receipt.mint_elligible,
+    ) {
+        (Some(lp_elligible), Some(mint_elligible)) => {
+            quote.lp_elligible = lp_elligible;
+            quote.mint_elligible = mint_elligible;
+        }
+        _ if !claim_deadline_passed => {
+            quote.lp_elligible = pool.lp_entitlement(purchase_receipt.amount)?;
+            quote.mint_elligible = pool.mint_entitlement(purchase_receipt.amount)?;
+        }
+        _ => return Ok(quote),
+    }
+
+    quote.lp_claimable =
+        VestingSchedule::new(vesting_started_at, pool.vesting_period, quote.lp_elligible)
+            .claimable(current_time, purchase_receipt.lp_claimed)?;
+    quote.mint_claimable = VestingSchedule::new(
+        vesting_started_at,
+        pool.reward_vesting_period,
+        quote.mint_elligible,
+    )
+    .claimable(current_time, purchase_receipt.mint_claimed)?;
+    if pool.dissolved_at.is_some() && !purchase_receipt.dissolved_lp_claimed {
+        quote.dissolved_lp_claimable = pool.dissolved_lp_entitlement(purchase_receipt.amount)?;
+    }
+
+    Ok(quote)
+}
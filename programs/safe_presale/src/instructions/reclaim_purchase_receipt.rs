@@ -0,0 +1,161 @@
+use crate::error::CustomError;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ReclaimPurchaseReceiptCtx<'info> {
+    #[account(
+        mut,
+        constraint = purchase_receipt.pool == pool.key() @CustomError::InvalidPool,
+        constraint = purchase_receipt.lp_elligible.is_none() @CustomError::ClaimedAlreadyChecked,
+        constraint = !purchase_receipt.reclaimed @CustomError::ReceiptReclaimed,
+    )]
+    pub purchase_receipt: Box<Account<'info, PurchaseReceipt>>,
+
+    #[account(
+        mut,
+        constraint = pool.load()?.current_status()?.has_launched() @CustomError::PresaleIsStillOngoing,
+        constraint = authority.key() == pool.load()?.authority || pool.load()?.reclaim_destination.get() == Some(authority.key()) @CustomError::InvalidSigner,
+        constraint = !pool.load()?.frozen.get() @CustomError::PoolFrozen,
+    )]
+    pub pool: AccountLoader<'info, Pool>,
+
+    #[account(
+        seeds = [CONFIG_PREFIX.as_bytes()],
+        bump = config.bump,
+        constraint = !config.paused @CustomError::ProgramPaused,
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(
+        mut,
+        constraint = pool_lp_token_account.owner == pool.key(),
+        constraint = pool_lp_token_account.mint == lp_mint.key(),
+    )]
+    pub pool_lp_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = pool_reward_token_account.owner == pool.key(),
+        constraint = pool_reward_token_account.mint == reward_mint.key(),
+    )]
+    pub pool_reward_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = destination_lp_token_account.owner == pool.load()?.reclaim_wallet() @CustomError::InvalidSigner,
+        constraint = destination_lp_token_account.mint == lp_mint.key(),
+    )]
+    pub destination_lp_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = destination_reward_token_account.owner == pool.load()?.reclaim_wallet() @CustomError::InvalidSigner,
+        constraint = destination_reward_token_account.mint == reward_mint.key(),
+    )]
+    pub destination_reward_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        constraint = pool.load()?.lp_mint.get() == Some(lp_mint.key()) @CustomError::InvalidLpMint,
+    )]
+    pub lp_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        constraint = reward_mint.key() == pool.load()?.mint @CustomError::InvalidRewardMint,
+    )]
+    pub reward_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    pub authority: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+pub(crate) fn handler(ctx: Context<ReclaimPurchaseReceiptCtx>) -> Result<()> {
+    let pool_key = ctx.accounts.pool.key();
+    let mut pool = ctx.accounts.pool.load_mut()?;
+    let purchase_receipt = &mut ctx.accounts.purchase_receipt;
+    // Reclaim Criteria
+    // 1. The pool must have a claim deadline and it must have passed.
+    // 2. Only receipts that never checked their elligibility can be reclaimed, the receipt is flagged so it can never claim afterwards.
+    // 3. The allocation goes to the configured reclaim destination, or the creator when none is set.
+    let claim_deadline = pool
+        .claim_deadline
+        .get()
+        .ok_or(CustomError::ClaimDeadlineNotReached)?;
+    require!(
+        Clock::get()?.unix_timestamp >= claim_deadline,
+        CustomError::ClaimDeadlineNotReached
+    );
+
+    let lp_reclaimed = pool.lp_entitlement(purchase_receipt.amount)?;
+    let mint_reclaimed = pool.mint_entitlement(purchase_receipt.amount)?;
+    purchase_receipt.reclaimed = true;
+
+    // Reclaimed allocations count as distributed so the rounding dust can still be swept.
+    pool.record_checked_receipt(purchase_receipt.amount, lp_reclaimed, mint_reclaimed)?;
+    // Reclaimed receipts can't claim the dissolved lp tokens, their share is left to the sweep.
+    if !purchase_receipt.dissolved_lp_claimed {
+        pool.record_dissolved_lp_claim(purchase_receipt.amount, 0)?;
+    }
+    let destination = pool.reclaim_wallet();
+
+    let reclaim_event = ReclaimPurchaseReceiptEvent {
+        payer: ctx.accounts.authority.key(),
+        pool: pool_key,
+        original_mint: purchase_receipt.original_mint,
+        destination,
+        lp_reclaimed,
+        mint_reclaimed,
+        lp_mint_distributed: pool.lp_mint_distributed,
+        initial_supply_distributed: pool.initial_supply_distributed,
+        purchase_receipts_checked: pool.purchase_receipts_checked,
+        event_seq: pool.next_event_seq(),
+    };
+    let (pool_mint, pool_bump) = (pool.mint, pool.bump);
+    drop(pool);
+    let pool_seed = &[POOL_PREFIX.as_bytes(), pool_mint.as_ref(), &[pool_bump]];
+    let signer = &[&pool_seed[..]];
+
+    //transfer lp
+    transfer_checked(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                mint: ctx.accounts.lp_mint.to_account_info(),
+                from: ctx.accounts.pool_lp_token_account.to_account_info(),
+                to: ctx.accounts.destination_lp_token_account.to_account_info(),
+                authority: ctx.accounts.pool.to_account_info(),
+            },
+        )
+        .with_signer(signer),
+        lp_reclaimed,
+        ctx.accounts.lp_mint.decimals,
+    )?;
+
+    //transfer mint
+    transfer_checked(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                mint: ctx.accounts.reward_mint.to_account_info(),
+                from: ctx.accounts.pool_reward_token_account.to_account_info(),
+                to: ctx
+                    .accounts
+                    .destination_reward_token_account
+                    .to_account_info(),
+                authority: ctx.accounts.pool.to_account_info(),
+            },
+        )
+        .with_signer(signer),
+        mint_reclaimed,
+        ctx.accounts.reward_mint.decimals,
+    )?;
+
+    emit_cpi!(reclaim_event);
+
+    Ok(())
+}
@@ -0,0 +1,42 @@
+use crate::error::CustomError;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RemoveStakingProgramCtx<'info> {
+    #[account(
+        mut,
+        close = authority,
+        constraint = staking_program_record.pool == pool.key() @CustomError::InvalidPool,
+    )]
+    pub staking_program_record: Box<Account<'info, StakingProgramRecord>>,
+
+    #[account(
+        mut,
+        constraint = pool.load()?.authority == authority.key() @CustomError::InvalidSigner,
+        constraint = !pool.load()?.frozen.get() @CustomError::PoolFrozen,
+    )]
+    pub pool: AccountLoader<'info, Pool>,
+
+    #[account(
+        seeds = [CONFIG_PREFIX.as_bytes()],
+        bump = config.bump,
+        constraint = !config.paused @CustomError::ProgramPaused,
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+pub(crate) fn handler(ctx: Context<RemoveStakingProgramCtx>) -> Result<()> {
+    emit_cpi!(StakingProgramRemovedEvent {
+        authority: ctx.accounts.authority.key(),
+        pool: ctx.accounts.pool.key(),
+        staking_program: ctx.accounts.staking_program_record.staking_program,
+        event_seq: ctx.accounts.pool.load_mut()?.next_event_seq(),
+    });
+
+    Ok(())
+}
@@ -0,0 +1,27 @@
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RevokeClaimApprovalCtx<'info> {
+    #[account(
+        mut,
+        close = owner,
+        seeds = [CLAIM_APPROVAL_PREFIX.as_bytes(), owner.key().as_ref(), claim_approval.delegate.as_ref(), ClaimApproval::scope_seed(&claim_approval.purchase_receipt)],
+        bump = claim_approval.bump,
+    )]
+    pub claim_approval: Box<Account<'info, ClaimApproval>>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
+
+pub(crate) fn handler(ctx: Context<RevokeClaimApprovalCtx>) -> Result<()> {
+    emit_cpi!(ClaimApprovalRevokedEvent {
+        owner: ctx.accounts.owner.key(),
+        delegate: ctx.accounts.claim_approval.delegate,
+        purchase_receipt: ctx.accounts.claim_approval.purchase_receipt,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,39 @@
+use crate::error::CustomError;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetDelegateCtx<'info> {
+    #[account(
+        mut,
+        constraint = pool.load()?.authority == authority.key() @CustomError::InvalidSigner,
+        constraint = !pool.load()?.frozen.get() @CustomError::PoolFrozen,
+    )]
+    pub pool: AccountLoader<'info, Pool>,
+
+    #[account(
+        seeds = [CONFIG_PREFIX.as_bytes()],
+        bump = config.bump,
+        constraint = !config.paused @CustomError::ProgramPaused,
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    pub authority: Signer<'info>,
+}
+
+pub(crate) fn handler(ctx: Context<SetDelegateCtx>, delegate: Option<Pubkey>) -> Result<()> {
+    let mut pool = ctx.accounts.pool.load_mut()?;
+    let previous_delegate = pool.delegate.get();
+    pool.delegate = delegate.into();
+
+    emit_cpi!(DelegateUpdatedEvent {
+        authority: ctx.accounts.authority.key(),
+        pool: ctx.accounts.pool.key(),
+        previous_delegate,
+        delegate: pool.delegate.get(),
+        event_seq: pool.next_event_seq(),
+    });
+
+    Ok(())
+}
@@ -0,0 +1,29 @@
+use crate::error::CustomError;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetPausedCtx<'info> {
+    #[account(
+        mut,
+        seeds = [CONFIG_PREFIX.as_bytes()],
+        bump = config.bump,
+        constraint = config.admin == admin.key() @CustomError::InvalidSigner,
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    pub admin: Signer<'info>,
+}
+
+pub(crate) fn handler(ctx: Context<SetPausedCtx>, paused: bool) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    config.paused = paused;
+
+    emit_cpi!(ProgramPausedEvent {
+        admin: ctx.accounts.admin.key(),
+        paused: config.paused,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,33 @@
+use crate::error::CustomError;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetPoolFrozenCtx<'info> {
+    #[account(
+        seeds = [CONFIG_PREFIX.as_bytes()],
+        bump = config.bump,
+        constraint = config.admin == admin.key() @CustomError::InvalidSigner,
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(mut)]
+    pub pool: AccountLoader<'info, Pool>,
+
+    pub admin: Signer<'info>,
+}
+
+pub(crate) fn handler(ctx: Context<SetPoolFrozenCtx>, frozen: bool) -> Result<()> {
+    let mut pool = ctx.accounts.pool.load_mut()?;
+    pool.frozen = frozen.into();
+
+    emit_cpi!(PoolFrozenEvent {
+        admin: ctx.accounts.admin.key(),
+        pool: ctx.accounts.pool.key(),
+        frozen: pool.frozen.get(),
+        event_seq: pool.next_event_seq(),
+    });
+
+    Ok(())
+}
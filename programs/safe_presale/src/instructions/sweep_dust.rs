@@ -0,0 +1,161 @@
+use crate::error::CustomError;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    burn, transfer_checked, Burn, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SweepDustCtx<'info> {
+    #[account(
+        mut,
+        constraint = pool.load()?.authority == authority.key() @CustomError::InvalidSigner,
+        constraint = pool.load()?.current_status()?.has_launched() @CustomError::PresaleIsStillOngoing,
+        constraint = !pool.load()?.dust_swept.get() @CustomError::DustAlreadySwept,
+        constraint = pool.load()?.receipts_tracked.get() @CustomError::ReceiptsNotTracked,
+        constraint = !pool.load()?.frozen.get() @CustomError::PoolFrozen,
+    )]
+    pub pool: AccountLoader<'info, Pool>,
+
+    #[account(
+        seeds = [CONFIG_PREFIX.as_bytes()],
+        bump = config.bump,
+        constraint = !config.paused @CustomError::ProgramPaused,
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(
+        mut,
+        constraint = pool_lp_token_account.owner == pool.key(),
+        constraint = pool_lp_token_account.mint == lp_mint.key(),
+    )]
+    pub pool_lp_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = pool_reward_token_account.owner == pool.key(),
+        constraint = pool_reward_token_account.mint == reward_mint.key(),
+    )]
+    pub pool_reward_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = pool.load()?.lp_mint.get() == Some(lp_mint.key()) @CustomError::InvalidLpMint,
+    )]
+    pub lp_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        constraint = reward_mint.key() == pool.load()?.mint @CustomError::InvalidRewardMint,
+    )]
+    pub reward_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Only required when the dust is sent to the creator
+    #[account(
+        mut,
+        constraint = authority_lp_token_account.owner == authority.key(),
+        constraint = authority_lp_token_account.mint == lp_mint.key(),
+    )]
+    pub authority_lp_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// Only required when the dust is sent to the creator
+    #[account(
+        mut,
+        constraint = authority_reward_token_account.owner == authority.key(),
+        constraint = authority_reward_token_account.mint == reward_mint.key(),
+    )]
+    pub authority_reward_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    pub authority: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+pub(crate) fn handler(ctx: Context<SweepDustCtx>, burn_dust: bool) -> Result<()> {
+    let pool_key = ctx.accounts.pool.key();
+    let mut pool = ctx.accounts.pool.load_mut()?;
+    // Sweep Criteria
+    // 1. Every purchase receipt must have been checked, or the claim deadline must have passed.
+    // 2. Only the rounding left over from the contributors' share is swept, the creator's allocation is untouched.
+    // 3. Entitlements still owed, to unchecked receipts or for the dissolved lp tokens, stay in the vaults.
+    let current_time = Clock::get()?.unix_timestamp;
+    let claim_deadline_passed = pool
+        .claim_deadline
+        .get()
+        .is_some_and(|claim_deadline| current_time >= claim_deadline);
+    require!(
+        pool.purchase_receipts_checked == pool.purchase_receipt_count || claim_deadline_passed,
+        CustomError::ReceiptsNotChecked
+    );
+
+    let (lp_dust, mint_dust) = pool.dust()?;
+    pool.dust_swept = true.into();
+
+    let sweep_event = SweepDustEvent {
+        payer: ctx.accounts.authority.key(),
+        pool: pool_key,
+        lp_dust,
+        mint_dust,
+        burned: burn_dust,
+        event_seq: pool.next_event_seq(),
+    };
+    let (pool_mint, pool_bump) = (pool.mint, pool.bump);
+    drop(pool);
+    let pool_seed = &[POOL_PREFIX.as_bytes(), pool_mint.as_ref(), &[pool_bump]];
+    let signer = &[&pool_seed[..]];
+
+    let sweeps = [
+        (
+            &ctx.accounts.pool_lp_token_account,
+            &ctx.accounts.lp_mint,
+            &ctx.accounts.authority_lp_token_account,
+            lp_dust,
+        ),
+        (
+            &ctx.accounts.pool_reward_token_account,
+            &ctx.accounts.reward_mint,
+            &ctx.accounts.authority_reward_token_account,
+            mint_dust,
+        ),
+    ];
+    for (from, mint, to, amount) in sweeps {
+        if amount == 0 {
+            continue;
+        }
+        if burn_dust {
+            burn(
+                CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    Burn {
+                        mint: mint.to_account_info(),
+                        from: from.to_account_info(),
+                        authority: ctx.accounts.pool.to_account_info(),
+                    },
+                )
+                .with_signer(signer),
+                amount,
+            )?;
+        } else {
+            let to = to.as_ref().ok_or(CustomError::MissingDustDestination)?;
+            transfer_checked(
+                CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    TransferChecked {
+                        mint: mint.to_account_info(),
+                        from: from.to_account_info(),
+                        to: to.to_account_info(),
+                        authority: ctx.accounts.pool.to_account_info(),
+                    },
+                )
+                .with_signer(signer),
+                amount,
+                mint.decimals,
+            )?;
+        }
+    }
+
+    emit_cpi!(sweep_event);
+
+    Ok(())
+}
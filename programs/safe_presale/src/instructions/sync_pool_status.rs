@@ -0,0 +1,29 @@
+use crate::error::CustomError;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+/// Stores the time based transitions (to Failed or Expired) that no other instruction saw.
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SyncPoolStatusCtx<'info> {
+    #[account(mut)]
+    pub pool: AccountLoader<'info, Pool>,
+
+    #[account(
+        seeds = [CONFIG_PREFIX.as_bytes()],
+        bump = config.bump,
+        constraint = !config.paused @CustomError::ProgramPaused,
+    )]
+    pub config: Box<Account<'info, Config>>,
+}
+
+pub(crate) fn handler(ctx: Context<SyncPoolStatusCtx>) -> Result<()> {
+    let mut pool = ctx.accounts.pool.load_mut()?;
+    if let Some(status_changed) =
+        pool.transition(ctx.accounts.pool.key(), Clock::get()?.unix_timestamp)
+    {
+        emit_cpi!(status_changed);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,135 @@
+use crate::error::CustomError;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use mpl_token_metadata::{
+    accounts::Metadata, instructions::UpdateMetadataAccountV2CpiBuilder, types::DataV2,
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct UpdatePoolArgs {
+    pub presale_target: u64,
+    pub presale_duration: u32,
+    pub vesting_period: u32,
+    pub reward_vesting_period: u32,
+    pub creator_reward_vesting_period: u32,
+    pub max_amount_per_purchase: Option<u64>,
+    pub uri: Option<String>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct UpdatePoolCtx<'info> {
+    #[account(
+        mut,
+        constraint = pool.load()?.authority == authority.key() @CustomError::InvalidSigner,
+        constraint = pool.load()?.current_status()? == PoolStatus::Upcoming @CustomError::PoolHasPurchases,
+        constraint = !pool.load()?.frozen.get() @CustomError::PoolFrozen,
+    )]
+    pub pool: AccountLoader<'info, Pool>,
+
+    #[account(
+        seeds = [CONFIG_PREFIX.as_bytes()],
+        bump = config.bump,
+        constraint = !config.paused @CustomError::ProgramPaused,
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    /// CHECK: Checked by cpi, only required when updating the uri, the first purchase locks it
+    #[account(
+        mut,
+        seeds = ["metadata".as_bytes(), mpl_token_metadata::ID.as_ref(), pool.load()?.mint.as_ref()],
+        bump,
+        seeds::program = mpl_token_metadata::ID
+    )]
+    pub reward_mint_metadata: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Checked by cpi, only required when updating the uri
+    #[account(address = mpl_token_metadata::ID)]
+    pub mpl_token_program: Option<AccountInfo<'info>>,
+
+    pub authority: Signer<'info>,
+}
+
+pub(crate) fn handler(ctx: Context<UpdatePoolCtx>, args: UpdatePoolArgs) -> Result<()> {
+    require!(args.presale_target > 0, CustomError::NumberCannotBeZero);
+    require!(args.presale_duration > 0, CustomError::NumberCannotBeZero);
+    if let Some(max_amount_per_purchase) = args.max_amount_per_purchase {
+        require!(max_amount_per_purchase > 0, CustomError::NumberCannotBeZero);
+        require!(
+            max_amount_per_purchase <= args.presale_target,
+            CustomError::PresaleTargetExceeded
+        );
+    }
+
+    let pool_key = ctx.accounts.pool.key();
+    let mut pool = ctx.accounts.pool.load_mut()?;
+    let current_time = Clock::get()?.unix_timestamp;
+    pool.presale_target = args.presale_target;
+    pool.presale_time_limit = current_time
+        .checked_add(args.presale_duration.into())
+        .ok_or(CustomError::IntegerOverflow)?;
+    pool.vesting_period = args.vesting_period;
+    pool.reward_vesting_period = args.reward_vesting_period;
+    pool.creator_reward_vesting_period = args.creator_reward_vesting_period;
+    pool.max_amount_per_purchase = args.max_amount_per_purchase.into();
+    let status_changed = pool.transition(pool_key, current_time);
+    let pool_updated_event = PoolUpdatedEvent {
+        authority: ctx.accounts.authority.key(),
+        pool: pool_key,
+        presale_target: pool.presale_target,
+        presale_time_limit: pool.presale_time_limit,
+        vesting_period: pool.vesting_period,
+        reward_vesting_period: pool.reward_vesting_period,
+        creator_reward_vesting_period: pool.creator_reward_vesting_period,
+        max_amount_per_purchase: pool.max_amount_per_purchase.get(),
+        uri: args.uri.clone(),
+        event_seq: pool.next_event_seq(),
+    };
+
+    let (pool_mint, pool_bump) = (pool.mint, pool.bump);
+    drop(pool);
+
+    if let Some(uri) = &args.uri {
+        let (Some(reward_mint_metadata), Some(mpl_token_program)) = (
+            &ctx.accounts.reward_mint_metadata,
+            &ctx.accounts.mpl_token_program,
+        ) else {
+            return Err(error!(CustomError::InvalidMintMetadata));
+        };
+        let metadata = {
+            let mint_metadata_data = reward_mint_metadata.try_borrow_data()?;
+            Metadata::deserialize(&mut mint_metadata_data.as_ref())
+                .map_err(|_| CustomError::InvalidMintMetadata)?
+        };
+        // The metadata is only mutable through the pool, which signs as update authority.
+        require!(
+            metadata.update_authority == pool_key,
+            CustomError::InvalidMintMetadata
+        );
+
+        let pool_seed = &[POOL_PREFIX.as_bytes(), pool_mint.as_ref(), &[pool_bump]];
+        let signer = &[&pool_seed[..]];
+
+        UpdateMetadataAccountV2CpiBuilder::new(mpl_token_program)
+            .metadata(reward_mint_metadata)
+            .update_authority(&ctx.accounts.pool.to_account_info())
+            .data(DataV2 {
+                name: metadata.name.trim_end_matches('\0').to_string(),
+                symbol: metadata.symbol.trim_end_matches('\0').to_string(),
+                uri: uri.clone(),
+                seller_fee_basis_points: metadata.seller_fee_basis_points,
+                creators: metadata.creators,
+                collection: metadata.collection,
+                uses: metadata.uses,
+            })
+            .invoke_signed(signer)?;
+    }
+
+    if let Some(status_changed) = status_changed {
+        emit_cpi!(status_changed);
+    }
+
+    emit_cpi!(pool_updated_event);
+
+    Ok(())
+}
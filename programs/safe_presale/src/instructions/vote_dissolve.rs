@@ -0,0 +1,96 @@
+use crate::error::CustomError;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct VoteDissolveCtx<'info> {
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = DISSOLVE_VOTE_RECORD_SIZE,
+        seeds = [DISSOLVE_VOTE_PREFIX.as_bytes(), purchase_receipt.key().as_ref()],
+        bump,
+    )]
+    pub dissolve_vote_record: Box<Account<'info, DissolveVoteRecord>>,
+
+    #[account(
+        constraint = purchase_receipt.pool == pool.key() @CustomError::InvalidPool,
+        constraint = purchase_receipt.original_mint == nft_owner_nft_token_account.mint @ CustomError::MintNotAllowed,
+        constraint = !purchase_receipt.reclaimed @CustomError::ReceiptReclaimed,
+    )]
+    pub purchase_receipt: Box<Account<'info, PurchaseReceipt>>,
+
+    #[account(
+        constraint = nft_owner_nft_token_account.amount == 1,
+        constraint = nft_owner_nft_token_account.owner == payer.key() @CustomError::InvalidSigner,
+    )]
+    pub nft_owner_nft_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = pool.load()?.current_status()?.has_launched() @CustomError::PresaleIsStillOngoing,
+        constraint = pool.load()?.current_status()? != PoolStatus::Cancelled @CustomError::PoolDissolved,
+        constraint = pool.load()?.dissolve_quorum_basis_points > 0 @CustomError::DissolveVoteDisabled,
+        constraint = !pool.load()?.frozen.get() @CustomError::PoolFrozen,
+    )]
+    pub pool: AccountLoader<'info, Pool>,
+
+    #[account(
+        seeds = [CONFIG_PREFIX.as_bytes()],
+        bump = config.bump,
+        constraint = !config.paused @CustomError::ProgramPaused,
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub(crate) fn handler(ctx: Context<VoteDissolveCtx>) -> Result<()> {
+    let pool_key = ctx.accounts.pool.key();
+    let mut pool = ctx.accounts.pool.load_mut()?;
+    let purchase_receipt = &ctx.accounts.purchase_receipt;
+    let dissolve_vote_record = &mut ctx.accounts.dissolve_vote_record;
+    let current_time = Clock::get()?.unix_timestamp;
+    let quorum_reached = pool.record_dissolve_vote(
+        dissolve_vote_record.round,
+        purchase_receipt.amount,
+        current_time,
+    )?;
+
+    dissolve_vote_record.bump = ctx.bumps.dissolve_vote_record;
+    dissolve_vote_record.pool = pool_key;
+    dissolve_vote_record.purchase_receipt = purchase_receipt.key();
+    dissolve_vote_record.round = pool.dissolve_round;
+
+    emit_cpi!(DissolveVoteEvent {
+        payer: ctx.accounts.payer.key(),
+        pool: pool_key,
+        original_mint: purchase_receipt.original_mint,
+        votes: purchase_receipt.amount,
+        dissolve_votes: pool.dissolve_votes,
+        dissolve_round: pool.dissolve_round,
+        dissolve_vote_started_at: pool.dissolve_vote_started_at.get().unwrap(),
+        event_seq: pool.next_event_seq(),
+    });
+
+    if quorum_reached {
+        pool.dissolve(current_time)?;
+
+        emit_cpi!(PoolDissolvedEvent {
+            pool: pool_key,
+            lp_mint_dissolved: pool.lp_mint_dissolved,
+            dissolved_at: current_time,
+            event_seq: pool.next_event_seq(),
+        });
+        if let Some(status_changed) = pool.transition(pool_key, current_time) {
+            emit_cpi!(status_changed);
+        }
+    }
+
+    Ok(())
+}
@@ -1,4 +1,4 @@
-use crate::{error::CustomError, state::*};
+use crate::{error::CustomError, state::*, utils::check_delegated_claim};
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
@@ -10,20 +10,17 @@ use anchor_spl::{
 pub struct Withdraw<'info> {
     #[account(
         mut,
-        close = payer,
+        close = receipt_payer,
         constraint = purchase_receipt.original_mint == nft_owner_nft_token_account.mint @ CustomError::MintNotAllowed,
         constraint = purchase_receipt.pool == pool.key() @CustomError::InvalidPool,
     )]
     pub purchase_receipt: Box<Account<'info, PurchaseReceipt>>,
     #[account(
         constraint = nft_owner_nft_token_account.amount == 1,
-        constraint = nft_owner_nft_token_account.owner == nft_owner.key(),
     )]
     pub nft_owner_nft_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
-    #[account(
-        constraint = pool.vesting_started_at.is_none() @CustomError::TokenHasLaunched,
-    )]
-    pub pool: Box<Account<'info, Pool>>,
+    #[account(mut)]
+    pub pool: AccountLoader<'info, Pool>,
     #[account(
 		init_if_needed,
         payer = payer,
@@ -39,7 +36,7 @@ pub struct Withdraw<'info> {
 	)]
     pub pool_quote_mint_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
     #[account(
-		constraint = quote_mint.key() == pool.quote_mint,
+		constraint = quote_mint.key() == pool.load()?.quote_mint,
 	)]
     pub quote_mint: Box<InterfaceAccount<'info, Mint>>,
     ///CHECK: Contraint is checked by other accounts
@@ -52,6 +49,19 @@ pub struct Withdraw<'info> {
     )]
     /// CHECK: This is not dangerous because we don't read or write from this account
     pub nft_metadata: AccountInfo<'info>,
+    #[account(
+        seeds = [STAKING_PROGRAM_PREFIX.as_bytes(), pool.key().as_ref(), staking_program_record.staking_program.as_ref()],
+        bump = staking_program_record.bump,
+    )]
+    pub staking_program_record: Option<Box<Account<'info, StakingProgramRecord>>>,
+    ///CHECK: Frozen delegate or escrow vault of the staking program, checked in the handler
+    pub staking_authority: Option<AccountInfo<'info>>,
+    ///CHECK: Receives the receipt rent, must be whoever paid for the purchase receipt
+    #[account(
+        mut,
+        address = purchase_receipt.payer,
+    )]
+    pub receipt_payer: AccountInfo<'info>,
     #[account(mut)]
     pub payer: Signer<'info>,
     /// Program to create the position manager state account
@@ -61,56 +71,46 @@ pub struct Withdraw<'info> {
     /// Program to create an ATA for receiving position NFT
     pub associated_token_program: Program<'info, AssociatedToken>,
 }
-pub fn handler<'info>(ctx: Context<Withdraw<'info>>) -> Result<()> {
-    let pool = &ctx.accounts.pool;
+pub(crate) fn handler<'info>(ctx: Context<Withdraw<'info>>) -> Result<()> {
+    let pool_key = ctx.accounts.pool.key();
+    let mut pool = ctx.accounts.pool.load_mut()?;
     let purchase_receipt = &mut ctx.accounts.purchase_receipt;
-    // Withdrawal criteria
-    // 1. Only allow withdrawal after presale has ended.
-    // 2. If presale target amount is not met, withdrawal is allowed immediately.
-    // 3. If presale target amount is met, only allow withdrawal after the creator failed to launch the project after 7 days.
+    // Withdrawal criteria, see Pool::check_refundable
     let current_time = Clock::get()?.unix_timestamp;
-    if current_time < pool.presale_time_limit + GRACE_PERIOD {
-        if current_time < pool.presale_time_limit {
-            return Err(error!(CustomError::UnauthorizedAtCurrentTime));
-        }
-        if pool.presale_target == pool.liquidity_collected {
-            return Err(error!(CustomError::WaitingForCreatorToLaunch));
-        }
+    pool.check_refundable(current_time)?;
+    if let Some(status_changed) = pool.transition(pool_key, current_time) {
+        emit_cpi!(status_changed);
     }
     // Delegated withdrawal criteria
-    // 1. Only allow delegated withdrawal if the nfts are frozen to the owner's wallet.
-    let allowed = ctx.accounts.nft_owner.key() == ctx.accounts.payer.key();
-    // if !ctx.accounts.nft_metadata.data_is_empty() {
-    //     let mint_metadata_data = ctx
-    //         .accounts
-    //         .nft_metadata
-    //         .try_borrow_mut_data()
-    //         .expect("Failed to borrow data");
-    //     if ctx.accounts.nft_metadata.to_account_info().owner.key() != mpl_token_metadata::ID {
-    //         return Err(error!(CustomError::InvalidMintMetadataOwner));
-    //     }
-    //     let original_mint_metadata = Metadata::deserialize(&mut mint_metadata_data.as_ref())
-    //         .expect("Failed to deserialize metadata");
-    //     if original_mint_metadata.mint != purchase_receipt.original_mint {
-    //         return Err(error!(CustomError::InvalidMintMetadata));
-    //     }
+    // 1. Only allow delegated withdrawal if the nft is frozen or escrowed for the owner by an allowlisted staking program.
+    check_delegated_claim(
+        &ctx.accounts.nft_owner,
+        &ctx.accounts.payer.to_account_info(),
+        &ctx.accounts.nft_owner_nft_token_account,
+        ctx.accounts.staking_program_record.as_deref(),
+        ctx.accounts.staking_authority.as_ref(),
+    )?;
 
-    //     if original_mint_metadata.collection.is_some() {
-    //         let collection = original_mint_metadata.collection.unwrap();
-    //         if collection.verified
-    //             && &collection.key == &collection::id()
-    //             && ctx.accounts.nft_owner_nft_token_account.is_frozen()
-    //         {
-    //             allowed = true;
-    //         }
-    //     }
-    // }
+    pool.purchase_receipt_count = pool
+        .purchase_receipt_count
+        .checked_sub(1)
+        .ok_or(CustomError::IntegerOverflow)?;
 
-    if !allowed {
-        return Err(error!(CustomError::InvalidSigner));
-    }
-
-    let pool_seed = &[POOL_PREFIX.as_bytes(), pool.mint.as_ref(), &[pool.bump]];
+    let withdraw_event = WithdrawEvent {
+        payer: ctx.accounts.payer.key(),
+        pool: pool_key,
+        original_mint: purchase_receipt.original_mint,
+        amount_withdrawn: purchase_receipt.amount,
+        original_mint_owner: ctx.accounts.nft_owner.key(),
+        purchase_receipt: purchase_receipt.key(),
+        purchase_receipt_closed: true,
+        purchase_receipt_count: pool.purchase_receipt_count,
+        event_seq: pool.next_event_seq(),
+    };
+    let (pool_mint, pool_bump) = (pool.mint, pool.bump);
+    // The pool signs the transfer below, its data can't stay borrowed
+    drop(pool);
+    let pool_seed = &[POOL_PREFIX.as_bytes(), pool_mint.as_ref(), &[pool_bump]];
     let signer = &[&pool_seed[..]];
 
     transfer_checked(
@@ -123,7 +123,7 @@ pub fn handler<'info>(ctx: Context<Withdraw<'info>>) -> Result<()> {
                     .accounts
                     .nft_owner_quote_mint_token_account
                     .to_account_info(),
-                authority: pool.to_account_info(),
+                authority: ctx.accounts.pool.to_account_info(),
             },
         )
         .with_signer(signer),
@@ -131,13 +131,7 @@ pub fn handler<'info>(ctx: Context<Withdraw<'info>>) -> Result<()> {
         ctx.accounts.quote_mint.decimals,
     )?;
 
-    emit_cpi!(WithdrawEvent {
-        payer: ctx.accounts.payer.key(),
-        pool: pool.key(),
-        original_mint: purchase_receipt.original_mint,
-        amount_withdrawn: purchase_receipt.amount,
-        original_mint_owner: ctx.accounts.nft_owner.key(),
-    });
+    emit_cpi!(withdraw_event);
 
     Ok(())
 }
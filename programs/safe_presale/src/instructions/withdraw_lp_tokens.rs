@@ -1,7 +1,8 @@
 use crate::error::CustomError;
 use crate::state::*;
-use crate::utils::Calculator;
-use crate::utils::U128;
+use crate::utils::{
+    cap_approved_claim, check_claim_approval, check_delegated_claim, VestingSchedule,
+};
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
@@ -14,6 +15,7 @@ pub struct WithdrawLpCtx<'info> {
     #[account(
         mut,
         constraint = purchase_receipt.lp_elligible.is_some() @CustomError::CheckClaimFirstBeforeClaiming,
+        constraint = !purchase_receipt.direct_claim @CustomError::DirectClaimReceipt,
         constraint = purchase_receipt.original_mint == nft_owner_nft_token_account.mint @ CustomError::MintNotAllowed,
     )]
     pub purchase_receipt: Box<Account<'info, PurchaseReceipt>>,
@@ -21,18 +23,18 @@ pub struct WithdrawLpCtx<'info> {
     #[account(
         mut,
         constraint = purchase_receipt_lp_token_account.owner == purchase_receipt.key(),
-        constraint = purchase_receipt_lp_token_account.mint == pool.lp_mint.unwrap(),
+        constraint = purchase_receipt_lp_token_account.mint == pool.load()?.lp_mint.get().unwrap(),
     )]
     pub purchase_receipt_lp_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
+        mut,
         constraint = pool.key() == purchase_receipt.pool @CustomError::InvalidPool,
     )]
-    pub pool: Box<Account<'info, Pool>>,
+    pub pool: AccountLoader<'info, Pool>,
 
     #[account(
         constraint = nft_owner_nft_token_account.amount == 1,
-        constraint = nft_owner_nft_token_account.owner == nft_owner.key()
     )]
     pub nft_owner_nft_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
@@ -56,7 +58,22 @@ pub struct WithdrawLpCtx<'info> {
     pub nft_metadata: AccountInfo<'info>,
 
     #[account(
-        constraint =  pool.lp_mint.is_some() && lp_mint.key() == pool.lp_mint.unwrap() @ CustomError::InvalidRewardMint
+        seeds = [STAKING_PROGRAM_PREFIX.as_bytes(), pool.key().as_ref(), staking_program_record.staking_program.as_ref()],
+        bump = staking_program_record.bump,
+    )]
+    pub staking_program_record: Option<Box<Account<'info, StakingProgramRecord>>>,
+
+    ///CHECK: Frozen delegate or escrow vault of the staking program, checked in the handler
+    pub staking_authority: Option<AccountInfo<'info>>,
+
+    #[account(
+        seeds = [CLAIM_APPROVAL_PREFIX.as_bytes(), nft_owner.key().as_ref(), payer.key().as_ref(), ClaimApproval::scope_seed(&claim_approval.purchase_receipt)],
+        bump = claim_approval.bump,
+    )]
+    pub claim_approval: Option<Box<Account<'info, ClaimApproval>>>,
+
+    #[account(
+        constraint =  pool.load()?.lp_mint.get() == Some(lp_mint.key()) @ CustomError::InvalidRewardMint
     )]
     pub lp_mint: Box<InterfaceAccount<'info, Mint>>,
 
@@ -68,69 +85,51 @@ pub struct WithdrawLpCtx<'info> {
     pub system_program: Program<'info, System>,
 }
 
-pub fn handler(ctx: Context<WithdrawLpCtx>) -> Result<()> {
+pub(crate) fn handler(ctx: Context<WithdrawLpCtx>) -> Result<()> {
     let purchase_receipt = &mut ctx.accounts.purchase_receipt;
+    let current_time = Clock::get()?.unix_timestamp;
     // Delegated Claim Criteria
-    // 1. Only allow delegated claiming if the nfts are frozen to the owner's wallet.
-    let allowed = ctx.accounts.nft_owner.key() == ctx.accounts.payer.key();
-    // if !ctx.accounts.nft_metadata.data_is_empty() {
-    //     let mint_metadata_data = ctx
-    //         .accounts
-    //         .nft_metadata
-    //         .try_borrow_mut_data()
-    //         .expect("Failed to borrow data");
-    //     if ctx.accounts.nft_metadata.to_account_info().owner.key() != mpl_token_metadata::ID {
-    //         return Err(error!(CustomError::InvalidMintMetadataOwner));
-    //     }
-    //     let original_mint_metadata = Metadata::deserialize(&mut mint_metadata_data.as_ref())
-    //         .expect("Failed to deserialize metadata");
-    //     if original_mint_metadata.mint != purchase_receipt.original_mint {
-    //         return Err(error!(CustomError::InvalidMintMetadata));
-    //     }
-
-    //     if original_mint_metadata.collection.is_some() {
-    //         let collection = original_mint_metadata.collection.unwrap();
-    //         if collection.verified && &collection.key == &collection::id() && ctx.accounts.nft_owner_nft_token_account.is_frozen(){ // only allow staked nfts to do delegated claiming
-    //             allowed = true;
-    //         }
-    //     }
-    // }
-    if !allowed {
-        return Err(error!(CustomError::InvalidSigner));
+    // 1. A delegate approved by the owner can claim, within the approval's expiry and per-claim limit.
+    // 2. Otherwise only allow delegated claiming if the nft is frozen or escrowed for the owner by an allowlisted staking program.
+    match &ctx.accounts.claim_approval {
+        Some(claim_approval) => {
+            check_claim_approval(
+                claim_approval,
+                &ctx.accounts.nft_owner,
+                &ctx.accounts.nft_owner_nft_token_account,
+                &purchase_receipt.key(),
+                current_time,
+            )?;
+        }
+        None => {
+            check_delegated_claim(
+                &ctx.accounts.nft_owner,
+                &ctx.accounts.payer.to_account_info(),
+                &ctx.accounts.nft_owner_nft_token_account,
+                ctx.accounts.staking_program_record.as_deref(),
+                ctx.accounts.staking_authority.as_ref(),
+            )?;
+        }
     }
 
-    let pool = &ctx.accounts.pool;
-    let vesting_started_at = pool.vesting_started_at.unwrap();
-    let vesting_period = pool.vesting_period;
-    let vesting_period_end = vesting_started_at
-        .checked_add(vesting_period.into())
-        .unwrap();
-    let lp_elligible_to_claim = purchase_receipt.lp_elligible.unwrap();
-
-    let current_time = Clock::get()?.unix_timestamp;
-    let lp_claimable;
-    if current_time >= vesting_period_end {
-        if lp_elligible_to_claim == purchase_receipt.lp_claimed {
-            return Err(error!(CustomError::MaximumAmountClaimed));
-        }
-        lp_claimable = lp_elligible_to_claim
-            .checked_sub(purchase_receipt.lp_claimed)
-            .ok_or(CustomError::IntegerOverflow)?;
-    } else {
-        let last_claimed_at = purchase_receipt
-            .last_claimed_at
-            .unwrap_or(vesting_started_at);
-
-        let duration_since_last_claimed = Calculator::to_u64_from_i64(
-            current_time
-                .checked_sub(last_claimed_at)
-                .ok_or(CustomError::IntegerOverflow)?,
+    let mut pool = ctx.accounts.pool.load_mut()?;
+    let vesting_schedule = VestingSchedule::new(
+        pool.vesting_started_at.get().unwrap(),
+        pool.vesting_period,
+        purchase_receipt.lp_elligible.unwrap(),
+    );
+
+    let mut lp_claimable = vesting_schedule.claimable(current_time, purchase_receipt.lp_claimed)?;
+    if let Some(claim_approval) = &ctx.accounts.claim_approval {
+        lp_claimable = cap_approved_claim(
+            claim_approval.max_lp_per_claim,
+            &mut purchase_receipt.lp_approved_claim_at,
+            lp_claimable,
+            current_time,
         )?;
-        lp_claimable = U128::from(duration_since_last_claimed)
-            .checked_mul(U128::from(lp_elligible_to_claim))
-            .and_then(|result| result.checked_div(vesting_period.try_into().unwrap()))
-            .and_then(|result| Some(result.as_u64()))
-            .ok_or(CustomError::IntegerOverflow)?;
+    }
+    if lp_claimable == 0 {
+        return Err(error!(CustomError::MaximumAmountClaimed));
     }
 
     //update lp_claimed
@@ -175,6 +174,8 @@ pub fn handler(ctx: Context<WithdrawLpCtx>) -> Result<()> {
         last_claimed_at: purchase_receipt.last_claimed_at.unwrap(),
         original_mint: purchase_receipt.original_mint,
         original_mint_owner: ctx.accounts.nft_owner.key(),
+        total_lp_claimed: purchase_receipt.lp_claimed,
+        event_seq: pool.next_event_seq(),
     });
 
     Ok(())
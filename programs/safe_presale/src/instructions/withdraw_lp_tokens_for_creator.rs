@@ -1,6 +1,6 @@
 use crate::error::CustomError;
 use crate::state::*;
-use crate::utils::{Calculator, U128};
+use crate::utils::VestingSchedule;
 use anchor_lang::prelude::*;
 use anchor_spl::associated_token::AssociatedToken;
 use anchor_spl::token_interface::{
@@ -21,21 +21,24 @@ pub struct WithdrawLpCreatorCtx<'info> {
         init_if_needed,
         payer = payer,
         associated_token::mint = lp_mint,
-        associated_token::authority = payer,
+        associated_token::authority = authority,
     )]
     pub pool_authority_lp_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
-        constraint = lp_mint.key() == pool.lp_mint.unwrap() @CustomError::InvalidRewardMint,
+        constraint = lp_mint.key() == pool.load()?.lp_mint.get().unwrap() @CustomError::InvalidRewardMint,
     )]
     pub lp_mint: Box<InterfaceAccount<'info, Mint>>,
 
     #[account(
-        mut, 
-        constraint = payer.key() == pool.authority @CustomError::InvalidSigner,
-        constraint = pool.vesting_started_at.is_some() @CustomError::PresaleIsStillOngoing,
+        mut,
+        constraint = authority.key() == pool.load()?.authority @CustomError::InvalidSigner,
+        constraint = pool.load()?.current_status()?.has_launched() @CustomError::PresaleIsStillOngoing,
+        constraint = pool.load()?.current_status()? != PoolStatus::Cancelled @CustomError::PoolDissolved,
     )]
-    pub pool: Box<Account<'info, Pool>>,
+    pub pool: AccountLoader<'info, Pool>,
+
+    pub authority: Signer<'info>,
 
     #[account(mut)]
     pub payer: Signer<'info>,
@@ -45,39 +48,19 @@ pub struct WithdrawLpCreatorCtx<'info> {
     pub system_program: Program<'info, System>,
 }
 
-pub fn handler(ctx: Context<WithdrawLpCreatorCtx>) -> Result<()> {
-    let pool = &mut ctx.accounts.pool;
-    let vesting_started_at = pool.vesting_started_at.unwrap();
-    let vesting_period = pool.vesting_period;
-    let vesting_period_end = vesting_started_at
-        .checked_add(vesting_period.into())
-        .unwrap();
-    let lp_elligible_to_claim = pool.lp_mint_supply_for_creator.unwrap();
+pub(crate) fn handler(ctx: Context<WithdrawLpCreatorCtx>) -> Result<()> {
+    let pool_key = ctx.accounts.pool.key();
+    let mut pool = ctx.accounts.pool.load_mut()?;
+    let vesting_schedule = VestingSchedule::new(
+        pool.vesting_started_at.get().unwrap(),
+        pool.vesting_period,
+        pool.lp_mint_supply_for_creator.get().unwrap(),
+    );
 
     let current_time = Clock::get()?.unix_timestamp;
-    let lp_claimable;
-    if current_time >= vesting_period_end {
-        if lp_elligible_to_claim == pool.lp_mint_claimed_by_creator {
-            return Err(error!(CustomError::MaximumAmountClaimed));
-        }
-        lp_claimable = lp_elligible_to_claim
-            .checked_sub(pool.lp_mint_claimed_by_creator)
-            .ok_or(CustomError::IntegerOverflow)?;
-    } else {
-        let last_claimed_at = pool
-            .lp_mint_last_claimed_by_creator
-            .unwrap_or(vesting_started_at);
-
-        let duration_since_last_claimed = Calculator::to_u64_from_i64(
-            current_time
-                .checked_sub(last_claimed_at)
-                .ok_or(CustomError::IntegerOverflow)?,
-        )?;
-        lp_claimable = U128::from(duration_since_last_claimed)
-            .checked_mul(U128::from(lp_elligible_to_claim))
-            .and_then(|result| result.checked_div(vesting_period.try_into().unwrap()))
-            .and_then(|result| Some(result.as_u64()))
-            .ok_or(CustomError::IntegerOverflow)?;
+    let lp_claimable = vesting_schedule.claimable(current_time, pool.lp_mint_claimed_by_creator)?;
+    if lp_claimable == 0 {
+        return Err(error!(CustomError::MaximumAmountClaimed));
     }
 
     //update lp_claimed
@@ -86,9 +69,19 @@ pub fn handler(ctx: Context<WithdrawLpCreatorCtx>) -> Result<()> {
         .checked_add(lp_claimable)
         .ok_or(CustomError::IntegerOverflow)?;
     //update last_claimed_at
-    pool.lp_mint_last_claimed_by_creator = Some(current_time);
+    pool.lp_mint_last_claimed_by_creator = Some(current_time).into();
 
-    let pool_seed = &[POOL_PREFIX.as_bytes(), pool.mint.as_ref(), &[pool.bump]];
+    let withdraw_event = WithdrawLpTokenForCreatorEvent {
+        payer: ctx.accounts.payer.key(),
+        pool: pool_key,
+        lp_claimed: lp_claimable,
+        last_claimed_at: current_time,
+        lp_mint_claimed_by_creator: pool.lp_mint_claimed_by_creator,
+        event_seq: pool.next_event_seq(),
+    };
+    let (pool_mint, pool_bump) = (pool.mint, pool.bump);
+    drop(pool);
+    let pool_seed = &[POOL_PREFIX.as_bytes(), pool_mint.as_ref(), &[pool_bump]];
     let signer = &[&pool_seed[..]];
 
     //transfer lp
@@ -102,7 +95,7 @@ pub fn handler(ctx: Context<WithdrawLpCreatorCtx>) -> Result<()> {
                     .accounts
                     .pool_authority_lp_token_account
                     .to_account_info(),
-                authority: pool.to_account_info(),
+                authority: ctx.accounts.pool.to_account_info(),
             },
         )
         .with_signer(signer),
@@ -110,12 +103,7 @@ pub fn handler(ctx: Context<WithdrawLpCreatorCtx>) -> Result<()> {
         ctx.accounts.lp_mint.decimals,
     )?;
 
-    emit_cpi!(WithdrawLpTokenForCreatorEvent {
-        payer: ctx.accounts.payer.key(),
-        pool: pool.key(),
-        lp_claimed: lp_claimable,
-        last_claimed_at: pool.lp_mint_last_claimed_by_creator.unwrap(),
-    });
+    emit_cpi!(withdraw_event);
 
     Ok(())
 }
@@ -50,4 +50,100 @@ pub mod safe_presale {
     ) -> Result<()> {
         instructions::launch_token_amm::handler(ctx, open_time)
     }
+    pub fn vote_dissolve(ctx: Context<VoteDissolveCtx>) -> Result<()> {
+        instructions::vote_dissolve::handler(ctx)
+    }
+    pub fn claim_dissolved_lp_tokens(ctx: Context<ClaimDissolvedLpCtx>) -> Result<()> {
+        instructions::claim_dissolved_lp_tokens::handler(ctx)
+    }
+    pub fn set_delegate(ctx: Context<SetDelegateCtx>, delegate: Option<Pubkey>) -> Result<()> {
+        instructions::set_delegate::handler(ctx, delegate)
+    }
+    pub fn propose_authority(
+        ctx: Context<ProposeAuthorityCtx>,
+        new_authority: Option<Pubkey>,
+    ) -> Result<()> {
+        instructions::propose_authority::handler(ctx, new_authority)
+    }
+    pub fn accept_authority(ctx: Context<AcceptAuthorityCtx>) -> Result<()> {
+        instructions::accept_authority::handler(ctx)
+    }
+    pub fn init_config(ctx: Context<InitConfigCtx>, admin: Pubkey) -> Result<()> {
+        instructions::init_config::handler(ctx, admin)
+    }
+    pub fn set_paused(ctx: Context<SetPausedCtx>, paused: bool) -> Result<()> {
+        instructions::set_paused::handler(ctx, paused)
+    }
+    pub fn set_pool_frozen(ctx: Context<SetPoolFrozenCtx>, frozen: bool) -> Result<()> {
+        instructions::set_pool_frozen::handler(ctx, frozen)
+    }
+    pub fn update_pool(ctx: Context<UpdatePoolCtx>, args: UpdatePoolArgs) -> Result<()> {
+        instructions::update_pool::handler(ctx, args)
+    }
+    pub fn close_purchase_receipt(ctx: Context<ClosePurchaseReceiptCtx>) -> Result<()> {
+        instructions::close_purchase_receipt::handler(ctx)
+    }
+    pub fn close_pool(ctx: Context<ClosePoolCtx>) -> Result<()> {
+        instructions::close_pool::handler(ctx)
+    }
+    pub fn sweep_dust(ctx: Context<SweepDustCtx>, burn: bool) -> Result<()> {
+        instructions::sweep_dust::handler(ctx, burn)
+    }
+    pub fn burn_reward_supply(ctx: Context<BurnRewardSupplyCtx>) -> Result<()> {
+        instructions::burn_reward_supply::handler(ctx)
+    }
+    pub fn reclaim_purchase_receipt(ctx: Context<ReclaimPurchaseReceiptCtx>) -> Result<()> {
+        instructions::reclaim_purchase_receipt::handler(ctx)
+    }
+    pub fn claim_all(ctx: Context<ClaimAllCtx>) -> Result<()> {
+        instructions::claim_all::handler(ctx)
+    }
+    pub fn batch_claim_reward_token<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BatchClaimRewardCtx<'info>>,
+    ) -> Result<()> {
+        instructions::batch_claim_reward_token::handler(ctx)
+    }
+    pub fn batch_withdraw_lp_tokens<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BatchWithdrawLpCtx<'info>>,
+    ) -> Result<()> {
+        instructions::batch_withdraw_lp_tokens::handler(ctx)
+    }
+    pub fn batch_withdraw<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BatchWithdrawCtx<'info>>,
+    ) -> Result<()> {
+        instructions::batch_withdraw::handler(ctx)
+    }
+    pub fn add_staking_program(
+        ctx: Context<AddStakingProgramCtx>,
+        staking_program: Pubkey,
+        escrow_seed: Option<Vec<u8>>,
+    ) -> Result<()> {
+        instructions::add_staking_program::handler(ctx, staking_program, escrow_seed)
+    }
+    pub fn remove_staking_program(ctx: Context<RemoveStakingProgramCtx>) -> Result<()> {
+        instructions::remove_staking_program::handler(ctx)
+    }
+    pub fn approve_claim(ctx: Context<ApproveClaimCtx>, args: ApproveClaimArgs) -> Result<()> {
+        instructions::approve_claim::handler(ctx, args)
+    }
+    pub fn revoke_claim_approval(ctx: Context<RevokeClaimApprovalCtx>) -> Result<()> {
+        instructions::revoke_claim_approval::handler(ctx)
+    }
+    pub fn quote_pool(ctx: Context<QuotePoolCtx>) -> Result<state::PoolQuote> {
+        instructions::quote_pool::handler(ctx)
+    }
+    pub fn quote_purchase_receipt(
+        ctx: Context<QuotePurchaseReceiptCtx>,
+    ) -> Result<state::PurchaseReceiptQuote> {
+        instructions::quote_purchase_receipt::handler(ctx)
+    }
+    pub fn sync_pool_status(ctx: Context<SyncPoolStatusCtx>) -> Result<()> {
+        instructions::sync_pool_status::handler(ctx)
+    }
+    pub fn migrate_pool(ctx: Context<MigratePoolCtx>) -> Result<()> {
+        instructions::migrate_pool::handler(ctx)
+    }
+    pub fn migrate_receipt(ctx: Context<MigrateReceiptCtx>) -> Result<()> {
+        instructions::migrate_receipt::handler(ctx)
+    }
 }
@@ -0,0 +1,259 @@
+//! Account layouts written by earlier versions of the program.
+//!
+//! They share the discriminator of the current layouts and are told apart by their size. The
+//! unversioned layouts were sized with `std::mem::size_of` of the struct plus the discriminator.
+use anchor_lang::prelude::*;
+
+use super::{
+    Pool, PoolStatus, PurchaseReceipt, POOL_RESERVED_SIZE, POOL_SIZE, POOL_VERSION,
+    PURCHASE_RECEIPT_RESERVED_SIZE, PURCHASE_RECEIPT_SIZE, PURCHASE_RECEIPT_VERSION,
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct LegacyPool {
+    pub bump: u8,
+    pub quote_mint: Pubkey,
+    pub requires_collection: bool,
+    pub delegate: Option<Pubkey>,
+    pub authority: Pubkey,
+    pub mint: Pubkey,
+    pub lp_mint: Option<Pubkey>,
+    pub lp_mint_supply: Option<u64>,
+    pub lp_mint_supply_for_creator: Option<u64>,
+    pub lp_mint_claimed_by_creator: u64,
+    pub lp_mint_last_claimed_by_creator: Option<i64>,
+    pub liquidity_collected: u64,
+    pub max_amount_per_purchase: Option<u64>,
+    pub creator_fee_basis_points: u16,
+    pub liquidity_pool_supply: u64,
+    pub initial_supply: u64,
+    pub initial_supply_for_creator: u64,
+    pub presale_target: u64,
+    pub presale_time_limit: i64,
+    pub vesting_period: u32,
+    pub vesting_started_at: Option<i64>,
+}
+pub const LEGACY_POOL_SIZE: usize = std::mem::size_of::<LegacyPool>() + 8;
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct LegacyPurchaseReceipt {
+    pub is_initialized: bool,
+    pub bump: u8,
+    pub pool: Pubkey,
+    pub amount: u64,
+    pub mint_elligible: Option<u64>,
+    pub mint_claimed: bool,
+    pub lp_elligible: Option<u64>,
+    pub original_mint: Pubkey,
+    pub lp_claimed: u64,
+    pub last_claimed_at: Option<i64>,
+}
+pub const LEGACY_PURCHASE_RECEIPT_SIZE: usize = std::mem::size_of::<LegacyPurchaseReceipt>() + 8;
+
+// Migration tells the layouts apart by size. The current ones must stay larger, so that an
+// account which was not migrated yet is too short to be loaded with the current layout.
+const _: () = assert!(LEGACY_POOL_SIZE < POOL_SIZE);
+const _: () = assert!(LEGACY_PURCHASE_RECEIPT_SIZE < PURCHASE_RECEIPT_SIZE);
+
+impl LegacyPool {
+    /// Upgrades to the current layout, the status is recomputed at `now`.
+    ///
+    /// Rewards used to be released in full, so the reward vesting periods are zero. The creator
+    /// reward claim was not tracked, a launched pool is treated as fully claimed by its creator
+    /// so that contributors' tokens can never be claimed twice. Receipts were not counted either,
+    /// they are counted again as they are migrated.
+    pub fn migrate(self, now: i64) -> Pool {
+        let mut pool = Pool {
+            version: POOL_VERSION,
+            bump: self.bump,
+            status: PoolStatus::Upcoming as u8,
+            requires_collection: self.requires_collection.into(),
+            frozen: false.into(),
+            dust_swept: false.into(),
+            receipts_tracked: false.into(),
+            padding: [0; 1],
+            lp_mint_supply: self.lp_mint_supply.into(),
+            lp_mint_supply_for_creator: self.lp_mint_supply_for_creator.into(),
+            lp_mint_claimed_by_creator: self.lp_mint_claimed_by_creator,
+            lp_mint_last_claimed_by_creator: self.lp_mint_last_claimed_by_creator.into(),
+            liquidity_collected: self.liquidity_collected,
+            max_amount_per_purchase: self.max_amount_per_purchase.into(),
+            liquidity_pool_supply: self.liquidity_pool_supply,
+            initial_supply: self.initial_supply,
+            initial_supply_for_creator: self.initial_supply_for_creator,
+            presale_target: self.presale_target,
+            presale_time_limit: self.presale_time_limit,
+            vesting_started_at: self.vesting_started_at.into(),
+            initial_supply_claimed_by_creator: if self.vesting_started_at.is_some() {
+                self.initial_supply_for_creator
+            } else {
+                0
+            },
+            initial_supply_last_claimed_by_creator: None.into(),
+            dissolve_vote_started_at: None.into(),
+            dissolve_votes: 0,
+            dissolved_at: None.into(),
+            lp_mint_dissolved: 0,
+            lp_mint_distributed: 0,
+            initial_supply_distributed: 0,
+            reward_supply_burned_at: None.into(),
+            claim_deadline: None.into(),
+            quote_mint: self.quote_mint,
+            authority: self.authority,
+            mint: self.mint,
+            payer: self.authority,
+            delegate: self.delegate.into(),
+            pending_authority: None.into(),
+            lp_mint: self.lp_mint.into(),
+            reclaim_destination: None.into(),
+            vesting_period: self.vesting_period,
+            reward_vesting_period: 0,
+            creator_reward_vesting_period: 0,
+            dissolve_vote_window: 0,
+            dissolve_round: 0,
+            purchase_receipt_count: 0,
+            purchase_receipts_checked: 0,
+            claim_period: None.into(),
+            creator_fee_basis_points: self.creator_fee_basis_points,
+            dissolve_quorum_basis_points: 0,
+            event_seq_padding: [0; 4],
+            event_seq: 0,
+            launch_payer: None.into(),
+            liquidity_checked_padding: [0; 7],
+            liquidity_checked: 0,
+            lp_mint_dissolved_claimed: 0,
+            liquidity_dissolved_settled: 0,
+            reserved: [0; POOL_RESERVED_SIZE],
+        };
+        pool.status = pool.status_at(now) as u8;
+        pool
+    }
+}
+
+impl LegacyPurchaseReceipt {
+    /// Upgrades to the current layout, `payer` becomes the rent recipient of the receipt and of
+    /// its claim token accounts.
+    pub fn migrate(self, payer: Pubkey) -> PurchaseReceipt {
+        let checked = self.lp_elligible.is_some();
+        PurchaseReceipt {
+            version: PURCHASE_RECEIPT_VERSION,
+            is_initialized: self.is_initialized,
+            bump: self.bump,
+            pool: self.pool,
+            amount: self.amount,
+            mint_elligible: self.mint_elligible,
+            mint_claimed: if self.mint_claimed {
+                self.mint_elligible.unwrap_or(0)
+            } else {
+                0
+            },
+            lp_elligible: self.lp_elligible,
+            original_mint: self.original_mint,
+            lp_claimed: self.lp_claimed,
+            last_claimed_at: self.last_claimed_at,
+            dissolved_lp_claimed: false,
+            payer,
+            claim_payer: checked.then_some(payer),
+            reclaimed: false,
+            direct_claim: false,
+            lp_approved_claim_at: None,
+            mint_approved_claim_at: None,
+            reserved: [0; PURCHASE_RECEIPT_RESERVED_SIZE],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn legacy_pool() -> LegacyPool {
+        LegacyPool {
+            bump: 255,
+            quote_mint: Pubkey::new_unique(),
+            requires_collection: true,
+            delegate: Some(Pubkey::new_unique()),
+            authority: Pubkey::new_unique(),
+            mint: Pubkey::new_unique(),
+            lp_mint: Some(Pubkey::new_unique()),
+            lp_mint_supply: Some(1_000_000),
+            lp_mint_supply_for_creator: Some(100_000),
+            lp_mint_claimed_by_creator: 10_000,
+            lp_mint_last_claimed_by_creator: Some(1_700_000_000),
+            liquidity_collected: 50_000,
+            max_amount_per_purchase: Some(5_000),
+            creator_fee_basis_points: 500,
+            liquidity_pool_supply: 800_000,
+            initial_supply: 200_000,
+            initial_supply_for_creator: 20_000,
+            presale_target: 50_000,
+            presale_time_limit: 1_690_000_000,
+            vesting_period: 30 * 24 * 60 * 60,
+            vesting_started_at: Some(1_690_000_100),
+        }
+    }
+
+    #[test]
+    fn migrated_pool_fits_and_keeps_state() {
+        let legacy = legacy_pool();
+        let bytes = legacy.try_to_vec().unwrap();
+        let pool = LegacyPool::deserialize(&mut &bytes[..])
+            .unwrap()
+            .migrate(1_700_000_000);
+
+        assert_eq!(pool.version, POOL_VERSION);
+        assert_eq!(pool.status(), PoolStatus::Launched);
+        assert_eq!(pool.liquidity_collected, legacy.liquidity_collected);
+        assert_eq!(
+            pool.lp_mint_claimed_by_creator,
+            legacy.lp_mint_claimed_by_creator
+        );
+        assert_eq!(
+            pool.initial_supply_claimed_by_creator,
+            legacy.initial_supply_for_creator
+        );
+        assert_eq!(pool.delegate.get(), legacy.delegate);
+        assert_eq!(pool.vesting_started_at.get(), legacy.vesting_started_at);
+        assert!(!pool.receipts_tracked.get());
+    }
+
+    #[test]
+    fn status_changes_take_the_next_event_seq() {
+        let mut pool = legacy_pool().migrate(1_700_000_000);
+        assert_eq!(pool.event_seq, 0);
+
+        pool.dissolved_at = Some(1_700_000_100).into();
+        let status_changed = pool
+            .transition(Pubkey::new_unique(), 1_700_000_100)
+            .unwrap();
+        assert_eq!(status_changed.status, PoolStatus::Cancelled);
+        assert_eq!(status_changed.event_seq, 1);
+        assert!(pool
+            .transition(Pubkey::new_unique(), 1_700_000_200)
+            .is_none());
+        assert_eq!(pool.next_event_seq(), 2);
+    }
+
+    #[test]
+    fn migrated_receipt_converts_reward_claim_flag() {
+        let payer = Pubkey::new_unique();
+        let legacy = LegacyPurchaseReceipt {
+            is_initialized: true,
+            bump: 254,
+            pool: Pubkey::new_unique(),
+            amount: 5_000,
+            mint_elligible: Some(20_000),
+            mint_claimed: true,
+            lp_elligible: Some(90_000),
+            original_mint: Pubkey::new_unique(),
+            lp_claimed: 1_000,
+            last_claimed_at: Some(1_700_000_000),
+        };
+        let purchase_receipt = legacy.migrate(payer);
+
+        assert_eq!(purchase_receipt.version, PURCHASE_RECEIPT_VERSION);
+        assert_eq!(purchase_receipt.mint_claimed, 20_000);
+        assert_eq!(purchase_receipt.claim_payer, Some(payer));
+        assert!(purchase_receipt.try_to_vec().unwrap().len() + 8 <= PURCHASE_RECEIPT_SIZE);
+    }
+}
@@ -1,3 +1,8 @@
+pub mod legacy;
+pub mod pod;
+#[allow(clippy::module_inception)]
 pub mod state;
 
+pub use legacy::*;
+pub use pod::*;
 pub use state::*;
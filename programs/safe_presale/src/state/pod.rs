@@ -0,0 +1,63 @@
+//! Plain old data stand-ins for the `bool` and `Option` fields of zero-copy accounts.
+use anchor_lang::prelude::*;
+
+#[zero_copy]
+#[derive(Default, Debug, PartialEq, Eq)]
+pub struct PodBool {
+    value: u8,
+}
+
+impl PodBool {
+    pub fn get(&self) -> bool {
+        self.value != 0
+    }
+}
+
+impl From<bool> for PodBool {
+    fn from(value: bool) -> Self {
+        PodBool {
+            value: value.into(),
+        }
+    }
+}
+
+macro_rules! pod_option {
+    ($name:ident, $ty:ty, $padding:expr) => {
+        #[zero_copy]
+        #[derive(Default, Debug, PartialEq, Eq)]
+        pub struct $name {
+            value: $ty,
+            is_some: u8,
+            padding: [u8; $padding],
+        }
+
+        impl $name {
+            pub fn get(&self) -> Option<$ty> {
+                (self.is_some != 0).then_some(self.value)
+            }
+
+            pub fn is_some(&self) -> bool {
+                self.is_some != 0
+            }
+
+            pub fn is_none(&self) -> bool {
+                self.is_some == 0
+            }
+        }
+
+        impl From<Option<$ty>> for $name {
+            fn from(value: Option<$ty>) -> Self {
+                $name {
+                    value: value.unwrap_or_default(),
+                    is_some: value.is_some().into(),
+                    padding: Default::default(),
+                }
+            }
+        }
+    };
+}
+
+pod_option!(PodOptionU64, u64, 7);
+pod_option!(PodOptionI64, i64, 7);
+pod_option!(PodOptionU32, u32, 3);
+pod_option!(PodOptionPubkey, Pubkey, 0);
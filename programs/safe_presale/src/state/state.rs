@@ -1,5 +1,8 @@
 use anchor_lang::{prelude::*, solana_program::program_pack::IsInitialized};
 
+use super::{PodBool, PodOptionI64, PodOptionPubkey, PodOptionU32, PodOptionU64};
+use crate::{error::CustomError, utils::U128};
+
 pub const FEE_COLLECTOR: &str = "73hCTYpoZNdFiwbh2PrW99ykAyNcQVfUwPMUhu9ogNTg";
 
 pub const RAYDIUM_CPMM_V4_DEVNET: &str = "CPMDWBwJDtYax9qW7AyRuVC19Cc4L4Vcy4n2BHAbHkCW";
@@ -11,54 +14,515 @@ pub const MINT_PREFIX: &str = "mint";
 pub const GRACE_PERIOD: i64 = 7 * 24 * 60 * 60;
 
 #[account]
+#[derive(InitSpace)]
+pub struct Config {
+    pub bump: u8,
+    pub admin: Pubkey,
+    pub paused: bool,
+}
+pub const CONFIG_PREFIX: &str = "config";
+pub const CONFIG_SIZE: usize = 8 + Config::INIT_SPACE;
+
+#[account(zero_copy)]
 pub struct Pool {
+    pub version: u8,
     pub bump: u8,
-    pub quote_mint: Pubkey,
-    pub requires_collection: bool,
-    pub delegate: Option<Pubkey>,
-    pub authority: Pubkey,
-    pub mint: Pubkey,
-    pub lp_mint: Option<Pubkey>,
-    pub lp_mint_supply: Option<u64>,
-    pub lp_mint_supply_for_creator: Option<u64>,
+    /// `PoolStatus` discriminant, read it with `status`
+    pub status: u8,
+    pub requires_collection: PodBool,
+    pub frozen: PodBool,
+    pub dust_swept: PodBool,
+    /// False for pools migrated from the unversioned layout, whose receipts were never counted
+    pub receipts_tracked: PodBool,
+    pub padding: [u8; 1],
+    pub lp_mint_supply: PodOptionU64,
+    pub lp_mint_supply_for_creator: PodOptionU64,
     pub lp_mint_claimed_by_creator: u64,
-    pub lp_mint_last_claimed_by_creator: Option<i64>,
+    pub lp_mint_last_claimed_by_creator: PodOptionI64,
     pub liquidity_collected: u64,
-    pub max_amount_per_purchase: Option<u64>,
-    pub creator_fee_basis_points: u16,
+    pub max_amount_per_purchase: PodOptionU64,
     pub liquidity_pool_supply: u64,
     pub initial_supply: u64,
     pub initial_supply_for_creator: u64,
     pub presale_target: u64,
     pub presale_time_limit: i64,
+    pub vesting_started_at: PodOptionI64,
+    pub initial_supply_claimed_by_creator: u64,
+    pub initial_supply_last_claimed_by_creator: PodOptionI64,
+    pub dissolve_vote_started_at: PodOptionI64,
+    pub dissolve_votes: u64,
+    pub dissolved_at: PodOptionI64,
+    pub lp_mint_dissolved: u64,
+    pub lp_mint_distributed: u64,
+    pub initial_supply_distributed: u64,
+    pub reward_supply_burned_at: PodOptionI64,
+    pub claim_deadline: PodOptionI64,
+    pub quote_mint: Pubkey,
+    pub authority: Pubkey,
+    pub mint: Pubkey,
+    pub payer: Pubkey,
+    pub delegate: PodOptionPubkey,
+    pub pending_authority: PodOptionPubkey,
+    pub lp_mint: PodOptionPubkey,
+    pub reclaim_destination: PodOptionPubkey,
     pub vesting_period: u32,
-    pub vesting_started_at: Option<i64>,
+    pub reward_vesting_period: u32,
+    pub creator_reward_vesting_period: u32,
+    pub dissolve_vote_window: u32,
+    pub dissolve_round: u32,
+    pub purchase_receipt_count: u32,
+    pub purchase_receipts_checked: u32,
+    pub claim_period: PodOptionU32,
+    pub creator_fee_basis_points: u16,
+    pub dissolve_quorum_basis_points: u16,
+    pub event_seq_padding: [u8; 4],
+    /// Sequence number of the last event emitted for the pool, gaps mean missed events
+    pub event_seq: u64,
+    /// Paid for the pool lp token account at launch, its rent goes back to them on close
+    pub launch_payer: PodOptionPubkey,
+    pub liquidity_checked_padding: [u8; 7],
+    /// Contributions of the receipts counted in `purchase_receipts_checked`
+    pub liquidity_checked: u64,
+    /// Dissolved lp tokens paid out to contributors
+    pub lp_mint_dissolved_claimed: u64,
+    /// Contributions whose share of the dissolved lp tokens was paid out
+    pub liquidity_dissolved_settled: u64,
+    pub reserved: [u8; POOL_RESERVED_SIZE],
+}
+
+impl Pool {
+    /// Share of the contributors' lp tokens owed for `amount` contributed, rounded down.
+    pub fn lp_entitlement(&self, amount: u64) -> Result<u64> {
+        let lp_mint_supply_after_creator_fees = self
+            .lp_mint_supply
+            .get()
+            .unwrap()
+            .checked_sub(self.lp_mint_supply_for_creator.get().unwrap())
+            .ok_or(CustomError::IntegerOverflow)?;
+        self.pro_rata(amount, lp_mint_supply_after_creator_fees)
+    }
+
+    /// Share of the contributors' reward tokens owed for `amount` contributed, rounded down.
+    pub fn mint_entitlement(&self, amount: u64) -> Result<u64> {
+        let initial_mint_supply_after_creator_fees = self
+            .initial_supply
+            .checked_sub(self.initial_supply_for_creator)
+            .ok_or(CustomError::IntegerOverflow)?;
+        self.pro_rata(amount, initial_mint_supply_after_creator_fees)
+    }
+
+    /// Share of the dissolved creator lp tokens owed for `amount` contributed, rounded down.
+    pub fn dissolved_lp_entitlement(&self, amount: u64) -> Result<u64> {
+        self.pro_rata(amount, self.lp_mint_dissolved)
+    }
+
+    /// Wallet receiving reclaimed allocations, the authority unless a community pool was configured.
+    pub fn reclaim_wallet(&self) -> Pubkey {
+        self.reclaim_destination.get().unwrap_or(self.authority)
+    }
+
+    /// Lifecycle status of the pool at `now`.
+    ///
+    /// This is the single transition function of the pool. `status` only stores the last
+    /// status seen by an instruction, the time based transitions (to Failed or Expired)
+    /// happen without one, so contexts should always check `current_status`.
+    pub fn status_at(&self, now: i64) -> PoolStatus {
+        if self.dissolved_at.is_some() {
+            return PoolStatus::Cancelled;
+        }
+        // Burning the reward supply is terminal, the pool keeps the status it was burned in
+        if self.reward_supply_burned_at.is_some() {
+            return self.status();
+        }
+        if self.vesting_started_at.is_some() {
+            return PoolStatus::Launched;
+        }
+        if self.liquidity_collected == self.presale_target {
+            // Do not allow project to launch after the 7 day grace period
+            if now > self.presale_time_limit + GRACE_PERIOD {
+                return PoolStatus::Expired;
+            }
+            return PoolStatus::Filled;
+        }
+        if now >= self.presale_time_limit {
+            return PoolStatus::Failed;
+        }
+        if self.liquidity_collected == 0 {
+            return PoolStatus::Upcoming;
+        }
+        PoolStatus::Open
+    }
+
+    /// Last status stored by `transition`.
+    pub fn status(&self) -> PoolStatus {
+        PoolStatus::ALL[usize::from(self.status)]
+    }
+
+    pub fn current_status(&self) -> Result<PoolStatus> {
+        Ok(self.status_at(Clock::get()?.unix_timestamp))
+    }
+
+    /// Stores the status at `now`, returning the change to emit if there was one.
+    pub fn transition(&mut self, pool: Pubkey, now: i64) -> Option<PoolStatusChanged> {
+        let status = self.status_at(now);
+        let previous_status = self.status();
+        if status == previous_status {
+            return None;
+        }
+        self.status = status as u8;
+        Some(PoolStatusChanged {
+            pool,
+            previous_status,
+            status,
+            changed_at: now,
+            event_seq: self.next_event_seq(),
+        })
+    }
+
+    /// Sequence number of the next event emitted for the pool. Events must be emitted in the
+    /// order their numbers were taken.
+    pub fn next_event_seq(&mut self) -> u64 {
+        self.event_seq += 1;
+        self.event_seq
+    }
+
+    /// Withdrawal criteria
+    /// 1. Only allow withdrawal after presale has ended.
+    /// 2. If presale target amount is not met, withdrawal is allowed immediately.
+    /// 3. If presale target amount is met, only allow withdrawal after the creator failed to launch the project after 7 days.
+    pub fn check_refundable(&self, now: i64) -> Result<()> {
+        match self.status_at(now) {
+            PoolStatus::Failed | PoolStatus::Expired => Ok(()),
+            PoolStatus::Upcoming | PoolStatus::Open => {
+                Err(error!(CustomError::UnauthorizedAtCurrentTime))
+            }
+            PoolStatus::Filled => Err(error!(CustomError::WaitingForCreatorToLaunch)),
+            PoolStatus::Launched | PoolStatus::Cancelled => {
+                Err(error!(CustomError::TokenHasLaunched))
+            }
+        }
+    }
+
+    /// Accounts for a receipt of `amount` whose entitlement has been settled, either by checking or reclaiming it.
+    pub fn record_checked_receipt(
+        &mut self,
+        amount: u64,
+        lp_elligible: u64,
+        mint_elligible: u64,
+    ) -> Result<()> {
+        self.liquidity_checked = self
+            .liquidity_checked
+            .checked_add(amount)
+            .ok_or(CustomError::IntegerOverflow)?;
+        self.lp_mint_distributed = self
+            .lp_mint_distributed
+            .checked_add(lp_elligible)
+            .ok_or(CustomError::IntegerOverflow)?;
+        self.initial_supply_distributed = self
+            .initial_supply_distributed
+            .checked_add(mint_elligible)
+            .ok_or(CustomError::IntegerOverflow)?;
+        self.purchase_receipts_checked = self
+            .purchase_receipts_checked
+            .checked_add(1)
+            .ok_or(CustomError::IntegerOverflow)?;
+        Ok(())
+    }
+
+    /// Dissolve Criteria
+    /// 1. Votes are weighted by the amount contributed to the presale.
+    /// 2. A round lasts for the dissolve vote window, starting from its first vote. Votes from an expired round are discarded.
+    /// 3. Once the quorum is reached, the creator's unvested lp tokens are redistributed to contributors.
+    ///
+    /// Counts `votes` of a receipt that last voted in `voted_round`, returning whether the quorum is reached.
+    pub fn record_dissolve_vote(&mut self, voted_round: u32, votes: u64, now: i64) -> Result<bool> {
+        let round_has_expired = match self.dissolve_vote_started_at.get() {
+            Some(started_at) => {
+                now >= started_at
+                    .checked_add(self.dissolve_vote_window.into())
+                    .ok_or(CustomError::IntegerOverflow)?
+            }
+            None => true,
+        };
+        if round_has_expired {
+            self.dissolve_round = self
+                .dissolve_round
+                .checked_add(1)
+                .ok_or(CustomError::IntegerOverflow)?;
+            self.dissolve_votes = 0;
+            self.dissolve_vote_started_at = Some(now).into();
+        }
+        require!(
+            voted_round != self.dissolve_round,
+            CustomError::AlreadyVoted
+        );
+
+        self.dissolve_votes = self
+            .dissolve_votes
+            .checked_add(votes)
+            .ok_or(CustomError::IntegerOverflow)?;
+
+        let quorum_reached = U128::from(self.dissolve_votes)
+            .checked_mul(U128::from(10000))
+            .ok_or(CustomError::IntegerOverflow)?
+            >= U128::from(self.liquidity_collected)
+                .checked_mul(U128::from(self.dissolve_quorum_basis_points))
+                .ok_or(CustomError::IntegerOverflow)?;
+        Ok(quorum_reached)
+    }
+
+    /// Dissolves the pool at `now`, setting aside the creator lp tokens that are not claimed yet.
+    pub fn dissolve(&mut self, now: i64) -> Result<()> {
+        self.dissolved_at = Some(now).into();
+        self.lp_mint_dissolved = self
+            .lp_mint_supply_for_creator
+            .get()
+            .unwrap()
+            .checked_sub(self.lp_mint_claimed_by_creator)
+            .ok_or(CustomError::IntegerOverflow)?;
+        Ok(())
+    }
+
+    /// Accounts for the dissolved `lp_claimed` paid to a receipt of `amount`.
+    pub fn record_dissolved_lp_claim(&mut self, amount: u64, lp_claimed: u64) -> Result<()> {
+        self.lp_mint_dissolved_claimed = self
+            .lp_mint_dissolved_claimed
+            .checked_add(lp_claimed)
+            .ok_or(CustomError::IntegerOverflow)?;
+        self.liquidity_dissolved_settled = self
+            .liquidity_dissolved_settled
+            .checked_add(amount)
+            .ok_or(CustomError::IntegerOverflow)?;
+        Ok(())
+    }
+
+    /// Rounding dust left in the pool vaults, as `(lp_dust, mint_dust)`.
+    ///
+    /// The share of the receipts not checked yet is held back so they can still be reclaimed,
+    /// and so is the dissolved lp share of the receipts that did not claim it yet. Both are
+    /// floored over all of them at once, which never falls below the sum of their floored
+    /// entitlements.
+    pub fn dust(&self) -> Result<(u64, u64)> {
+        let lp_mint_supply_after_creator_fees = self
+            .lp_mint_supply
+            .get()
+            .unwrap()
+            .checked_sub(self.lp_mint_supply_for_creator.get().unwrap())
+            .ok_or(CustomError::IntegerOverflow)?;
+        let initial_mint_supply_after_creator_fees = self
+            .initial_supply
+            .checked_sub(self.initial_supply_for_creator)
+            .ok_or(CustomError::IntegerOverflow)?;
+        let liquidity_unchecked = self
+            .liquidity_collected
+            .checked_sub(self.liquidity_checked)
+            .ok_or(CustomError::IntegerOverflow)?;
+        let liquidity_dissolved_unsettled = self
+            .liquidity_collected
+            .checked_sub(self.liquidity_dissolved_settled)
+            .ok_or(CustomError::IntegerOverflow)?;
+        let lp_unchecked = self.pro_rata(liquidity_unchecked, lp_mint_supply_after_creator_fees)?;
+        let mint_unchecked =
+            self.pro_rata(liquidity_unchecked, initial_mint_supply_after_creator_fees)?;
+        let lp_dissolved_unclaimed =
+            self.pro_rata(liquidity_dissolved_unsettled, self.lp_mint_dissolved)?;
+
+        let lp_dust = lp_mint_supply_after_creator_fees
+            .checked_sub(self.lp_mint_distributed)
+            .and_then(|result| result.checked_sub(lp_unchecked))
+            .and_then(|result| result.checked_add(self.lp_mint_dissolved))
+            .and_then(|result| result.checked_sub(self.lp_mint_dissolved_claimed))
+            .and_then(|result| result.checked_sub(lp_dissolved_unclaimed))
+            .ok_or(CustomError::IntegerOverflow)?;
+        let mint_dust = initial_mint_supply_after_creator_fees
+            .checked_sub(self.initial_supply_distributed)
+            .and_then(|result| result.checked_sub(mint_unchecked))
+            .ok_or(CustomError::IntegerOverflow)?;
+        Ok((lp_dust, mint_dust))
+    }
+
+    fn pro_rata(&self, amount: u64, supply: u64) -> Result<u64> {
+        let result = U128::from(amount)
+            .checked_mul(U128::from(supply))
+            .and_then(|result| result.checked_div(U128::from(self.liquidity_collected)))
+            .map(|result| result.as_u64())
+            .ok_or(CustomError::IntegerOverflow)?;
+        Ok(result)
+    }
 }
 pub const POOL_PREFIX: &str = "pool";
-pub const POOL_SIZE: usize = std::mem::size_of::<Pool>() + 8;
+pub const POOL_SIZE: usize = 8 + std::mem::size_of::<Pool>();
+pub const POOL_VERSION: u8 = 1;
+/// Room left for new fields, they take bytes from here so existing pools keep their size.
+pub const POOL_RESERVED_SIZE: usize = 48;
+
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum PoolStatus {
+    /// Accepting purchases, none made yet
+    Upcoming,
+    /// Accepting purchases
+    Open,
+    /// Presale target reached, waiting for the creator to launch
+    Filled,
+    Launched,
+    /// Presale ended without reaching its target
+    Failed,
+    /// Presale target reached but the creator did not launch within the grace period
+    Expired,
+    /// Dissolved by contributors after launch
+    Cancelled,
+}
+
+impl PoolStatus {
+    /// Indexed by discriminant.
+    pub const ALL: [PoolStatus; 7] = [
+        PoolStatus::Upcoming,
+        PoolStatus::Open,
+        PoolStatus::Filled,
+        PoolStatus::Launched,
+        PoolStatus::Failed,
+        PoolStatus::Expired,
+        PoolStatus::Cancelled,
+    ];
+
+    pub fn accepts_purchases(self) -> bool {
+        matches!(self, PoolStatus::Upcoming | PoolStatus::Open)
+    }
+
+    pub fn has_launched(self) -> bool {
+        matches!(self, PoolStatus::Launched | PoolStatus::Cancelled)
+    }
+}
+
+/// Returned by `quote_pool`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PoolQuote {
+    pub status: PoolStatus,
+    pub liquidity_collected: u64,
+    pub presale_target: u64,
+    pub presale_time_limit: i64,
+    pub vesting_started_at: Option<i64>,
+    pub claim_deadline: Option<i64>,
+    pub creator_lp_claimable: u64,
+    pub creator_mint_claimable: u64,
+}
+
+/// Returned by `quote_purchase_receipt`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PurchaseReceiptQuote {
+    pub status: PoolStatus,
+    pub refundable: u64,
+    pub lp_elligible: u64,
+    pub mint_elligible: u64,
+    pub lp_claimable: u64,
+    pub mint_claimable: u64,
+    pub dissolved_lp_claimable: u64,
+}
 
 #[account]
+#[derive(InitSpace)]
 pub struct PurchaseAuthorisationRecord {
     pub pool: Pubkey,
     pub collection_mint: Pubkey,
     pub bump: u8,
 }
 pub const PURCHASE_AUTHORISATION_PREFIX: &str = "authorisation";
-pub const PURCHASE_AUTHORISATION_SIZE: usize =
-    std::mem::size_of::<PurchaseAuthorisationRecord>() + 8;
+pub const PURCHASE_AUTHORISATION_SIZE: usize = 8 + PurchaseAuthorisationRecord::INIT_SPACE;
 
 #[account]
+#[derive(InitSpace)]
+pub struct StakingProgramRecord {
+    pub pool: Pubkey,
+    pub staking_program: Pubkey,
+    /// Seed prefix of the staking program's vault PDA, `[escrow_seed, nft_owner]`, when it escrows nfts
+    #[max_len(MAX_ESCROW_SEED_LEN)]
+    pub escrow_seed: Option<Vec<u8>>,
+    pub bump: u8,
+}
+pub const STAKING_PROGRAM_PREFIX: &str = "staking_program";
+pub const MAX_ESCROW_SEED_LEN: usize = 32;
+pub const STAKING_PROGRAM_RECORD_SIZE: usize = 8 + StakingProgramRecord::INIT_SPACE;
+
+#[account]
+#[derive(InitSpace)]
+pub struct ClaimApproval {
+    pub owner: Pubkey,
+    pub delegate: Pubkey,
+    /// Restricts the approval to a single purchase receipt, otherwise every receipt of the owner
+    pub purchase_receipt: Option<Pubkey>,
+    pub expires_at: Option<i64>,
+    pub max_lp_per_claim: Option<u64>,
+    pub max_mint_per_claim: Option<u64>,
+    pub bump: u8,
+}
+
+impl ClaimApproval {
+    /// Last seed of the approval, the purchase receipt it is scoped to or nothing when it covers
+    /// every receipt of the owner.
+    pub fn scope_seed(purchase_receipt: &Option<Pubkey>) -> &[u8] {
+        purchase_receipt
+            .as_ref()
+            .map_or(&[], |purchase_receipt| purchase_receipt.as_ref())
+    }
+}
+pub const CLAIM_APPROVAL_PREFIX: &str = "claim_approval";
+pub const CLAIM_APPROVAL_SIZE: usize = 8 + ClaimApproval::INIT_SPACE;
+
+#[account]
+#[derive(InitSpace)]
 pub struct PurchaseReceipt {
+    pub version: u8,
     pub is_initialized: bool,
     pub bump: u8,
     pub pool: Pubkey,
     pub amount: u64,
     pub mint_elligible: Option<u64>,
-    pub mint_claimed: bool,
+    pub mint_claimed: u64,
     pub lp_elligible: Option<u64>,
     pub original_mint: Pubkey,
     pub lp_claimed: u64,
     pub last_claimed_at: Option<i64>,
+    pub dissolved_lp_claimed: bool,
+    pub payer: Pubkey,
+    pub claim_payer: Option<Pubkey>,
+    pub reclaimed: bool,
+    pub direct_claim: bool,
+    /// Last lp claim made through a claim approval
+    pub lp_approved_claim_at: Option<i64>,
+    /// Last reward claim made through a claim approval
+    pub mint_approved_claim_at: Option<i64>,
+    pub reserved: [u8; PURCHASE_RECEIPT_RESERVED_SIZE],
+}
+
+impl PurchaseReceipt {
+    /// Settles the entitlement of a receipt paid straight from the pool vaults, once.
+    ///
+    /// Like `check_claim_ellgibility`, settling is stopped while the program is paused or the
+    /// pool is frozen, receipts settled before can still claim.
+    pub fn settle_direct_claim(
+        &mut self,
+        pool: &mut Pool,
+        config: &Config,
+        now: i64,
+    ) -> Result<()> {
+        require!(!self.reclaimed, CustomError::ReceiptReclaimed);
+        if self.lp_elligible.is_some() {
+            require!(self.direct_claim, CustomError::EscrowedClaimReceipt);
+            return Ok(());
+        }
+        require!(!config.paused, CustomError::ProgramPaused);
+        require!(!pool.frozen.get(), CustomError::PoolFrozen);
+        if let Some(claim_deadline) = pool.claim_deadline.get() {
+            require!(now < claim_deadline, CustomError::ClaimDeadlinePassed);
+        }
+        let lp_elligible = pool.lp_entitlement(self.amount)?;
+        let mint_elligible = pool.mint_entitlement(self.amount)?;
+        self.lp_elligible = Some(lp_elligible);
+        self.mint_elligible = Some(mint_elligible);
+        self.direct_claim = true;
+        pool.record_checked_receipt(self.amount, lp_elligible, mint_elligible)
+    }
 }
 
 impl IsInitialized for PurchaseReceipt {
@@ -68,7 +532,21 @@ impl IsInitialized for PurchaseReceipt {
 }
 
 pub const PURCHASE_RECEIPT_PREFIX: &str = "receipt";
-pub const PURCHASE_RECEIPT_SIZE: usize = std::mem::size_of::<PurchaseReceipt>() + 8;
+pub const PURCHASE_RECEIPT_SIZE: usize = 8 + PurchaseReceipt::INIT_SPACE;
+pub const PURCHASE_RECEIPT_VERSION: u8 = 1;
+/// Room left for new fields, they take bytes from here so existing receipts keep their size.
+pub const PURCHASE_RECEIPT_RESERVED_SIZE: usize = 46;
+
+#[account]
+#[derive(InitSpace)]
+pub struct DissolveVoteRecord {
+    pub pool: Pubkey,
+    pub purchase_receipt: Pubkey,
+    pub round: u32,
+    pub bump: u8,
+}
+pub const DISSOLVE_VOTE_PREFIX: &str = "dissolve_vote";
+pub const DISSOLVE_VOTE_RECORD_SIZE: usize = 8 + DissolveVoteRecord::INIT_SPACE;
 
 #[event]
 pub struct InitializedPoolEvent {
@@ -84,9 +562,16 @@ pub struct InitializedPoolEvent {
     pub initial_supply_for_creator: u64,
     pub decimal: u8,
     pub vesting_period: u32,
+    pub reward_vesting_period: u32,
+    pub creator_reward_vesting_period: u32,
+    pub dissolve_quorum_basis_points: u16,
+    pub dissolve_vote_window: u32,
+    pub claim_period: Option<u32>,
+    pub reclaim_destination: Option<Pubkey>,
     pub max_amount_per_purchase: Option<u64>,
     pub requires_collection: bool,
     pub quote_mint: Pubkey,
+    pub event_seq: u64,
 }
 
 #[event]
@@ -94,6 +579,41 @@ pub struct CreatePurchaseAuthorisationEvent {
     pub payer: Pubkey,
     pub collection_mint: Pubkey,
     pub pool: Pubkey,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct StakingProgramAddedEvent {
+    pub authority: Pubkey,
+    pub pool: Pubkey,
+    pub staking_program: Pubkey,
+    pub escrow_seed: Option<Vec<u8>>,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct StakingProgramRemovedEvent {
+    pub authority: Pubkey,
+    pub pool: Pubkey,
+    pub staking_program: Pubkey,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct ClaimApprovalUpdatedEvent {
+    pub owner: Pubkey,
+    pub delegate: Pubkey,
+    pub purchase_receipt: Option<Pubkey>,
+    pub expires_at: Option<i64>,
+    pub max_lp_per_claim: Option<u64>,
+    pub max_mint_per_claim: Option<u64>,
+}
+
+#[event]
+pub struct ClaimApprovalRevokedEvent {
+    pub owner: Pubkey,
+    pub delegate: Pubkey,
+    pub purchase_receipt: Option<Pubkey>,
 }
 
 #[event]
@@ -102,6 +622,11 @@ pub struct PurchasedPresaleEvent {
     pub amount: u64,
     pub pool: Pubkey,
     pub original_mint: Pubkey,
+    /// Total contributed through the purchase receipt
+    pub receipt_amount: u64,
+    pub liquidity_collected: u64,
+    pub purchase_receipt_count: u32,
+    pub event_seq: u64,
 }
 
 #[event]
@@ -111,6 +636,10 @@ pub struct CheckClaimEvent {
     pub original_mint: Pubkey,
     pub lp_elligible: u64,
     pub mint_elligible: u64,
+    pub lp_mint_distributed: u64,
+    pub initial_supply_distributed: u64,
+    pub purchase_receipts_checked: u32,
+    pub event_seq: u64,
 }
 
 #[event]
@@ -119,14 +648,21 @@ pub struct ClaimRewardEvent {
     pub pool: Pubkey,
     pub original_mint: Pubkey,
     pub original_mint_owner: Pubkey,
-    pub mint_elligible: u64,
+    pub mint_claimed: u64,
+    pub last_claimed_at: i64,
+    /// Total claimed through the purchase receipt
+    pub total_mint_claimed: u64,
+    pub event_seq: u64,
 }
 
 #[event]
 pub struct ClaimRewardForCreatorEvent {
     pub payer: Pubkey,
     pub pool: Pubkey,
-    pub mint_elligible: u64,
+    pub mint_claimed: u64,
+    pub last_claimed_at: i64,
+    pub initial_supply_claimed_by_creator: u64,
+    pub event_seq: u64,
 }
 
 #[event]
@@ -135,6 +671,8 @@ pub struct WithdrawLpTokenForCreatorEvent {
     pub pool: Pubkey,
     pub lp_claimed: u64,
     pub last_claimed_at: i64,
+    pub lp_mint_claimed_by_creator: u64,
+    pub event_seq: u64,
 }
 
 #[event]
@@ -145,6 +683,9 @@ pub struct WithdrawLpTokenEvent {
     pub last_claimed_at: i64,
     pub original_mint: Pubkey,
     pub original_mint_owner: Pubkey,
+    /// Total claimed through the purchase receipt
+    pub total_lp_claimed: u64,
+    pub event_seq: u64,
 }
 
 #[event]
@@ -156,6 +697,10 @@ pub struct LaunchTokenAmmEvent {
     pub amount_lp_received: u64,
     pub lp_mint: Pubkey,
     pub vesting_started_at: i64,
+    pub claim_deadline: Option<i64>,
+    pub lp_mint_supply: u64,
+    pub lp_mint_supply_for_creator: u64,
+    pub event_seq: u64,
 }
 
 #[event]
@@ -165,4 +710,517 @@ pub struct WithdrawEvent {
     pub original_mint: Pubkey,
     pub amount_withdrawn: u64,
     pub original_mint_owner: Pubkey,
+    pub purchase_receipt: Pubkey,
+    /// The purchase receipt is closed by the withdrawal
+    pub purchase_receipt_closed: bool,
+    pub purchase_receipt_count: u32,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct DissolveVoteEvent {
+    pub payer: Pubkey,
+    pub pool: Pubkey,
+    pub original_mint: Pubkey,
+    pub votes: u64,
+    pub dissolve_votes: u64,
+    pub dissolve_round: u32,
+    pub dissolve_vote_started_at: i64,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct PoolDissolvedEvent {
+    pub pool: Pubkey,
+    pub lp_mint_dissolved: u64,
+    pub dissolved_at: i64,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct ClaimDissolvedLpTokenEvent {
+    pub payer: Pubkey,
+    pub pool: Pubkey,
+    pub lp_claimed: u64,
+    pub original_mint: Pubkey,
+    pub original_mint_owner: Pubkey,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct DelegateUpdatedEvent {
+    pub authority: Pubkey,
+    pub pool: Pubkey,
+    pub previous_delegate: Option<Pubkey>,
+    pub delegate: Option<Pubkey>,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct AuthorityTransferProposedEvent {
+    pub authority: Pubkey,
+    pub pool: Pubkey,
+    pub pending_authority: Option<Pubkey>,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct AuthorityTransferredEvent {
+    pub previous_authority: Pubkey,
+    pub authority: Pubkey,
+    pub pool: Pubkey,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct InitializedConfigEvent {
+    pub payer: Pubkey,
+    pub admin: Pubkey,
+}
+
+#[event]
+pub struct ProgramPausedEvent {
+    pub admin: Pubkey,
+    pub paused: bool,
+}
+
+#[event]
+pub struct PoolFrozenEvent {
+    pub admin: Pubkey,
+    pub pool: Pubkey,
+    pub frozen: bool,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct PoolUpdatedEvent {
+    pub authority: Pubkey,
+    pub pool: Pubkey,
+    pub presale_target: u64,
+    pub presale_time_limit: i64,
+    pub vesting_period: u32,
+    pub reward_vesting_period: u32,
+    pub creator_reward_vesting_period: u32,
+    pub max_amount_per_purchase: Option<u64>,
+    pub uri: Option<String>,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct ClosePurchaseReceiptEvent {
+    pub payer: Pubkey,
+    pub pool: Pubkey,
+    pub original_mint: Pubkey,
+    pub purchase_receipt_count: u32,
+    pub purchase_receipts_checked: u32,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct ClosePoolEvent {
+    pub payer: Pubkey,
+    pub pool: Pubkey,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct SweepDustEvent {
+    pub payer: Pubkey,
+    pub pool: Pubkey,
+    pub lp_dust: u64,
+    pub mint_dust: u64,
+    pub burned: bool,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct BurnRewardSupplyEvent {
+    pub payer: Pubkey,
+    pub pool: Pubkey,
+    pub amount_burned: u64,
+    pub burned_at: i64,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct ReclaimPurchaseReceiptEvent {
+    pub payer: Pubkey,
+    pub pool: Pubkey,
+    pub original_mint: Pubkey,
+    pub destination: Pubkey,
+    pub lp_reclaimed: u64,
+    pub mint_reclaimed: u64,
+    pub lp_mint_distributed: u64,
+    pub initial_supply_distributed: u64,
+    pub purchase_receipts_checked: u32,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct ClaimAllEvent {
+    pub payer: Pubkey,
+    pub pool: Pubkey,
+    pub lp_claimed: u64,
+    pub mint_claimed: u64,
+    pub last_claimed_at: i64,
+    pub original_mint: Pubkey,
+    pub original_mint_owner: Pubkey,
+    pub lp_elligible: u64,
+    pub mint_elligible: u64,
+    /// Total claimed through the purchase receipt
+    pub total_lp_claimed: u64,
+    /// Total claimed through the purchase receipt
+    pub total_mint_claimed: u64,
+    pub lp_mint_distributed: u64,
+    pub initial_supply_distributed: u64,
+    pub purchase_receipts_checked: u32,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct BatchClaimRewardEvent {
+    pub payer: Pubkey,
+    pub pool: Pubkey,
+    pub mint_claimed: u64,
+    pub last_claimed_at: i64,
+    pub original_mints: Vec<Pubkey>,
+    pub original_mint_owner: Pubkey,
+    /// Total claimed through each purchase receipt, in the order of `original_mints`
+    pub total_mint_claimed: Vec<u64>,
+    pub lp_mint_distributed: u64,
+    pub initial_supply_distributed: u64,
+    pub purchase_receipts_checked: u32,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct BatchWithdrawLpTokenEvent {
+    pub payer: Pubkey,
+    pub pool: Pubkey,
+    pub lp_claimed: u64,
+    pub last_claimed_at: i64,
+    pub original_mints: Vec<Pubkey>,
+    pub original_mint_owner: Pubkey,
+    /// Total claimed through each purchase receipt, in the order of `original_mints`
+    pub total_lp_claimed: Vec<u64>,
+    pub lp_mint_distributed: u64,
+    pub initial_supply_distributed: u64,
+    pub purchase_receipts_checked: u32,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct BatchWithdrawEvent {
+    pub payer: Pubkey,
+    pub pool: Pubkey,
+    pub amount_withdrawn: u64,
+    pub original_mints: Vec<Pubkey>,
+    pub original_mint_owner: Pubkey,
+    /// The purchase receipts are closed by the withdrawal
+    pub purchase_receipts_closed: bool,
+    pub purchase_receipt_count: u32,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct PoolStatusChanged {
+    pub pool: Pubkey,
+    pub previous_status: PoolStatus,
+    pub status: PoolStatus,
+    pub changed_at: i64,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct MigratePoolEvent {
+    pub pool: Pubkey,
+    pub version: u8,
+    pub status: PoolStatus,
+    pub payer: Pubkey,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct MigratePurchaseReceiptEvent {
+    pub purchase_receipt: Pubkey,
+    pub pool: Pubkey,
+    pub version: u8,
+    pub original_mint: Pubkey,
+    pub payer: Pubkey,
+    pub purchase_receipt_count: u32,
+    pub lp_mint_distributed: u64,
+    pub initial_supply_distributed: u64,
+    pub purchase_receipts_checked: u32,
+    pub event_seq: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WINDOW: u32 = 24 * 60 * 60;
+
+    /// Launched pool with 10_000 collected and a 50% dissolve quorum.
+    fn launched_pool() -> Pool {
+        let mut pool: Pool = bytemuck::Zeroable::zeroed();
+        pool.liquidity_collected = 10_000;
+        pool.lp_mint_supply_for_creator = Some(1_000).into();
+        pool.lp_mint_claimed_by_creator = 300;
+        pool.dissolve_quorum_basis_points = 5_000;
+        pool.dissolve_vote_window = WINDOW;
+        pool
+    }
+
+    fn config(paused: bool) -> Config {
+        Config {
+            bump: 255,
+            admin: Pubkey::new_unique(),
+            paused,
+        }
+    }
+
+    fn purchase_receipt(amount: u64) -> PurchaseReceipt {
+        PurchaseReceipt {
+            version: PURCHASE_RECEIPT_VERSION,
+            is_initialized: true,
+            bump: 255,
+            pool: Pubkey::new_unique(),
+            amount,
+            mint_elligible: None,
+            mint_claimed: 0,
+            lp_elligible: None,
+            original_mint: Pubkey::new_unique(),
+            lp_claimed: 0,
+            last_claimed_at: None,
+            dissolved_lp_claimed: false,
+            payer: Pubkey::new_unique(),
+            claim_payer: None,
+            reclaimed: false,
+            direct_claim: false,
+            lp_approved_claim_at: None,
+            mint_approved_claim_at: None,
+            reserved: [0; PURCHASE_RECEIPT_RESERVED_SIZE],
+        }
+    }
+
+    /// Launched pool that minted 2_000 lp tokens, 200 of them to the creator.
+    fn vesting_pool() -> Pool {
+        let mut pool = launched_pool();
+        pool.lp_mint_supply = Some(2_000).into();
+        pool.lp_mint_supply_for_creator = Some(200).into();
+        pool.initial_supply = 1_000;
+        pool.vesting_started_at = Some(0).into();
+        pool
+    }
+
+    #[test]
+    fn direct_claims_settle_once() {
+        let mut pool = vesting_pool();
+        let mut purchase_receipt = purchase_receipt(5_000);
+        purchase_receipt
+            .settle_direct_claim(&mut pool, &config(false), 100)
+            .unwrap();
+        assert_eq!(purchase_receipt.lp_elligible, Some(900));
+        assert_eq!(purchase_receipt.mint_elligible, Some(500));
+        assert_eq!(pool.purchase_receipts_checked, 1);
+
+        purchase_receipt
+            .settle_direct_claim(&mut pool, &config(false), 200)
+            .unwrap();
+        assert_eq!(pool.purchase_receipts_checked, 1);
+    }
+
+    #[test]
+    fn pause_and_freeze_stop_settling_direct_claims() {
+        let mut pool = vesting_pool();
+        let mut purchase_receipt = purchase_receipt(5_000);
+        assert_eq!(
+            purchase_receipt
+                .settle_direct_claim(&mut pool, &config(true), 100)
+                .unwrap_err(),
+            error!(CustomError::ProgramPaused)
+        );
+        pool.frozen = true.into();
+        assert_eq!(
+            purchase_receipt
+                .settle_direct_claim(&mut pool, &config(false), 100)
+                .unwrap_err(),
+            error!(CustomError::PoolFrozen)
+        );
+        assert_eq!(purchase_receipt.lp_elligible, None);
+        assert_eq!(pool.purchase_receipts_checked, 0);
+
+        // Receipts settled before keep claiming
+        pool.frozen = false.into();
+        purchase_receipt
+            .settle_direct_claim(&mut pool, &config(false), 100)
+            .unwrap();
+        pool.frozen = true.into();
+        assert!(purchase_receipt
+            .settle_direct_claim(&mut pool, &config(true), 200)
+            .is_ok());
+    }
+
+    #[test]
+    fn escrowed_receipts_are_not_settled_directly() {
+        let mut pool = vesting_pool();
+        let mut purchase_receipt = purchase_receipt(5_000);
+        purchase_receipt.lp_elligible = Some(900);
+        purchase_receipt.mint_elligible = Some(500);
+        assert_eq!(
+            purchase_receipt
+                .settle_direct_claim(&mut pool, &config(false), 100)
+                .unwrap_err(),
+            error!(CustomError::EscrowedClaimReceipt)
+        );
+    }
+
+    #[test]
+    fn quorum_is_reached_at_its_basis_points() {
+        let mut pool = launched_pool();
+        assert!(pool.record_dissolve_vote(0, 5_000, 100).unwrap());
+
+        let mut pool = launched_pool();
+        assert!(!pool.record_dissolve_vote(0, 2_500, 100).unwrap());
+        assert!(!pool.record_dissolve_vote(0, 2_499, 200).unwrap());
+        assert!(pool.record_dissolve_vote(0, 1, 300).unwrap());
+        assert_eq!(pool.dissolve_round, 1);
+        assert_eq!(pool.dissolve_votes, 5_000);
+    }
+
+    #[test]
+    fn receipt_cannot_vote_twice_in_a_round() {
+        let mut pool = launched_pool();
+        pool.record_dissolve_vote(0, 1_000, 100).unwrap();
+        assert_eq!(
+            pool.record_dissolve_vote(pool.dissolve_round, 1_000, 200)
+                .unwrap_err(),
+            error!(CustomError::AlreadyVoted)
+        );
+        assert_eq!(pool.dissolve_votes, 1_000);
+    }
+
+    #[test]
+    fn expired_round_discards_its_votes() {
+        let mut pool = launched_pool();
+        pool.record_dissolve_vote(0, 4_000, 100).unwrap();
+        let expired_round = pool.dissolve_round;
+
+        // The receipt that voted in the expired round can vote again
+        let started_at = 100 + i64::from(WINDOW);
+        assert!(!pool
+            .record_dissolve_vote(expired_round, 4_000, started_at)
+            .unwrap());
+        assert_eq!(pool.dissolve_round, expired_round + 1);
+        assert_eq!(pool.dissolve_votes, 4_000);
+        assert_eq!(pool.dissolve_vote_started_at.get(), Some(started_at));
+    }
+
+    #[test]
+    fn round_stays_open_until_the_window_ends() {
+        let mut pool = launched_pool();
+        pool.record_dissolve_vote(0, 4_000, 100).unwrap();
+        assert!(pool
+            .record_dissolve_vote(0, 1_000, 100 + i64::from(WINDOW) - 1)
+            .unwrap());
+        assert_eq!(pool.dissolve_round, 1);
+        assert_eq!(pool.dissolve_vote_started_at.get(), Some(100));
+    }
+
+    #[test]
+    fn dissolving_sets_aside_the_unclaimed_creator_lp_tokens() {
+        let mut pool = launched_pool();
+        pool.dissolve(500).unwrap();
+        assert_eq!(pool.lp_mint_dissolved, 700);
+        assert_eq!(pool.dissolved_at.get(), Some(500));
+        assert_eq!(pool.status_at(500), PoolStatus::Cancelled);
+        assert_eq!(pool.dissolved_lp_entitlement(2_500).unwrap(), 175);
+    }
+
+    #[test]
+    fn burned_pools_keep_the_status_they_were_burned_in() {
+        let mut pool: Pool = bytemuck::Zeroable::zeroed();
+        pool.presale_target = 10_000;
+        pool.presale_time_limit = 1_000;
+        pool.liquidity_collected = 5_000;
+        assert_eq!(pool.status_at(500), PoolStatus::Open);
+        pool.transition(Pubkey::new_unique(), 1_000);
+        pool.reward_supply_burned_at = Some(1_000).into();
+        assert_eq!(pool.status_at(500), PoolStatus::Failed);
+        pool.liquidity_collected = 10_000;
+        assert_eq!(pool.status_at(1_000), PoolStatus::Failed);
+
+        let expired_at = pool.presale_time_limit + GRACE_PERIOD + 1;
+        let mut pool: Pool = bytemuck::Zeroable::zeroed();
+        pool.presale_target = 10_000;
+        pool.presale_time_limit = 1_000;
+        pool.liquidity_collected = 10_000;
+        pool.transition(Pubkey::new_unique(), expired_at);
+        pool.reward_supply_burned_at = Some(expired_at).into();
+        assert_eq!(pool.status_at(expired_at), PoolStatus::Expired);
+        assert_eq!(pool.status_at(1_000), PoolStatus::Expired);
+    }
+
+    fn check_receipt(pool: &mut Pool, amount: u64) {
+        let lp_elligible = pool.lp_entitlement(amount).unwrap();
+        let mint_elligible = pool.mint_entitlement(amount).unwrap();
+        pool.record_checked_receipt(amount, lp_elligible, mint_elligible)
+            .unwrap();
+    }
+
+    #[test]
+    fn dust_holds_back_receipts_not_checked_yet() {
+        let mut pool = vesting_pool();
+        check_receipt(&mut pool, 3_333);
+        // 599 and 333 distributed, 1_200 and 666 held back for the other 6_667
+        assert_eq!(pool.dust().unwrap(), (1, 1));
+
+        check_receipt(&mut pool, 3_333);
+        check_receipt(&mut pool, 3_334);
+        assert_eq!(pool.lp_mint_distributed, 1_798);
+        assert_eq!(pool.initial_supply_distributed, 999);
+        assert_eq!(pool.dust().unwrap(), (2, 1));
+    }
+
+    #[test]
+    fn dust_includes_the_dissolved_remainder() {
+        let mut pool = vesting_pool();
+        pool.lp_mint_claimed_by_creator = 0;
+        for amount in [3_333, 3_333, 3_334] {
+            check_receipt(&mut pool, amount);
+        }
+        pool.dissolve(500).unwrap();
+        assert_eq!(pool.lp_mint_dissolved, 200);
+
+        let lp_claimed = pool.dissolved_lp_entitlement(3_333).unwrap();
+        pool.record_dissolved_lp_claim(3_333, lp_claimed).unwrap();
+        // 66 claimed, 133 held back for the other 6_667
+        assert_eq!(pool.dust().unwrap(), (3, 1));
+
+        for amount in [3_333, 3_334] {
+            let lp_claimed = pool.dissolved_lp_entitlement(amount).unwrap();
+            pool.record_dissolved_lp_claim(amount, lp_claimed).unwrap();
+        }
+        assert_eq!(pool.lp_mint_dissolved_claimed, 198);
+        assert_eq!(pool.dust().unwrap(), (4, 1));
+    }
+
+    #[test]
+    fn dust_includes_the_dissolved_share_of_reclaimed_receipts() {
+        let mut pool = vesting_pool();
+        pool.lp_mint_claimed_by_creator = 0;
+        check_receipt(&mut pool, 3_333);
+        check_receipt(&mut pool, 3_333);
+        pool.dissolve(500).unwrap();
+
+        // Reclaimed like reclaim_purchase_receipt does
+        check_receipt(&mut pool, 3_334);
+        pool.record_dissolved_lp_claim(3_334, 0).unwrap();
+        for _ in 0..2 {
+            let lp_claimed = pool.dissolved_lp_entitlement(3_333).unwrap();
+            pool.record_dissolved_lp_claim(3_333, lp_claimed).unwrap();
+        }
+        // 2 of rounding and 200 - 2 * 66 dissolved lp tokens nobody can claim
+        assert_eq!(pool.dust().unwrap(), (70, 1));
+    }
 }
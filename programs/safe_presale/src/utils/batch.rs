@@ -0,0 +1,239 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+
+use crate::{error::CustomError, state::PurchaseReceipt};
+
+/// Reads `(purchase_receipt, nft_token_account)` pairs from the remaining accounts.
+///
+/// Every pair is validated the same way the single receipt instructions do it: the receipt
+/// must belong to `pool` and the token account must hold its original mint for `nft_owner`.
+pub fn purchase_receipt_pairs<'info>(
+    remaining_accounts: &'info [AccountInfo<'info>],
+    pool: &Pubkey,
+    nft_owner: &Pubkey,
+) -> Result<Vec<Account<'info, PurchaseReceipt>>> {
+    Ok(
+        purchase_receipt_groups(remaining_accounts, 2, pool, nft_owner)?
+            .into_iter()
+            .map(|(purchase_receipt, _)| purchase_receipt)
+            .collect(),
+    )
+}
+
+/// Reads `(purchase_receipt, nft_token_account, receipt_payer)` triples from the remaining
+/// accounts, validated like `purchase_receipt_pairs`.
+///
+/// The third account must be whoever paid for the receipt, it receives the receipt rent.
+pub fn purchase_receipt_refunds<'info>(
+    remaining_accounts: &'info [AccountInfo<'info>],
+    pool: &Pubkey,
+    nft_owner: &Pubkey,
+) -> Result<Vec<(Account<'info, PurchaseReceipt>, &'info AccountInfo<'info>)>> {
+    purchase_receipt_groups(remaining_accounts, 3, pool, nft_owner)?
+        .into_iter()
+        .map(|(purchase_receipt, accounts)| {
+            let receipt_payer = &accounts[0];
+            require_keys_eq!(
+                receipt_payer.key(),
+                purchase_receipt.payer,
+                CustomError::InvalidRemainingAccounts
+            );
+            Ok((purchase_receipt, receipt_payer))
+        })
+        .collect()
+}
+
+/// Groups of `group_size` accounts starting with a purchase receipt and its nft token
+/// account, along with the accounts after them.
+fn purchase_receipt_groups<'info>(
+    remaining_accounts: &'info [AccountInfo<'info>],
+    group_size: usize,
+    pool: &Pubkey,
+    nft_owner: &Pubkey,
+) -> Result<Vec<(Account<'info, PurchaseReceipt>, &'info [AccountInfo<'info>])>> {
+    require!(
+        !remaining_accounts.is_empty()
+            && remaining_accounts
+                .chunks_exact(group_size)
+                .remainder()
+                .is_empty(),
+        CustomError::InvalidRemainingAccounts
+    );
+    let mut purchase_receipts: Vec<(Account<PurchaseReceipt>, &[AccountInfo])> =
+        Vec::with_capacity(remaining_accounts.len() / group_size);
+    for group in remaining_accounts.chunks_exact(group_size) {
+        let purchase_receipt = Account::<PurchaseReceipt>::try_from(&group[0])?;
+        let nft_owner_nft_token_account = InterfaceAccount::<TokenAccount>::try_from(&group[1])?;
+        require!(purchase_receipt.pool == *pool, CustomError::InvalidPool);
+        require!(
+            purchase_receipt.original_mint == nft_owner_nft_token_account.mint,
+            CustomError::MintNotAllowed
+        );
+        require!(
+            nft_owner_nft_token_account.amount == 1
+                && nft_owner_nft_token_account.owner == *nft_owner,
+            CustomError::InvalidSigner
+        );
+        require!(
+            purchase_receipts
+                .iter()
+                .all(|(other, _)| other.key() != purchase_receipt.key()),
+            CustomError::DuplicatePurchaseReceipt
+        );
+        purchase_receipts.push((purchase_receipt, &group[2..]));
+    }
+    Ok(purchase_receipts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        state::{PURCHASE_RECEIPT_RESERVED_SIZE, PURCHASE_RECEIPT_VERSION},
+        utils::test_accounts::TestAccount,
+    };
+    use anchor_lang::solana_program::system_program;
+
+    fn purchase_receipt(pool: Pubkey, original_mint: Pubkey) -> TestAccount {
+        TestAccount::program_account(&PurchaseReceipt {
+            version: PURCHASE_RECEIPT_VERSION,
+            is_initialized: true,
+            bump: 255,
+            pool,
+            amount: 1_000,
+            mint_elligible: None,
+            mint_claimed: 0,
+            lp_elligible: None,
+            original_mint,
+            lp_claimed: 0,
+            last_claimed_at: None,
+            dissolved_lp_claimed: false,
+            payer: Pubkey::new_unique(),
+            claim_payer: None,
+            reclaimed: false,
+            direct_claim: false,
+            lp_approved_claim_at: None,
+            mint_approved_claim_at: None,
+            reserved: [0; PURCHASE_RECEIPT_RESERVED_SIZE],
+        })
+    }
+
+    /// Receipt and nft token account of an nft of `nft_owner`.
+    fn receipt_pair(pool: Pubkey, nft_owner: Pubkey) -> Vec<TestAccount> {
+        let nft = Pubkey::new_unique();
+        vec![
+            purchase_receipt(pool, nft),
+            TestAccount::nft_token_account(nft, nft_owner),
+        ]
+    }
+
+    fn read_pairs(
+        accounts: &mut [TestAccount],
+        pool: &Pubkey,
+        nft_owner: &Pubkey,
+    ) -> Result<Vec<Pubkey>> {
+        let infos: Vec<AccountInfo> = accounts.iter_mut().map(TestAccount::info).collect();
+        Ok(purchase_receipt_pairs(&infos, pool, nft_owner)?
+            .iter()
+            .map(|purchase_receipt| purchase_receipt.original_mint)
+            .collect())
+    }
+
+    #[test]
+    fn reads_every_pair() {
+        let (pool, nft_owner) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let nfts = vec![Pubkey::new_unique(), Pubkey::new_unique()];
+        let mut accounts: Vec<TestAccount> = nfts
+            .iter()
+            .flat_map(|nft| {
+                [
+                    purchase_receipt(pool, *nft),
+                    TestAccount::nft_token_account(*nft, nft_owner),
+                ]
+            })
+            .collect();
+        assert_eq!(read_pairs(&mut accounts, &pool, &nft_owner).unwrap(), nfts);
+    }
+
+    #[test]
+    fn rejects_incomplete_pairs() {
+        let (pool, nft_owner) = (Pubkey::new_unique(), Pubkey::new_unique());
+        assert_eq!(
+            read_pairs(&mut [], &pool, &nft_owner).unwrap_err(),
+            error!(CustomError::InvalidRemainingAccounts)
+        );
+        let mut accounts = receipt_pair(pool, nft_owner);
+        accounts.pop();
+        assert_eq!(
+            read_pairs(&mut accounts, &pool, &nft_owner).unwrap_err(),
+            error!(CustomError::InvalidRemainingAccounts)
+        );
+    }
+
+    #[test]
+    fn rejects_duplicate_receipts() {
+        let (pool, nft_owner) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut accounts = receipt_pair(pool, nft_owner);
+        let duplicate: Vec<TestAccount> = accounts
+            .iter()
+            .map(|account| TestAccount::new(account.key, account.owner, account.data.clone()))
+            .collect();
+        accounts.extend(duplicate);
+        assert_eq!(
+            read_pairs(&mut accounts, &pool, &nft_owner).unwrap_err(),
+            error!(CustomError::DuplicatePurchaseReceipt)
+        );
+    }
+
+    #[test]
+    fn rejects_nft_of_another_owner() {
+        let (pool, nft_owner) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut accounts = receipt_pair(pool, nft_owner);
+        accounts.extend(receipt_pair(pool, Pubkey::new_unique()));
+        assert_eq!(
+            read_pairs(&mut accounts, &pool, &nft_owner).unwrap_err(),
+            error!(CustomError::InvalidSigner)
+        );
+    }
+
+    #[test]
+    fn rejects_receipt_of_another_pool_or_nft() {
+        let (pool, nft_owner) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut accounts = receipt_pair(Pubkey::new_unique(), nft_owner);
+        assert_eq!(
+            read_pairs(&mut accounts, &pool, &nft_owner).unwrap_err(),
+            error!(CustomError::InvalidPool)
+        );
+        let mut accounts = receipt_pair(pool, nft_owner);
+        accounts[1] = TestAccount::nft_token_account(Pubkey::new_unique(), nft_owner);
+        assert_eq!(
+            read_pairs(&mut accounts, &pool, &nft_owner).unwrap_err(),
+            error!(CustomError::MintNotAllowed)
+        );
+    }
+
+    #[test]
+    fn refunds_require_the_receipt_payer() {
+        let (pool, nft_owner) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut accounts = receipt_pair(pool, nft_owner);
+        let receipt_payer = PurchaseReceipt::try_deserialize(&mut &accounts[0].data[..])
+            .unwrap()
+            .payer;
+        accounts.push(TestAccount::new(receipt_payer, system_program::ID, vec![]));
+        let refund = |accounts: &mut [TestAccount]| {
+            let infos: Vec<AccountInfo> = accounts.iter_mut().map(TestAccount::info).collect();
+            purchase_receipt_refunds(&infos, &pool, &nft_owner).map(|refunds| {
+                refunds
+                    .iter()
+                    .map(|(_, payer)| payer.key())
+                    .collect::<Vec<_>>()
+            })
+        };
+        assert_eq!(refund(&mut accounts).unwrap(), vec![receipt_payer]);
+        accounts[2].key = nft_owner;
+        assert_eq!(
+            refund(&mut accounts).unwrap_err(),
+            error!(CustomError::InvalidRemainingAccounts)
+        );
+    }
+}
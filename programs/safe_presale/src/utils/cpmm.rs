@@ -0,0 +1,67 @@
+use anchor_lang::prelude::*;
+
+use crate::error::CustomError;
+
+/// Accounts of the CPMM `initialize` that `launch_token_amm` reads from its remaining
+/// accounts, in this order.
+pub struct CpmmInitializeAccounts<'info> {
+    pub lp_mint: AccountInfo<'info>,
+    pub user_token_lp: AccountInfo<'info>,
+    pub pool_token_lp: AccountInfo<'info>,
+    pub amm_config: AccountInfo<'info>,
+    pub amm_authority: AccountInfo<'info>,
+    pub amm_pool: AccountInfo<'info>,
+    pub token_0_vault: AccountInfo<'info>,
+    pub token_1_vault: AccountInfo<'info>,
+    pub create_fee_destination: AccountInfo<'info>,
+    pub observation_state: AccountInfo<'info>,
+}
+
+impl<'info> CpmmInitializeAccounts<'info> {
+    pub fn from_remaining_accounts(remaining_accounts: &[AccountInfo<'info>]) -> Result<Self> {
+        let mut remaining_accounts = remaining_accounts.iter().cloned();
+        let mut next = || {
+            remaining_accounts
+                .next()
+                .ok_or_else(|| error!(CustomError::InvalidRemainingAccounts))
+        };
+        // Struct fields are evaluated in the order they are written
+        Ok(CpmmInitializeAccounts {
+            lp_mint: next()?,
+            user_token_lp: next()?,
+            pool_token_lp: next()?,
+            amm_config: next()?,
+            amm_authority: next()?,
+            amm_pool: next()?,
+            token_0_vault: next()?,
+            token_1_vault: next()?,
+            create_fee_destination: next()?,
+            observation_state: next()?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_accounts::TestAccount;
+    use anchor_lang::solana_program::system_program;
+
+    #[test]
+    fn reads_the_accounts_in_order() {
+        let mut accounts: Vec<TestAccount> = (0..10)
+            .map(|_| TestAccount::new(Pubkey::new_unique(), system_program::ID, vec![]))
+            .collect();
+        let keys: Vec<Pubkey> = accounts.iter().map(|account| account.key).collect();
+        let infos: Vec<AccountInfo> = accounts.iter_mut().map(TestAccount::info).collect();
+
+        let cpmm_accounts = CpmmInitializeAccounts::from_remaining_accounts(&infos).unwrap();
+        assert_eq!(cpmm_accounts.lp_mint.key(), keys[0]);
+        assert_eq!(cpmm_accounts.amm_config.key(), keys[3]);
+        assert_eq!(cpmm_accounts.observation_state.key(), keys[9]);
+        assert!(matches!(
+            CpmmInitializeAccounts::from_remaining_accounts(&infos[..9]),
+            Err(err) if err == error!(CustomError::InvalidRemainingAccounts)
+        ));
+    }
+}
@@ -0,0 +1,283 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+
+use crate::{
+    error::CustomError,
+    state::{ClaimApproval, StakingProgramRecord},
+};
+
+/// Checks that `payer` may act on the nft held in `nft_token_account` on behalf of `nft_owner`.
+///
+/// The owner can always act for themselves. Anybody else can only do so when the nft is
+/// staked in an allowlisted staking program, either:
+/// 1. frozen in the owner's wallet and delegated to an account of the staking program, or
+/// 2. escrowed in the staking program's vault PDA derived from `[escrow_seed, nft_owner]`.
+pub fn check_delegated_claim(
+    nft_owner: &AccountInfo,
+    payer: &AccountInfo,
+    nft_token_account: &InterfaceAccount<TokenAccount>,
+    staking_program_record: Option<&Account<StakingProgramRecord>>,
+    staking_authority: Option<&AccountInfo>,
+) -> Result<()> {
+    if nft_token_account.owner == nft_owner.key() && payer.key() == nft_owner.key() {
+        return Ok(());
+    }
+    let (Some(staking_program_record), Some(staking_authority)) =
+        (staking_program_record, staking_authority)
+    else {
+        return Err(error!(CustomError::InvalidSigner));
+    };
+
+    let frozen_for_owner = nft_token_account.owner == nft_owner.key()
+        && nft_token_account.is_frozen()
+        && nft_token_account.delegate == Some(staking_authority.key()).into()
+        && *staking_authority.owner == staking_program_record.staking_program;
+    if frozen_for_owner {
+        return Ok(());
+    }
+
+    if let Some(escrow_seed) = &staking_program_record.escrow_seed {
+        let (vault, _) = Pubkey::find_program_address(
+            &[escrow_seed.as_ref(), nft_owner.key().as_ref()],
+            &staking_program_record.staking_program,
+        );
+        if nft_token_account.owner == vault && staking_authority.key() == vault {
+            return Ok(());
+        }
+    }
+
+    Err(error!(CustomError::InvalidSigner))
+}
+
+/// Checks that the delegate of `claim_approval` may claim `purchase_receipt` at `now`.
+///
+/// The seeds of the approval already bind it to the nft owner, the delegate and the receipt it
+/// is scoped to, the nft itself must still sit in the owner's wallet.
+pub fn check_claim_approval(
+    claim_approval: &ClaimApproval,
+    nft_owner: &AccountInfo,
+    nft_token_account: &InterfaceAccount<TokenAccount>,
+    purchase_receipt: &Pubkey,
+    now: i64,
+) -> Result<()> {
+    require!(
+        nft_token_account.owner == nft_owner.key(),
+        CustomError::InvalidSigner
+    );
+    if let Some(approved_receipt) = claim_approval.purchase_receipt {
+        require!(
+            approved_receipt == *purchase_receipt,
+            CustomError::ClaimApprovalScopeMismatch
+        );
+    }
+    if let Some(expires_at) = claim_approval.expires_at {
+        require!(now < expires_at, CustomError::ClaimApprovalExpired);
+    }
+    Ok(())
+}
+
+/// Caps a claim of `claimable` made through a claim approval to `max_per_claim`.
+///
+/// A receipt is claimed through an approval at most once per timestamp, repeating the claim
+/// within a transaction would otherwise get around the cap.
+pub fn cap_approved_claim(
+    max_per_claim: Option<u64>,
+    approved_claim_at: &mut Option<i64>,
+    claimable: u64,
+    now: i64,
+) -> Result<u64> {
+    require!(
+        *approved_claim_at != Some(now),
+        CustomError::ApprovedClaimRepeated
+    );
+    *approved_claim_at = Some(now);
+    Ok(max_per_claim.map_or(claimable, |max_per_claim| claimable.min(max_per_claim)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_accounts::TestAccount;
+    use anchor_lang::solana_program::{program_option::COption, system_program};
+    use anchor_spl::token::spl_token::state::AccountState;
+
+    const ESCROW_SEED: &[u8] = b"escrow";
+
+    /// An nft owned by `nft_owner` and the accounts of the allowlisted staking program.
+    struct Fixture {
+        nft_owner: TestAccount,
+        nft_token_account: TestAccount,
+        staking_program_record: TestAccount,
+        staking_authority: TestAccount,
+        staking_program: Pubkey,
+    }
+
+    impl Fixture {
+        fn new() -> Self {
+            let staking_program = Pubkey::new_unique();
+            let nft_owner = TestAccount::new(Pubkey::new_unique(), system_program::ID, vec![]);
+            Fixture {
+                nft_token_account: TestAccount::nft_token_account(
+                    Pubkey::new_unique(),
+                    nft_owner.key,
+                ),
+                nft_owner,
+                staking_program_record: TestAccount::program_account(&StakingProgramRecord {
+                    pool: Pubkey::new_unique(),
+                    staking_program,
+                    escrow_seed: Some(ESCROW_SEED.to_vec()),
+                    bump: 255,
+                }),
+                staking_authority: TestAccount::new(Pubkey::new_unique(), staking_program, vec![]),
+                staking_program,
+            }
+        }
+
+        fn set_nft_token_account(
+            &mut self,
+            owner: Pubkey,
+            delegate: COption<Pubkey>,
+            state: AccountState,
+        ) {
+            self.nft_token_account
+                .set_token_account(Pubkey::new_unique(), owner, delegate, state);
+        }
+
+        fn escrow_vault(&self) -> Pubkey {
+            Pubkey::find_program_address(
+                &[ESCROW_SEED, self.nft_owner.key.as_ref()],
+                &self.staking_program,
+            )
+            .0
+        }
+
+        fn check(&mut self, payer: Pubkey, staked: bool) -> Result<()> {
+            let mut payer_lamports = 0;
+            let payer = AccountInfo::new(
+                &payer,
+                true,
+                true,
+                &mut payer_lamports,
+                &mut [],
+                &system_program::ID,
+                false,
+                0,
+            );
+            let nft_owner = self.nft_owner.info();
+            let nft_token_account_info = self.nft_token_account.info();
+            let nft_token_account = InterfaceAccount::try_from(&nft_token_account_info)?;
+            let staking_program_record_info = self.staking_program_record.info();
+            let staking_program_record = Account::try_from(&staking_program_record_info)?;
+            let staking_authority = self.staking_authority.info();
+            check_delegated_claim(
+                &nft_owner,
+                &payer,
+                &nft_token_account,
+                staked.then_some(&staking_program_record),
+                staked.then_some(&staking_authority),
+            )
+        }
+    }
+
+    #[test]
+    fn owner_claims_for_themselves() {
+        let mut fixture = Fixture::new();
+        let owner = fixture.nft_owner.key;
+        assert!(fixture.check(owner, false).is_ok());
+        assert_eq!(
+            fixture.check(Pubkey::new_unique(), false).unwrap_err(),
+            error!(CustomError::InvalidSigner)
+        );
+    }
+
+    #[test]
+    fn delegate_claims_for_nft_frozen_by_staking_program() {
+        let mut fixture = Fixture::new();
+        let (owner, staking_authority) = (fixture.nft_owner.key, fixture.staking_authority.key);
+        fixture.set_nft_token_account(
+            owner,
+            COption::Some(staking_authority),
+            AccountState::Frozen,
+        );
+        assert!(fixture.check(Pubkey::new_unique(), true).is_ok());
+    }
+
+    #[test]
+    fn delegate_claims_for_nft_in_escrow_vault() {
+        let mut fixture = Fixture::new();
+        let vault = fixture.escrow_vault();
+        fixture.set_nft_token_account(vault, COption::None, AccountState::Initialized);
+        fixture.staking_authority.key = vault;
+        assert!(fixture.check(Pubkey::new_unique(), true).is_ok());
+
+        // Vault of another owner
+        fixture.nft_owner.key = Pubkey::new_unique();
+        assert_eq!(
+            fixture.check(Pubkey::new_unique(), true).unwrap_err(),
+            error!(CustomError::InvalidSigner)
+        );
+    }
+
+    #[test]
+    fn delegate_of_another_staking_program_is_rejected() {
+        let mut fixture = Fixture::new();
+        let (owner, staking_authority) = (fixture.nft_owner.key, fixture.staking_authority.key);
+        fixture.set_nft_token_account(
+            owner,
+            COption::Some(staking_authority),
+            AccountState::Frozen,
+        );
+        fixture.staking_authority.owner = Pubkey::new_unique();
+        assert_eq!(
+            fixture.check(Pubkey::new_unique(), true).unwrap_err(),
+            error!(CustomError::InvalidSigner)
+        );
+    }
+
+    #[test]
+    fn unfrozen_nft_with_spoofed_delegate_is_rejected() {
+        let mut fixture = Fixture::new();
+        let (owner, staking_authority) = (fixture.nft_owner.key, fixture.staking_authority.key);
+        fixture.set_nft_token_account(
+            owner,
+            COption::Some(staking_authority),
+            AccountState::Initialized,
+        );
+        assert_eq!(
+            fixture.check(Pubkey::new_unique(), true).unwrap_err(),
+            error!(CustomError::InvalidSigner)
+        );
+    }
+
+    #[test]
+    fn approved_claims_are_capped_without_being_spent() {
+        let mut approved_claim_at = None;
+        assert_eq!(
+            cap_approved_claim(Some(100), &mut approved_claim_at, 60, 1_000).unwrap(),
+            60
+        );
+        assert_eq!(
+            cap_approved_claim(Some(100), &mut approved_claim_at, 160, 1_001).unwrap(),
+            100
+        );
+        assert_eq!(
+            cap_approved_claim(Some(100), &mut approved_claim_at, 160, 1_002).unwrap(),
+            100
+        );
+        assert_eq!(
+            cap_approved_claim(None, &mut approved_claim_at, u64::MAX, 1_003).unwrap(),
+            u64::MAX
+        );
+        assert_eq!(approved_claim_at, Some(1_003));
+    }
+
+    #[test]
+    fn approved_claim_cannot_repeat_in_a_transaction() {
+        let mut approved_claim_at = None;
+        cap_approved_claim(Some(100), &mut approved_claim_at, 160, 1_000).unwrap();
+        assert_eq!(
+            cap_approved_claim(Some(100), &mut approved_claim_at, 60, 1_000).unwrap_err(),
+            error!(CustomError::ApprovedClaimRepeated)
+        );
+    }
+}
@@ -1,10 +1,21 @@
-use std::{cmp::Eq, convert::TryInto};
-use uint::construct_uint;
+use std::convert::TryInto;
 
 use crate::error::CustomError;
 
-construct_uint! {
-    pub struct U128(2);
+pub use wide::U128;
+
+/// Clippy flags the code generated by `construct_uint!`, not ours.
+#[allow(
+    clippy::assign_op_pattern,
+    clippy::manual_div_ceil,
+    clippy::reversed_empty_ranges
+)]
+mod wide {
+    use uint::construct_uint;
+
+    construct_uint! {
+        pub struct U128(2);
+    }
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -18,4 +29,83 @@ impl Calculator {
         val.try_into()
             .map_err(|_| CustomError::InvalidNegativeValue)
     }
+    /// `amount` whole tokens in the base units of a mint with `decimals`.
+    pub fn to_base_units(amount: u64, decimals: u8) -> Result<u64, CustomError> {
+        10u64
+            .checked_pow(decimals.into())
+            .and_then(|factor| amount.checked_mul(factor))
+            .ok_or(CustomError::IntegerOverflow)
+    }
+    /// Share of `amount` worth `basis_points`, rounded down.
+    pub fn basis_points_of(amount: u64, basis_points: u16) -> Result<u64, CustomError> {
+        let result = U128::from(amount)
+            .checked_mul(basis_points.into())
+            .and_then(|result| result.checked_div(U128::from(10000)))
+            .ok_or(CustomError::IntegerOverflow)?;
+        Self::to_u64(result.as_u128())
+    }
+    /// Lp tokens the CPMM mints to the creator of a pool seeded with both amounts, the
+    /// liquidity it locks in the pool excluded.
+    pub fn lp_tokens_received(amount_0: u64, amount_1: u64) -> Result<u64, CustomError> {
+        // Can't overflow, the product of two u64 fits in a U128
+        let liquidity = U128::from(amount_0)
+            .checked_mul(amount_1.into())
+            .ok_or(CustomError::IntegerOverflow)?
+            .integer_sqrt();
+        Self::to_u64(liquidity.as_u128())?
+            .checked_sub(LOCKED_LP_AMOUNT)
+            .ok_or(CustomError::InsufficientLiquidity)
+    }
+}
+
+/// Lp tokens the CPMM keeps locked in every pool it creates.
+pub const LOCKED_LP_AMOUNT: u64 = 100;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn to_base_units_rejects_overflowing_decimals() {
+        assert!(matches!(
+            Calculator::to_base_units(1_000, 9),
+            Ok(1_000_000_000_000)
+        ));
+        assert!(matches!(
+            Calculator::to_base_units(1, 20),
+            Err(CustomError::IntegerOverflow)
+        ));
+        assert!(matches!(
+            Calculator::to_base_units(u64::MAX, 1),
+            Err(CustomError::IntegerOverflow)
+        ));
+    }
+
+    #[test]
+    fn basis_points_of_rounds_down() {
+        assert!(matches!(
+            Calculator::basis_points_of(u64::MAX, 10000),
+            Ok(u64::MAX)
+        ));
+        assert!(matches!(Calculator::basis_points_of(9_999, 1), Ok(0)));
+        assert!(matches!(
+            Calculator::basis_points_of(u64::MAX, 10001),
+            Err(CustomError::ConversionFailure)
+        ));
+    }
+
+    #[test]
+    fn lp_tokens_received_excludes_locked_liquidity() {
+        let lp_amount = Calculator::lp_tokens_received(u64::MAX, u64::MAX);
+        assert!(matches!(lp_amount, Ok(amount) if amount == u64::MAX - 100));
+        assert!(matches!(Calculator::lp_tokens_received(10_000, 1), Ok(0)));
+        assert!(matches!(
+            Calculator::lp_tokens_received(99, 100),
+            Err(CustomError::InsufficientLiquidity)
+        ));
+        assert!(matches!(
+            Calculator::lp_tokens_received(1_000_000, 0),
+            Err(CustomError::InsufficientLiquidity)
+        ));
+    }
 }
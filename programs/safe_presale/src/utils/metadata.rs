@@ -0,0 +1,43 @@
+use anchor_lang::prelude::*;
+use mpl_token_metadata::{accounts::Metadata, instructions::UpdateMetadataAccountV2CpiBuilder};
+
+use crate::error::CustomError;
+
+/// Whether the metadata account data of `mint` allows buying with the nft, its collection
+/// must be verified and be `collection_mint` when it has one.
+pub fn collection_allowed(
+    metadata_data: &[u8],
+    mint: &Pubkey,
+    collection_mint: &Pubkey,
+) -> Result<bool> {
+    let metadata = Metadata::deserialize(&mut &metadata_data[..])
+        .map_err(|_| error!(CustomError::InvalidMintMetadata))?;
+    require_keys_eq!(metadata.mint, *mint, CustomError::InvalidMintMetadata);
+    Ok(match metadata.collection {
+        Some(collection) => collection.verified && collection.key == *collection_mint,
+        None => true,
+    })
+}
+
+/// Clears the `is_mutable` flag of the reward mint metadata unless it is already cleared, the
+/// pool is its update authority.
+#[inline(never)]
+pub fn lock_metadata<'info>(
+    mpl_token_program: AccountInfo<'info>,
+    metadata: AccountInfo<'info>,
+    pool: AccountInfo<'info>,
+    signer: &[&[&[u8]]; 1],
+) -> Result<()> {
+    let is_mutable = Metadata::deserialize(&mut &metadata.try_borrow_data()?[..])
+        .map_err(|_| error!(CustomError::InvalidMintMetadata))?
+        .is_mutable;
+    if !is_mutable {
+        return Ok(());
+    }
+    UpdateMetadataAccountV2CpiBuilder::new(&mpl_token_program)
+        .metadata(&metadata)
+        .update_authority(&pool)
+        .is_mutable(false)
+        .invoke_signed(signer)?;
+    Ok(())
+}
@@ -0,0 +1,52 @@
+use crate::error::CustomError;
+use anchor_lang::{
+    prelude::*,
+    system_program::{transfer, Transfer},
+};
+
+/// Reads an account written with an earlier layout, identified by its discriminator and size.
+pub fn legacy_account<T: AnchorDeserialize>(
+    account: &AccountInfo,
+    discriminator: [u8; 8],
+    current_size: usize,
+    legacy_size: usize,
+) -> Result<T> {
+    let data = account.try_borrow_data()?;
+    require!(
+        data.len() >= 8 && data[..8] == discriminator,
+        CustomError::InvalidAccountLayout
+    );
+    require!(
+        data.len() != current_size,
+        CustomError::AccountAlreadyMigrated
+    );
+    require!(data.len() == legacy_size, CustomError::InvalidAccountLayout);
+
+    T::deserialize(&mut &data[8..]).map_err(|_| error!(CustomError::InvalidAccountLayout))
+}
+
+/// Grows the account to `new_size`, the payer covers the extra rent.
+pub fn realloc_account<'info>(
+    account: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    new_size: usize,
+) -> Result<()> {
+    let rent = Rent::get()?.minimum_balance(new_size);
+    let top_up = rent.saturating_sub(account.lamports());
+    if top_up > 0 {
+        transfer(
+            CpiContext::new(
+                system_program.clone(),
+                Transfer {
+                    from: payer.clone(),
+                    to: account.clone(),
+                },
+            ),
+            top_up,
+        )?;
+    }
+    account.realloc(new_size, true)?;
+
+    Ok(())
+}
@@ -1,3 +1,17 @@
+pub mod batch;
+pub mod cpmm;
+pub mod delegation;
 pub mod math;
+pub mod metadata;
+pub mod migration;
+#[cfg(test)]
+mod test_accounts;
+pub mod vesting;
 
+pub use batch::*;
+pub use cpmm::*;
+pub use delegation::*;
 pub use math::*;
+pub use metadata::*;
+pub use migration::*;
+pub use vesting::*;
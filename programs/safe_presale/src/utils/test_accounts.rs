@@ -0,0 +1,75 @@
+//! Accounts backing the `AccountInfo`s of unit tests.
+use anchor_lang::{
+    prelude::*,
+    solana_program::{program_option::COption, program_pack::Pack},
+};
+use anchor_spl::token::spl_token::{
+    self,
+    state::{Account as SplTokenAccount, AccountState},
+};
+
+pub struct TestAccount {
+    pub key: Pubkey,
+    pub lamports: u64,
+    pub data: Vec<u8>,
+    pub owner: Pubkey,
+}
+
+impl TestAccount {
+    pub fn new(key: Pubkey, owner: Pubkey, data: Vec<u8>) -> Self {
+        TestAccount {
+            key,
+            lamports: 1,
+            data,
+            owner,
+        }
+    }
+
+    /// Program account holding `account`, serialized with its discriminator.
+    pub fn program_account(account: &impl AccountSerialize) -> Self {
+        let mut data = vec![];
+        account.try_serialize(&mut data).unwrap();
+        TestAccount::new(Pubkey::new_unique(), crate::ID, data)
+    }
+
+    /// Token account holding one nft of `mint` for `owner`.
+    pub fn nft_token_account(mint: Pubkey, owner: Pubkey) -> Self {
+        let mut account = TestAccount::new(Pubkey::new_unique(), spl_token::ID, vec![]);
+        account.set_token_account(mint, owner, COption::None, AccountState::Initialized);
+        account
+    }
+
+    pub fn set_token_account(
+        &mut self,
+        mint: Pubkey,
+        owner: Pubkey,
+        delegate: COption<Pubkey>,
+        state: AccountState,
+    ) {
+        let mut data = vec![0; SplTokenAccount::LEN];
+        SplTokenAccount {
+            mint,
+            owner,
+            amount: 1,
+            delegate,
+            state,
+            delegated_amount: delegate.map_or(0, |_| 1),
+            ..Default::default()
+        }
+        .pack_into_slice(&mut data);
+        self.data = data;
+    }
+
+    pub fn info(&mut self) -> AccountInfo<'_> {
+        AccountInfo::new(
+            &self.key,
+            false,
+            false,
+            &mut self.lamports,
+            &mut self.data,
+            &self.owner,
+            false,
+            0,
+        )
+    }
+}
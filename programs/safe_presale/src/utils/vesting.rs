@@ -0,0 +1,252 @@
+use crate::error::CustomError;
+
+use super::{Calculator, U128};
+
+/// Linear vesting of `total` tokens over `duration` seconds starting at `start`.
+///
+/// Everything is derived from the total vested to date, so the amount released
+/// never depends on how often (or when) somebody claimed before.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VestingSchedule {
+    pub start: i64,
+    pub duration: u32,
+    pub total: u64,
+}
+
+impl VestingSchedule {
+    pub fn new(start: i64, duration: u32, total: u64) -> Self {
+        Self {
+            start,
+            duration,
+            total,
+        }
+    }
+
+    pub fn end(&self) -> Result<i64, CustomError> {
+        self.start
+            .checked_add(self.duration.into())
+            .ok_or(CustomError::IntegerOverflow)
+    }
+
+    /// Total amount released between `start` and `now`, rounded down.
+    pub fn vested(&self, now: i64) -> Result<u64, CustomError> {
+        if now >= self.end()? {
+            return Ok(self.total);
+        }
+        if now <= self.start {
+            return Ok(0);
+        }
+        let elapsed = Calculator::to_u64_from_i64(
+            now.checked_sub(self.start)
+                .ok_or(CustomError::IntegerOverflow)?,
+        )?;
+        U128::from(elapsed)
+            .checked_mul(U128::from(self.total))
+            .and_then(|result| result.checked_div(U128::from(self.duration)))
+            .map(|result| result.as_u64())
+            .ok_or(CustomError::IntegerOverflow)
+    }
+
+    /// Amount that can be paid out at `now` given what has already been `claimed`.
+    pub fn claimable(&self, now: i64, claimed: u64) -> Result<u64, CustomError> {
+        self.vested(now)?
+            .checked_sub(claimed)
+            .ok_or(CustomError::IntegerOverflow)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    const DAY: u32 = 24 * 60 * 60;
+
+    fn claim_at(schedule: &VestingSchedule, times: &[i64]) -> Vec<u64> {
+        let mut claimed = 0u64;
+        times
+            .iter()
+            .map(|now| {
+                let amount = schedule.claimable(*now, claimed).unwrap();
+                claimed += amount;
+                amount
+            })
+            .collect()
+    }
+
+    #[test]
+    fn nothing_vests_before_start() {
+        let schedule = VestingSchedule::new(1_000, DAY, 1_000_000);
+        assert_eq!(schedule.vested(0).unwrap(), 0);
+        assert_eq!(schedule.vested(1_000).unwrap(), 0);
+        assert_eq!(schedule.claimable(999, 0).unwrap(), 0);
+    }
+
+    #[test]
+    fn everything_vests_at_end() {
+        let schedule = VestingSchedule::new(1_000, DAY, 1_000_000);
+        assert_eq!(schedule.end().unwrap(), 1_000 + i64::from(DAY));
+        assert_eq!(schedule.vested(1_000 + i64::from(DAY)).unwrap(), 1_000_000);
+        assert_eq!(schedule.vested(i64::MAX).unwrap(), 1_000_000);
+    }
+
+    #[test]
+    fn zero_duration_releases_immediately() {
+        let schedule = VestingSchedule::new(1_000, 0, 42);
+        assert_eq!(schedule.vested(999).unwrap(), 0);
+        assert_eq!(schedule.vested(1_000).unwrap(), 42);
+        assert_eq!(schedule.claimable(1_000, 0).unwrap(), 42);
+    }
+
+    #[test]
+    fn vests_linearly() {
+        let schedule = VestingSchedule::new(0, 4 * DAY, 1_000);
+        assert_eq!(schedule.vested(i64::from(DAY)).unwrap(), 250);
+        assert_eq!(schedule.vested(i64::from(2 * DAY)).unwrap(), 500);
+        assert_eq!(schedule.vested(i64::from(3 * DAY)).unwrap(), 750);
+    }
+
+    #[test]
+    fn claimable_subtracts_claimed() {
+        let schedule = VestingSchedule::new(0, 100, 1_000);
+        assert_eq!(schedule.claimable(50, 0).unwrap(), 500);
+        assert_eq!(schedule.claimable(50, 500).unwrap(), 0);
+        assert_eq!(schedule.claimable(100, 500).unwrap(), 500);
+    }
+
+    #[test]
+    fn claimed_above_vested_is_an_error() {
+        let schedule = VestingSchedule::new(0, 100, 1_000);
+        assert!(matches!(
+            schedule.claimable(10, 101),
+            Err(CustomError::IntegerOverflow)
+        ));
+    }
+
+    #[test]
+    fn end_overflow_is_an_error() {
+        let schedule = VestingSchedule::new(i64::MAX, 1, 1);
+        assert!(matches!(schedule.end(), Err(CustomError::IntegerOverflow)));
+        assert!(matches!(
+            schedule.vested(0),
+            Err(CustomError::IntegerOverflow)
+        ));
+    }
+
+    #[test]
+    fn maximum_total_does_not_overflow() {
+        let schedule = VestingSchedule::new(0, u32::MAX, u64::MAX);
+        let now = i64::from(u32::MAX) - 1;
+        let exact = u128::from(u64::MAX) * (u128::from(u32::MAX) - 1) / u128::from(u32::MAX);
+        assert_eq!(u128::from(schedule.vested(now).unwrap()), exact);
+    }
+
+    #[test]
+    fn repeated_claims_do_not_lose_rounding() {
+        // Paying `elapsed_since_last_claim * total / duration` floored each of
+        // these claims to 1, releasing only 3 of the 5 tokens vested by t = 3.
+        let schedule = VestingSchedule::new(0, 4, 7);
+        assert_eq!(claim_at(&schedule, &[1, 2, 3]), vec![1, 2, 2]);
+        assert_eq!(claim_at(&schedule, &[1, 2, 3, 4]), vec![1, 2, 2, 2]);
+    }
+
+    #[test]
+    fn every_claim_sequence_over_a_short_schedule_matches_vested() {
+        // Exhaustively walk every subset of claim times for a short schedule.
+        let schedule = VestingSchedule::new(10, 8, 1_001);
+        let times: Vec<i64> = (8..=20).collect();
+        for mask in 0u32..(1 << times.len()) {
+            let sequence: Vec<i64> = times
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| mask & (1 << i) != 0)
+                .map(|(_, t)| *t)
+                .collect();
+            let paid: u64 = claim_at(&schedule, &sequence).iter().sum();
+            let expected = sequence
+                .last()
+                .map(|t| schedule.vested(*t).unwrap())
+                .unwrap_or(0);
+            assert_eq!(paid, expected, "sequence {:?}", sequence);
+        }
+    }
+
+    fn schedule_strategy() -> impl Strategy<Value = VestingSchedule> {
+        (
+            -1_000_000_000i64..2_000_000_000i64,
+            0u32..=10 * 365 * DAY,
+            any::<u64>(),
+        )
+            .prop_map(|(start, duration, total)| VestingSchedule::new(start, duration, total))
+    }
+
+    proptest! {
+        #[test]
+        fn vested_is_monotonic_and_bounded(
+            schedule in schedule_strategy(),
+            a in any::<i32>(),
+            b in any::<i32>(),
+        ) {
+            let (earlier, later) = if a <= b { (a, b) } else { (b, a) };
+            let earlier = schedule.start + i64::from(earlier);
+            let later = schedule.start + i64::from(later);
+            let vested_earlier = schedule.vested(earlier).unwrap();
+            let vested_later = schedule.vested(later).unwrap();
+            prop_assert!(vested_earlier <= vested_later);
+            prop_assert!(vested_later <= schedule.total);
+        }
+
+        #[test]
+        fn claims_sum_to_vested_for_any_sequence(
+            schedule in schedule_strategy(),
+            mut offsets in prop::collection::vec(0u32..=11 * 365 * DAY, 0..64),
+        ) {
+            offsets.sort_unstable();
+            let times: Vec<i64> = offsets
+                .iter()
+                .map(|offset| schedule.start + i64::from(*offset))
+                .collect();
+            let payouts = claim_at(&schedule, &times);
+            let paid: u64 = payouts.iter().sum();
+            let expected = times.last().map(|t| schedule.vested(*t).unwrap()).unwrap_or(0);
+            prop_assert_eq!(paid, expected);
+            prop_assert!(paid <= schedule.total);
+        }
+
+        #[test]
+        fn claiming_after_end_releases_everything(
+            schedule in schedule_strategy(),
+            mut offsets in prop::collection::vec(0u32..=10 * 365 * DAY, 0..64),
+        ) {
+            offsets.sort_unstable();
+            let mut times: Vec<i64> = offsets
+                .iter()
+                .map(|offset| schedule.start + i64::from(*offset))
+                .collect();
+            times.push(schedule.end().unwrap());
+            let paid: u64 = claim_at(&schedule, &times).iter().sum();
+            prop_assert_eq!(paid, schedule.total);
+            prop_assert_eq!(
+                schedule.claimable(schedule.end().unwrap(), schedule.total).unwrap(),
+                0
+            );
+        }
+
+        #[test]
+        fn vested_is_within_one_unit_of_exact(
+            schedule in schedule_strategy(),
+            offset in 0u32..=10 * 365 * DAY,
+        ) {
+            let now = schedule.start + i64::from(offset);
+            let vested = u128::from(schedule.vested(now).unwrap());
+            if schedule.duration == 0 || offset >= schedule.duration {
+                prop_assert_eq!(vested, u128::from(schedule.total));
+            } else {
+                let numerator = u128::from(schedule.total) * u128::from(offset);
+                let duration = u128::from(schedule.duration);
+                prop_assert!(vested * duration <= numerator);
+                prop_assert!(numerator < (vested + 1) * duration);
+            }
+        }
+    }
+}
@@ -0,0 +1,206 @@
+//! Stand-in for the Raydium CPMM, registered at its mainnet address.
+//!
+//! Only `initialize` is supported. Like the CPMM it moves both initial amounts into the pool
+//! vaults and mints `sqrt(amount_0 * amount_1) - 100` lp tokens to the creator. The lp mint,
+//! the vaults and the creator's lp token account are created up front by
+//! `TestEnv::create_cpmm_pool` instead of by the instruction.
+use std::str::FromStr;
+
+use anchor_lang::solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    instruction::AccountMeta,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+use anchor_spl::{associated_token::get_associated_token_address, token::spl_token};
+use safe_presale::{state::RAYDIUM_CPMM_V4_MAINNET, utils::U128};
+
+use super::TestEnv;
+
+/// Anchor discriminator of the CPMM `initialize` instruction.
+pub const INITIALIZE: [u8; 8] = [175, 175, 109, 31, 13, 152, 155, 237];
+/// Lp tokens the CPMM keeps locked in every pool.
+pub const LOCKED_LIQUIDITY: u64 = 100;
+pub const LP_DECIMALS: u8 = 9;
+const AUTHORITY_SEED: &[u8] = b"vault_and_lp_mint_auth_seed";
+
+pub fn program_id() -> Pubkey {
+    Pubkey::from_str(RAYDIUM_CPMM_V4_MAINNET).unwrap()
+}
+
+/// Signs for the vaults and mints the lp tokens of every pool.
+pub fn authority() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[AUTHORITY_SEED], &program_id())
+}
+
+/// Lp tokens minted for a pool created with both amounts, the locked ones included.
+pub fn liquidity(amount_0: u64, amount_1: u64) -> u64 {
+    U128::from(amount_0)
+        .checked_mul(amount_1.into())
+        .unwrap()
+        .integer_sqrt()
+        .as_u64()
+}
+
+/// Addresses of the pool launched for the presale `pool`, in the order `launch_token_amm`
+/// reads them from its remaining accounts.
+pub struct CpmmPool {
+    pub lp_mint: Pubkey,
+    pub user_token_lp: Pubkey,
+    pub pool_token_lp: Pubkey,
+    pub amm_config: Pubkey,
+    pub authority: Pubkey,
+    pub pool_state: Pubkey,
+    pub token_0_mint: Pubkey,
+    pub token_1_mint: Pubkey,
+    pub token_0_vault: Pubkey,
+    pub token_1_vault: Pubkey,
+    pub create_pool_fee_receiver: Pubkey,
+    pub observation_state: Pubkey,
+}
+
+impl CpmmPool {
+    pub fn new(
+        pool: &Pubkey,
+        reward_mint: &Pubkey,
+        quote_mint: &Pubkey,
+        user_wallet: &Pubkey,
+    ) -> Self {
+        let program_id = program_id();
+        let find = |seeds: &[&[u8]]| Pubkey::find_program_address(seeds, &program_id).0;
+        let (token_0_mint, token_1_mint) = if reward_mint < quote_mint {
+            (*reward_mint, *quote_mint)
+        } else {
+            (*quote_mint, *reward_mint)
+        };
+        let amm_config = find(&[b"amm_config", &0u16.to_be_bytes()]);
+        let pool_state = find(&[
+            b"pool",
+            amm_config.as_ref(),
+            token_0_mint.as_ref(),
+            token_1_mint.as_ref(),
+        ]);
+        let lp_mint = find(&[b"pool_lp_mint", pool_state.as_ref()]);
+        CpmmPool {
+            lp_mint,
+            user_token_lp: get_associated_token_address(user_wallet, &lp_mint),
+            pool_token_lp: get_associated_token_address(pool, &lp_mint),
+            amm_config,
+            authority: authority().0,
+            pool_state,
+            token_0_mint,
+            token_1_mint,
+            token_0_vault: find(&[b"pool_vault", pool_state.as_ref(), token_0_mint.as_ref()]),
+            token_1_vault: find(&[b"pool_vault", pool_state.as_ref(), token_1_mint.as_ref()]),
+            create_pool_fee_receiver: Pubkey::new_unique(),
+            observation_state: find(&[b"observation", pool_state.as_ref()]),
+        }
+    }
+
+    pub fn to_account_metas(&self) -> Vec<AccountMeta> {
+        vec![
+            AccountMeta::new(self.lp_mint, false),
+            AccountMeta::new(self.user_token_lp, false),
+            AccountMeta::new(self.pool_token_lp, false),
+            AccountMeta::new(self.amm_config, false),
+            AccountMeta::new_readonly(self.authority, false),
+            AccountMeta::new(self.pool_state, false),
+            AccountMeta::new(self.token_0_vault, false),
+            AccountMeta::new(self.token_1_vault, false),
+            AccountMeta::new(self.create_pool_fee_receiver, false),
+            AccountMeta::new(self.observation_state, false),
+        ]
+    }
+}
+
+impl TestEnv {
+    /// Creates the accounts the CPMM `initialize` would create for `user_wallet`.
+    pub fn create_cpmm_pool(&mut self, cpmm_pool: &CpmmPool, user_wallet: Pubkey) {
+        let authority = cpmm_pool.authority;
+        self.create_mint_at(cpmm_pool.lp_mint, authority, LP_DECIMALS, 0);
+        self.create_token_account_at(cpmm_pool.user_token_lp, cpmm_pool.lp_mint, user_wallet, 0);
+        for (vault, mint) in [
+            (cpmm_pool.token_0_vault, cpmm_pool.token_0_mint),
+            (cpmm_pool.token_1_vault, cpmm_pool.token_1_mint),
+        ] {
+            self.create_token_account_at(vault, mint, authority, 0);
+        }
+    }
+}
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    if data.len() != 32 || data[..8] != INITIALIZE {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    let amount = |offset: usize| u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap());
+    let (init_amount_0, init_amount_1) = (amount(8), amount(16));
+
+    // Accounts of the CPMM `initialize`, the ones the mock does not touch are skipped
+    let account = move |index: usize| {
+        accounts
+            .get(index)
+            .ok_or(ProgramError::NotEnoughAccountKeys)
+    };
+    let creator = account(0)?;
+    let authority = account(2)?;
+    let lp_mint = account(6)?;
+    let creator_token_0 = account(7)?;
+    let creator_token_1 = account(8)?;
+    let creator_token_lp = account(9)?;
+    let token_0_vault = account(10)?;
+    let token_1_vault = account(11)?;
+    let token_program = account(14)?;
+    let (expected_authority, bump) = Pubkey::find_program_address(&[AUTHORITY_SEED], program_id);
+    if *authority.key != expected_authority {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    for (from, to, amount) in [
+        (creator_token_0, token_0_vault, init_amount_0),
+        (creator_token_1, token_1_vault, init_amount_1),
+    ] {
+        invoke(
+            &spl_token::instruction::transfer(
+                token_program.key,
+                from.key,
+                to.key,
+                creator.key,
+                &[],
+                amount,
+            )?,
+            &[
+                from.clone(),
+                to.clone(),
+                creator.clone(),
+                token_program.clone(),
+            ],
+        )?;
+    }
+
+    let lp_amount = liquidity(init_amount_0, init_amount_1)
+        .checked_sub(LOCKED_LIQUIDITY)
+        .ok_or(ProgramError::InsufficientFunds)?;
+    invoke_signed(
+        &spl_token::instruction::mint_to(
+            token_program.key,
+            lp_mint.key,
+            creator_token_lp.key,
+            authority.key,
+            &[],
+            lp_amount,
+        )?,
+        &[
+            lp_mint.clone(),
+            creator_token_lp.clone(),
+            authority.clone(),
+            token_program.clone(),
+        ],
+        &[&[AUTHORITY_SEED, &[bump]]],
+    )
+}
@@ -0,0 +1,417 @@
+//! Local runtime shared by the program-test suites.
+//!
+//! The program is loaded from `target/deploy/safe_presale.so`, build it with `anchor build`.
+//! The token metadata program is loaded from `tests/fixtures/mpl_token_metadata.so`, the
+//! Raydium CPMM is replaced by the builtin in `mock_cpmm`.
+#![allow(dead_code)]
+
+pub mod mock_cpmm;
+pub mod presale;
+
+use anchor_lang::{
+    solana_program::{
+        instruction::Instruction, program_option::COption, program_pack::Pack, pubkey::Pubkey,
+        system_program, sysvar::clock::Clock,
+    },
+    AccountDeserialize, AccountSerialize, InstructionData, ToAccountMetas,
+};
+use anchor_spl::{
+    associated_token::{self, get_associated_token_address},
+    token::spl_token,
+};
+use safe_presale::{
+    instructions::InitPoolArgs,
+    state::{
+        ClaimApproval, Config, Pool, PurchaseReceipt, CLAIM_APPROVAL_PREFIX, CONFIG_PREFIX,
+        DISSOLVE_VOTE_PREFIX, MINT_PREFIX, POOL_PREFIX, POOL_SIZE, PURCHASE_RECEIPT_PREFIX,
+    },
+};
+use solana_program_test::{
+    processor, BanksTransactionResultWithMetadata, ProgramTest, ProgramTestContext,
+};
+use solana_sdk::{
+    account::{Account, AccountSharedData},
+    rent::Rent,
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+
+pub const PROGRAM_SO_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../../target/deploy");
+
+pub struct TestEnv {
+    pub context: ProgramTestContext,
+}
+
+impl TestEnv {
+    /// Starts a runtime with the program, the metadata program, the mock CPMM and an unpaused
+    /// config administered by the payer.
+    pub async fn start() -> Self {
+        if std::env::var_os("SBF_OUT_DIR").is_none() && std::env::var_os("BPF_OUT_DIR").is_none() {
+            std::env::set_var("SBF_OUT_DIR", PROGRAM_SO_DIR);
+        }
+        let mut program_test = ProgramTest::default();
+        // Passing the processor straight to `add_program` lets it coerce to the builtin
+        // function type, `add_program` only uses it when SBF is not preferred
+        program_test.prefer_bpf(false);
+        program_test.add_program(
+            "mock_cpmm",
+            mock_cpmm::program_id(),
+            processor!(mock_cpmm::process_instruction),
+        );
+        program_test.prefer_bpf(true);
+        program_test.add_program("safe_presale", safe_presale::ID, None);
+        program_test.add_program("mpl_token_metadata", mpl_token_metadata::ID, None);
+
+        let mut env = TestEnv {
+            context: program_test.start_with_context().await,
+        };
+        let admin = env.payer();
+        let (config, bump) =
+            Pubkey::find_program_address(&[CONFIG_PREFIX.as_bytes()], &safe_presale::ID);
+        let mut data = vec![];
+        Config {
+            bump,
+            admin,
+            paused: false,
+        }
+        .try_serialize(&mut data)
+        .unwrap();
+        env.set_program_account(config, data);
+        env
+    }
+
+    pub fn payer(&self) -> Pubkey {
+        self.context.payer.pubkey()
+    }
+
+    /// Processes the instructions in a single transaction paid by the payer and returns its
+    /// metadata, panicking with the program logs if it fails.
+    pub async fn process(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> BanksTransactionResultWithMetadata {
+        let blockhash = self.context.get_new_latest_blockhash().await.unwrap();
+        let mut all_signers = vec![&self.context.payer];
+        all_signers.extend_from_slice(signers);
+        let transaction = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.context.payer.pubkey()),
+            &all_signers,
+            blockhash,
+        );
+        let result = self
+            .context
+            .banks_client
+            .process_transaction_with_metadata(transaction)
+            .await
+            .unwrap();
+        if let Err(err) = &result.result {
+            let logs = result
+                .metadata
+                .as_ref()
+                .map(|metadata| metadata.log_messages.join("\n"))
+                .unwrap_or_default();
+            panic!("transaction failed: {err}\n{logs}");
+        }
+        result
+    }
+
+    /// Processes the instructions in a single transaction paid by the payer, which must fail,
+    /// and returns its error.
+    pub async fn process_err(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> TransactionError {
+        let blockhash = self.context.get_new_latest_blockhash().await.unwrap();
+        let mut all_signers = vec![&self.context.payer];
+        all_signers.extend_from_slice(signers);
+        let transaction = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.context.payer.pubkey()),
+            &all_signers,
+            blockhash,
+        );
+        self.context
+            .banks_client
+            .process_transaction(transaction)
+            .await
+            .expect_err("transaction succeeded")
+            .unwrap()
+    }
+
+    /// Processes a single instruction and returns the compute units it consumed.
+    pub async fn compute_units(&mut self, instruction: Instruction, signers: &[&Keypair]) -> u64 {
+        self.process(&[instruction], signers)
+            .await
+            .metadata
+            .unwrap()
+            .compute_units_consumed
+    }
+
+    pub async fn account(&mut self, address: Pubkey) -> Account {
+        self.context
+            .banks_client
+            .get_account(address)
+            .await
+            .unwrap()
+            .unwrap_or_else(|| panic!("account {address} does not exist"))
+    }
+
+    pub async fn pool(&mut self, pool: Pubkey) -> Pool {
+        let account = self.account(pool).await;
+        bytemuck::pod_read_unaligned(&account.data[8..POOL_SIZE])
+    }
+
+    pub async fn purchase_receipt(&mut self, purchase_receipt: Pubkey) -> PurchaseReceipt {
+        let account = self.account(purchase_receipt).await;
+        PurchaseReceipt::try_deserialize(&mut account.data.as_slice()).unwrap()
+    }
+
+    pub async fn token_amount(&mut self, token_account: Pubkey) -> u64 {
+        let account = self.account(token_account).await;
+        spl_token::state::Account::unpack(&account.data)
+            .unwrap()
+            .amount
+    }
+
+    pub async fn now(&mut self) -> i64 {
+        self.context
+            .banks_client
+            .get_sysvar::<Clock>()
+            .await
+            .unwrap()
+            .unix_timestamp
+    }
+
+    pub async fn warp_to_timestamp(&mut self, unix_timestamp: i64) {
+        let mut clock = self
+            .context
+            .banks_client
+            .get_sysvar::<Clock>()
+            .await
+            .unwrap();
+        clock.unix_timestamp = unix_timestamp;
+        self.context.set_sysvar(&clock);
+    }
+
+    fn set_account(&mut self, address: Pubkey, owner: Pubkey, data: Vec<u8>) {
+        let lamports = Rent::default().minimum_balance(data.len());
+        self.set_account_with_lamports(address, owner, data, lamports);
+    }
+
+    fn set_account_with_lamports(
+        &mut self,
+        address: Pubkey,
+        owner: Pubkey,
+        data: Vec<u8>,
+        lamports: u64,
+    ) {
+        let account = Account {
+            lamports,
+            data,
+            owner,
+            executable: false,
+            rent_epoch: 0,
+        };
+        self.context
+            .set_account(&address, &AccountSharedData::from(account));
+    }
+
+    pub fn set_program_account(&mut self, address: Pubkey, data: Vec<u8>) {
+        self.set_account(address, safe_presale::ID, data);
+    }
+
+    /// Funds a new wallet with 10 SOL.
+    pub fn create_wallet(&mut self) -> Keypair {
+        let wallet = Keypair::new();
+        self.set_account_with_lamports(wallet.pubkey(), system_program::ID, vec![], 10_000_000_000);
+        wallet
+    }
+
+    pub fn create_mint(&mut self, decimals: u8, supply: u64) -> Pubkey {
+        let mint = Pubkey::new_unique();
+        let payer = self.payer();
+        self.create_mint_at(mint, payer, decimals, supply);
+        mint
+    }
+
+    pub fn create_mint_at(
+        &mut self,
+        mint: Pubkey,
+        mint_authority: Pubkey,
+        decimals: u8,
+        supply: u64,
+    ) {
+        let mut data = vec![0; spl_token::state::Mint::LEN];
+        spl_token::state::Mint {
+            mint_authority: COption::Some(mint_authority),
+            supply,
+            decimals,
+            is_initialized: true,
+            freeze_authority: COption::None,
+        }
+        .pack_into_slice(&mut data);
+        self.set_account(mint, spl_token::ID, data);
+    }
+
+    /// Creates the associated token account of `owner` holding `amount` tokens.
+    pub fn create_token_account(&mut self, mint: Pubkey, owner: Pubkey, amount: u64) -> Pubkey {
+        let token_account = get_associated_token_address(&owner, &mint);
+        self.create_token_account_at(token_account, mint, owner, amount);
+        token_account
+    }
+
+    pub fn create_token_account_at(
+        &mut self,
+        token_account: Pubkey,
+        mint: Pubkey,
+        owner: Pubkey,
+        amount: u64,
+    ) {
+        let mut data = vec![0; spl_token::state::Account::LEN];
+        spl_token::state::Account {
+            mint,
+            owner,
+            amount,
+            state: spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        }
+        .pack_into_slice(&mut data);
+        self.set_account(token_account, spl_token::ID, data);
+    }
+
+    /// Mints a single token NFT to `owner`, returning the mint and the owner's token account.
+    pub fn create_nft(&mut self, owner: Pubkey) -> (Pubkey, Pubkey) {
+        let nft = self.create_mint(0, 1);
+        let token_account = self.create_token_account(nft, owner, 1);
+        (nft, token_account)
+    }
+}
+
+pub fn event_authority() -> Pubkey {
+    Pubkey::find_program_address(&[b"__event_authority"], &safe_presale::ID).0
+}
+
+pub fn config() -> Pubkey {
+    Pubkey::find_program_address(&[CONFIG_PREFIX.as_bytes()], &safe_presale::ID).0
+}
+
+pub fn reward_mint(random_key: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[MINT_PREFIX.as_bytes(), random_key.to_le_bytes().as_ref()],
+        &safe_presale::ID,
+    )
+    .0
+}
+
+pub fn pool(reward_mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[POOL_PREFIX.as_bytes(), reward_mint.as_ref()],
+        &safe_presale::ID,
+    )
+    .0
+}
+
+pub fn purchase_receipt(pool: &Pubkey, nft: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            PURCHASE_RECEIPT_PREFIX.as_bytes(),
+            pool.as_ref(),
+            nft.as_ref(),
+        ],
+        &safe_presale::ID,
+    )
+    .0
+}
+
+pub fn dissolve_vote_record(purchase_receipt: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[DISSOLVE_VOTE_PREFIX.as_bytes(), purchase_receipt.as_ref()],
+        &safe_presale::ID,
+    )
+    .0
+}
+
+pub fn claim_approval(
+    owner: &Pubkey,
+    delegate: &Pubkey,
+    purchase_receipt: Option<Pubkey>,
+) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            CLAIM_APPROVAL_PREFIX.as_bytes(),
+            owner.as_ref(),
+            delegate.as_ref(),
+            ClaimApproval::scope_seed(&purchase_receipt),
+        ],
+        &safe_presale::ID,
+    )
+    .0
+}
+
+pub fn metadata(mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"metadata", mpl_token_metadata::ID.as_ref(), mint.as_ref()],
+        &mpl_token_metadata::ID,
+    )
+    .0
+}
+
+pub fn instruction(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: safe_presale::ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// A presale of 1_000 quote tokens lasting an hour, without a collection requirement.
+pub fn init_pool_args(random_key: u64, quote_mint: Pubkey) -> InitPoolArgs {
+    InitPoolArgs {
+        name: "Presale".to_string(),
+        symbol: "SALE".to_string(),
+        uri: "https://example.com/presale.json".to_string(),
+        decimals: 6,
+        presale_target: 1_000_000_000,
+        presale_duration: 3600,
+        vesting_period: 86400,
+        reward_vesting_period: 86400,
+        creator_reward_vesting_period: 86400,
+        dissolve_quorum_basis_points: 0,
+        dissolve_vote_window: 0,
+        claim_period: None,
+        reclaim_destination: None,
+        max_amount_per_purchase: None,
+        liquidity_pool_supply: 800_000_000,
+        initial_supply: 200_000_000,
+        creator_fee_basis_points: 500,
+        delegate: None,
+        random_key,
+        requires_collection: false,
+        quote_mint,
+    }
+}
+
+pub fn init_pool(payer: Pubkey, args: InitPoolArgs) -> Instruction {
+    let reward_mint = reward_mint(args.random_key);
+    let pool = pool(&reward_mint);
+    instruction(
+        safe_presale::accounts::InitPoolCtx {
+            reward_mint,
+            pool,
+            config: config(),
+            reward_mint_metadata: metadata(&reward_mint),
+            pool_reward_mint_token_account: get_associated_token_address(&pool, &reward_mint),
+            payer,
+            system_program: system_program::ID,
+            token_program: spl_token::ID,
+            associated_token_program: associated_token::ID,
+            mpl_token_program: mpl_token_metadata::ID,
+            event_authority: event_authority(),
+            program: safe_presale::ID,
+        },
+        safe_presale::instruction::InitPool { args },
+    )
+}
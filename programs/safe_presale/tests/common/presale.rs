@@ -0,0 +1,515 @@
+//! A pool of the default `init_pool_args` and the instructions of its lifecycle.
+use std::str::FromStr;
+
+use anchor_lang::solana_program::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    system_program, sysvar,
+};
+use anchor_spl::{
+    associated_token::{self, get_associated_token_address},
+    token::spl_token,
+};
+use safe_presale::{
+    instructions::{ApproveClaimArgs, InitPoolArgs},
+    state::FEE_COLLECTOR,
+};
+use solana_sdk::signature::{Keypair, Signer};
+
+use super::{mock_cpmm::*, *};
+
+pub const PRESALE_TARGET: u64 = 1_000_000_000;
+const VESTING_PERIOD: i64 = 86400;
+
+/// A pool of the default `init_pool_args` with its quote token accounts.
+pub struct Presale {
+    pub pool: Pubkey,
+    pub reward_mint: Pubkey,
+    pub quote_mint: Pubkey,
+}
+
+impl Presale {
+    pub async fn init(env: &mut TestEnv) -> Self {
+        Self::init_with(env, |_| {}).await
+    }
+
+    /// Same as `init` with the default arguments changed by `configure`.
+    pub async fn init_with(env: &mut TestEnv, configure: impl FnOnce(&mut InitPoolArgs)) -> Self {
+        let payer = env.payer();
+        let quote_mint = env.create_mint(6, 10 * PRESALE_TARGET);
+        env.create_token_account(quote_mint, payer, 10 * PRESALE_TARGET);
+        env.create_token_account(quote_mint, Pubkey::from_str(FEE_COLLECTOR).unwrap(), 0);
+
+        let mut args = init_pool_args(1, quote_mint);
+        configure(&mut args);
+        let reward_mint = reward_mint(args.random_key);
+        let pool = pool(&reward_mint);
+        env.process(&[init_pool(payer, args)], &[]).await;
+        env.create_token_account(quote_mint, pool, 0);
+        Presale {
+            pool,
+            reward_mint,
+            quote_mint,
+        }
+    }
+
+    pub fn buy_presale(&self, payer: Pubkey, nft: Pubkey, amount: u64) -> Instruction {
+        instruction(
+            safe_presale::accounts::BuyPresaleCtx {
+                purchase_receipt: purchase_receipt(&self.pool, &nft),
+                pool: self.pool,
+                config: config(),
+                pool_quote_mint_token_account: get_associated_token_address(
+                    &self.pool,
+                    &self.quote_mint,
+                ),
+                quote_mint: self.quote_mint,
+                nft,
+                nft_metadata: metadata(&nft),
+                purchase_authorisation_record: None,
+                payer,
+                payer_quote_mint_token_account: get_associated_token_address(
+                    &payer,
+                    &self.quote_mint,
+                ),
+                fee_collector_quote_mint_token_account: get_associated_token_address(
+                    &Pubkey::from_str(FEE_COLLECTOR).unwrap(),
+                    &self.quote_mint,
+                ),
+                reward_mint_metadata: metadata(&self.reward_mint),
+                mpl_token_program: mpl_token_metadata::ID,
+                system_program: system_program::ID,
+                token_program: spl_token::ID,
+                event_authority: event_authority(),
+                program: safe_presale::ID,
+            },
+            safe_presale::instruction::BuyPresale { amount },
+        )
+    }
+
+    pub fn withdraw(&self, nft_owner: Pubkey, nft: Pubkey) -> Instruction {
+        instruction(
+            safe_presale::accounts::Withdraw {
+                purchase_receipt: purchase_receipt(&self.pool, &nft),
+                nft_owner_nft_token_account: get_associated_token_address(&nft_owner, &nft),
+                pool: self.pool,
+                nft_owner_quote_mint_token_account: get_associated_token_address(
+                    &nft_owner,
+                    &self.quote_mint,
+                ),
+                pool_quote_mint_token_account: get_associated_token_address(
+                    &self.pool,
+                    &self.quote_mint,
+                ),
+                quote_mint: self.quote_mint,
+                nft_owner,
+                nft_metadata: metadata(&nft),
+                staking_program_record: None,
+                staking_authority: None,
+                receipt_payer: nft_owner,
+                payer: nft_owner,
+                system_program: system_program::ID,
+                token_program: spl_token::ID,
+                associated_token_program: associated_token::ID,
+                event_authority: event_authority(),
+                program: safe_presale::ID,
+            },
+            safe_presale::instruction::Withdraw {},
+        )
+    }
+
+    pub fn launch_token_amm(&self, user_wallet: Pubkey, cpmm_pool: &CpmmPool) -> Instruction {
+        let mut instruction = instruction(
+            safe_presale::accounts::LaunchTokenAmmCtx {
+                pool: self.pool,
+                config: config(),
+                user_wallet,
+                user_token_coin: get_associated_token_address(&user_wallet, &self.reward_mint),
+                user_token_pc: get_associated_token_address(&user_wallet, &self.quote_mint),
+                pool_token_coin: get_associated_token_address(&self.pool, &self.reward_mint),
+                pool_token_pc: get_associated_token_address(&self.pool, &self.quote_mint),
+                rent: sysvar::rent::ID,
+                system_program: system_program::ID,
+                token_program: spl_token::ID,
+                associated_token_program: associated_token::ID,
+                amm_coin_mint: self.reward_mint,
+                amm_pc_mint: self.quote_mint,
+                raydium_amm_program: program_id(),
+                reward_mint_metadata: metadata(&self.reward_mint),
+                mpl_token_program: mpl_token_metadata::ID,
+                event_authority: event_authority(),
+                program: safe_presale::ID,
+            },
+            safe_presale::instruction::LaunchTokenAmm { open_time: 0 },
+        );
+        instruction.accounts.extend(cpmm_pool.to_account_metas());
+        instruction
+    }
+
+    pub fn check_claim(&self, payer: Pubkey, lp_mint: Pubkey, nft: Pubkey) -> Instruction {
+        let purchase_receipt = purchase_receipt(&self.pool, &nft);
+        instruction(
+            safe_presale::accounts::CheckClaimCtx {
+                purchase_receipt,
+                purchase_receipt_lp_token_account: get_associated_token_address(
+                    &purchase_receipt,
+                    &lp_mint,
+                ),
+                pool_lp_token_account: get_associated_token_address(&self.pool, &lp_mint),
+                lp_mint,
+                purchase_receipt_reward_token_account: get_associated_token_address(
+                    &purchase_receipt,
+                    &self.reward_mint,
+                ),
+                pool_reward_token_account: get_associated_token_address(
+                    &self.pool,
+                    &self.reward_mint,
+                ),
+                reward_mint: self.reward_mint,
+                pool: self.pool,
+                config: config(),
+                payer,
+                associated_token_program: associated_token::ID,
+                token_program: spl_token::ID,
+                system_program: system_program::ID,
+                event_authority: event_authority(),
+                program: safe_presale::ID,
+            },
+            safe_presale::instruction::CheckClaimEllgibility {},
+        )
+    }
+
+    pub fn withdraw_lp_tokens(
+        &self,
+        nft_owner: Pubkey,
+        lp_mint: Pubkey,
+        nft: Pubkey,
+    ) -> Instruction {
+        self.withdraw_lp_tokens_by(nft_owner, nft_owner, lp_mint, nft, None)
+    }
+
+    /// Lp token withdrawal paid by `payer`, the owner or a delegate approved by `claim_approval`.
+    pub fn withdraw_lp_tokens_by(
+        &self,
+        payer: Pubkey,
+        nft_owner: Pubkey,
+        lp_mint: Pubkey,
+        nft: Pubkey,
+        claim_approval: Option<Pubkey>,
+    ) -> Instruction {
+        let purchase_receipt = purchase_receipt(&self.pool, &nft);
+        instruction(
+            safe_presale::accounts::WithdrawLpCtx {
+                purchase_receipt,
+                purchase_receipt_lp_token_account: get_associated_token_address(
+                    &purchase_receipt,
+                    &lp_mint,
+                ),
+                pool: self.pool,
+                nft_owner_nft_token_account: get_associated_token_address(&nft_owner, &nft),
+                nft_owner_lp_token_account: get_associated_token_address(&nft_owner, &lp_mint),
+                nft_owner,
+                nft_metadata: metadata(&nft),
+                staking_program_record: None,
+                staking_authority: None,
+                claim_approval,
+                lp_mint,
+                payer,
+                associated_token_program: associated_token::ID,
+                token_program: spl_token::ID,
+                system_program: system_program::ID,
+                event_authority: event_authority(),
+                program: safe_presale::ID,
+            },
+            safe_presale::instruction::WithdrawLpTokens {},
+        )
+    }
+
+    pub fn approve_claim(
+        &self,
+        owner: Pubkey,
+        delegate: Pubkey,
+        args: ApproveClaimArgs,
+    ) -> Instruction {
+        instruction(
+            safe_presale::accounts::ApproveClaimCtx {
+                claim_approval: claim_approval(&owner, &delegate, args.purchase_receipt),
+                delegate,
+                owner,
+                system_program: system_program::ID,
+                event_authority: event_authority(),
+                program: safe_presale::ID,
+            },
+            safe_presale::instruction::ApproveClaim { args },
+        )
+    }
+
+    pub fn revoke_claim_approval(&self, owner: Pubkey, claim_approval: Pubkey) -> Instruction {
+        instruction(
+            safe_presale::accounts::RevokeClaimApprovalCtx {
+                claim_approval,
+                owner,
+                event_authority: event_authority(),
+                program: safe_presale::ID,
+            },
+            safe_presale::instruction::RevokeClaimApproval {},
+        )
+    }
+
+    pub fn claim_reward_token(&self, nft_owner: Pubkey, nft: Pubkey) -> Instruction {
+        let purchase_receipt = purchase_receipt(&self.pool, &nft);
+        instruction(
+            safe_presale::accounts::ClaimRewardCtx {
+                purchase_receipt,
+                purchase_receipt_reward_token_account: get_associated_token_address(
+                    &purchase_receipt,
+                    &self.reward_mint,
+                ),
+                pool: self.pool,
+                nft_owner_nft_token_account: get_associated_token_address(&nft_owner, &nft),
+                nft_owner_reward_token_account: get_associated_token_address(
+                    &nft_owner,
+                    &self.reward_mint,
+                ),
+                nft_owner,
+                nft_metadata: metadata(&nft),
+                staking_program_record: None,
+                staking_authority: None,
+                claim_approval: None,
+                reward_mint: self.reward_mint,
+                payer: nft_owner,
+                associated_token_program: associated_token::ID,
+                token_program: spl_token::ID,
+                system_program: system_program::ID,
+                event_authority: event_authority(),
+                program: safe_presale::ID,
+            },
+            safe_presale::instruction::ClaimRewardToken {},
+        )
+    }
+
+    pub fn withdraw_lp_tokens_for_creators(
+        &self,
+        authority: Pubkey,
+        lp_mint: Pubkey,
+    ) -> Instruction {
+        instruction(
+            safe_presale::accounts::WithdrawLpCreatorCtx {
+                pool_lp_token_account: get_associated_token_address(&self.pool, &lp_mint),
+                pool_authority_lp_token_account: get_associated_token_address(&authority, &lp_mint),
+                lp_mint,
+                pool: self.pool,
+                authority,
+                payer: authority,
+                associated_token_program: associated_token::ID,
+                token_program: spl_token::ID,
+                system_program: system_program::ID,
+                event_authority: event_authority(),
+                program: safe_presale::ID,
+            },
+            safe_presale::instruction::WithdrawLpTokensForCreators {},
+        )
+    }
+
+    pub fn claim_reward_token_for_creators(&self, authority: Pubkey) -> Instruction {
+        instruction(
+            safe_presale::accounts::ClaimRewardCreatorCtx {
+                pool_reward_token_account: get_associated_token_address(
+                    &self.pool,
+                    &self.reward_mint,
+                ),
+                pool_authority_reward_token_account: get_associated_token_address(
+                    &authority,
+                    &self.reward_mint,
+                ),
+                reward_mint: self.reward_mint,
+                pool: self.pool,
+                authority,
+                payer: authority,
+                associated_token_program: associated_token::ID,
+                token_program: spl_token::ID,
+                system_program: system_program::ID,
+                event_authority: event_authority(),
+                program: safe_presale::ID,
+            },
+            safe_presale::instruction::ClaimRewardTokenForCreators {},
+        )
+    }
+
+    pub fn claim_all(&self, nft_owner: Pubkey, lp_mint: Pubkey, nft: Pubkey) -> Instruction {
+        instruction(
+            safe_presale::accounts::ClaimAllCtx {
+                purchase_receipt: purchase_receipt(&self.pool, &nft),
+                pool: self.pool,
+                pool_lp_token_account: get_associated_token_address(&self.pool, &lp_mint),
+                pool_reward_token_account: get_associated_token_address(
+                    &self.pool,
+                    &self.reward_mint,
+                ),
+                nft_owner_nft_token_account: get_associated_token_address(&nft_owner, &nft),
+                nft_owner_lp_token_account: get_associated_token_address(&nft_owner, &lp_mint),
+                nft_owner_reward_token_account: get_associated_token_address(
+                    &nft_owner,
+                    &self.reward_mint,
+                ),
+                nft_owner,
+                lp_mint,
+                reward_mint: self.reward_mint,
+                config: config(),
+                payer: nft_owner,
+                associated_token_program: associated_token::ID,
+                token_program: spl_token::ID,
+                system_program: system_program::ID,
+                event_authority: event_authority(),
+                program: safe_presale::ID,
+            },
+            safe_presale::instruction::ClaimAll {},
+        )
+    }
+
+    pub fn batch_withdraw_lp_tokens(
+        &self,
+        nft_owner: Pubkey,
+        lp_mint: Pubkey,
+        nfts: &[Pubkey],
+    ) -> Instruction {
+        self.batch_withdraw_lp_tokens_by(nft_owner, nft_owner, lp_mint, nfts)
+    }
+
+    /// Batch lp token withdrawal paid by `payer`, only the owner is allowed to sign it.
+    pub fn batch_withdraw_lp_tokens_by(
+        &self,
+        payer: Pubkey,
+        nft_owner: Pubkey,
+        lp_mint: Pubkey,
+        nfts: &[Pubkey],
+    ) -> Instruction {
+        let mut instruction = instruction(
+            safe_presale::accounts::BatchWithdrawLpCtx {
+                pool: self.pool,
+                pool_lp_token_account: get_associated_token_address(&self.pool, &lp_mint),
+                nft_owner_lp_token_account: get_associated_token_address(&nft_owner, &lp_mint),
+                nft_owner,
+                lp_mint,
+                config: config(),
+                payer,
+                associated_token_program: associated_token::ID,
+                token_program: spl_token::ID,
+                system_program: system_program::ID,
+                event_authority: event_authority(),
+                program: safe_presale::ID,
+            },
+            safe_presale::instruction::BatchWithdrawLpTokens {},
+        );
+        instruction
+            .accounts
+            .extend(self.purchase_receipt_pairs(nft_owner, nfts));
+        instruction
+    }
+
+    pub fn batch_claim_reward_token(&self, nft_owner: Pubkey, nfts: &[Pubkey]) -> Instruction {
+        let mut instruction = instruction(
+            safe_presale::accounts::BatchClaimRewardCtx {
+                pool: self.pool,
+                pool_reward_token_account: get_associated_token_address(
+                    &self.pool,
+                    &self.reward_mint,
+                ),
+                nft_owner_reward_token_account: get_associated_token_address(
+                    &nft_owner,
+                    &self.reward_mint,
+                ),
+                nft_owner,
+                reward_mint: self.reward_mint,
+                config: config(),
+                payer: nft_owner,
+                associated_token_program: associated_token::ID,
+                token_program: spl_token::ID,
+                system_program: system_program::ID,
+                event_authority: event_authority(),
+                program: safe_presale::ID,
+            },
+            safe_presale::instruction::BatchClaimRewardToken {},
+        );
+        instruction
+            .accounts
+            .extend(self.purchase_receipt_pairs(nft_owner, nfts));
+        instruction
+    }
+
+    /// Remaining accounts of the batch claims, see `utils::purchase_receipt_pairs`.
+    fn purchase_receipt_pairs(&self, nft_owner: Pubkey, nfts: &[Pubkey]) -> Vec<AccountMeta> {
+        nfts.iter()
+            .flat_map(|nft| {
+                [
+                    AccountMeta::new(purchase_receipt(&self.pool, nft), false),
+                    AccountMeta::new_readonly(get_associated_token_address(&nft_owner, nft), false),
+                ]
+            })
+            .collect()
+    }
+
+    pub fn vote_dissolve(&self, nft_owner: Pubkey, nft: Pubkey) -> Instruction {
+        let purchase_receipt = purchase_receipt(&self.pool, &nft);
+        instruction(
+            safe_presale::accounts::VoteDissolveCtx {
+                dissolve_vote_record: dissolve_vote_record(&purchase_receipt),
+                purchase_receipt,
+                nft_owner_nft_token_account: get_associated_token_address(&nft_owner, &nft),
+                pool: self.pool,
+                config: config(),
+                payer: nft_owner,
+                system_program: system_program::ID,
+                event_authority: event_authority(),
+                program: safe_presale::ID,
+            },
+            safe_presale::instruction::VoteDissolve {},
+        )
+    }
+
+    pub fn claim_dissolved_lp_tokens(
+        &self,
+        nft_owner: Pubkey,
+        lp_mint: Pubkey,
+        nft: Pubkey,
+    ) -> Instruction {
+        instruction(
+            safe_presale::accounts::ClaimDissolvedLpCtx {
+                purchase_receipt: purchase_receipt(&self.pool, &nft),
+                pool_lp_token_account: get_associated_token_address(&self.pool, &lp_mint),
+                pool: self.pool,
+                nft_owner_nft_token_account: get_associated_token_address(&nft_owner, &nft),
+                nft_owner_lp_token_account: get_associated_token_address(&nft_owner, &lp_mint),
+                nft_owner,
+                lp_mint,
+                config: config(),
+                payer: nft_owner,
+                associated_token_program: associated_token::ID,
+                token_program: spl_token::ID,
+                system_program: system_program::ID,
+                event_authority: event_authority(),
+                program: safe_presale::ID,
+            },
+            safe_presale::instruction::ClaimDissolvedLpTokens {},
+        )
+    }
+}
+
+/// A contributor with its own wallet, quote tokens and nft.
+pub struct Contributor {
+    pub wallet: Keypair,
+    pub nft: Pubkey,
+}
+
+impl Contributor {
+    pub fn new(env: &mut TestEnv, presale: &Presale, quote_amount: u64) -> Self {
+        let wallet = env.create_wallet();
+        env.create_token_account(presale.quote_mint, wallet.pubkey(), quote_amount);
+        let (nft, _) = env.create_nft(wallet.pubkey());
+        Contributor { wallet, nft }
+    }
+
+    pub fn pubkey(&self) -> Pubkey {
+        self.wallet.pubkey()
+    }
+}
@@ -0,0 +1,329 @@
+//! Compute units consumed by each instruction, checked against a budget so that regressions
+//! fail the test run. Needs the deployable program, run `anchor build` first.
+mod common;
+
+use std::str::FromStr;
+
+use anchor_lang::solana_program::{pubkey::Pubkey, system_program};
+use anchor_spl::{associated_token, token::spl_token};
+use common::{mock_cpmm::CpmmPool, presale::*, *};
+use safe_presale::state::{PoolStatus, FEE_COLLECTOR, PURCHASE_RECEIPT_PREFIX};
+
+/// Upper bounds in compute units, lower them when an instruction gets cheaper.
+const BUDGETS: &[(&str, u64)] = &[
+    ("init_pool", 150_000),
+    ("buy_presale", 80_000),
+    ("quote_pool", 10_000),
+    ("set_pool_frozen", 15_000),
+    ("sync_pool_status", 15_000),
+    ("withdraw", 60_000),
+    ("launch_token_amm", 150_000),
+    ("check_claim_ellgibility", 100_000),
+    ("withdraw_lp_tokens", 70_000),
+    ("claim_reward_token", 70_000),
+    ("claim_all", 100_000),
+    ("batch_withdraw_lp_tokens", 80_000),
+    ("batch_claim_reward_token", 80_000),
+    ("withdraw_lp_tokens_for_creators", 60_000),
+    ("claim_reward_token_for_creators", 60_000),
+    ("vote_dissolve", 40_000),
+    ("claim_dissolved_lp_tokens", 60_000),
+];
+
+#[derive(Default)]
+struct Report {
+    measured: Vec<(&'static str, u64)>,
+}
+
+impl Report {
+    fn record(&mut self, name: &'static str, units: u64) {
+        self.measured.push((name, units));
+    }
+
+    fn check(&self) {
+        let mut over_budget = vec![];
+        println!("{:<32} {:>10} {:>10}", "instruction", "units", "budget");
+        for (name, units) in &self.measured {
+            let budget = budget(name);
+            println!("{name:<32} {units:>10} {budget:>10}");
+            if *units > budget {
+                over_budget.push(*name);
+            }
+        }
+        assert!(
+            over_budget.is_empty(),
+            "over compute unit budget: {over_budget:?}"
+        );
+    }
+}
+
+fn budget(name: &str) -> u64 {
+    BUDGETS
+        .iter()
+        .find(|(instruction, _)| *instruction == name)
+        .map(|(_, budget)| *budget)
+        .unwrap_or_else(|| panic!("no compute unit budget for {name}"))
+}
+
+#[tokio::test]
+#[ignore = "needs target/deploy/safe_presale.so, run `anchor build` first"]
+async fn instructions_stay_within_compute_budget() {
+    let mut env = TestEnv::start().await;
+    let mut report = Report::default();
+    let payer = env.payer();
+
+    let quote_mint = env.create_mint(6, 10_000_000_000);
+    let payer_quote_token_account = env.create_token_account(quote_mint, payer, 10_000_000_000);
+    let fee_collector_quote_token_account =
+        env.create_token_account(quote_mint, Pubkey::from_str(FEE_COLLECTOR).unwrap(), 0);
+
+    let args = init_pool_args(1, quote_mint);
+    let reward_mint = reward_mint(args.random_key);
+    let pool = pool(&reward_mint);
+    report.record(
+        "init_pool",
+        env.compute_units(init_pool(payer, args), &[]).await,
+    );
+
+    let pool_quote_token_account = env.create_token_account(quote_mint, pool, 0);
+
+    let (nft, nft_token_account) = env.create_nft(payer);
+    let purchase_receipt = Pubkey::find_program_address(
+        &[
+            PURCHASE_RECEIPT_PREFIX.as_bytes(),
+            pool.as_ref(),
+            nft.as_ref(),
+        ],
+        &safe_presale::ID,
+    )
+    .0;
+    let buy_presale = instruction(
+        safe_presale::accounts::BuyPresaleCtx {
+            purchase_receipt,
+            pool,
+            config: config(),
+            pool_quote_mint_token_account: pool_quote_token_account,
+            quote_mint,
+            nft,
+            nft_metadata: metadata(&nft),
+            purchase_authorisation_record: None,
+            payer,
+            payer_quote_mint_token_account: payer_quote_token_account,
+            fee_collector_quote_mint_token_account: fee_collector_quote_token_account,
+            reward_mint_metadata: metadata(&reward_mint),
+            mpl_token_program: mpl_token_metadata::ID,
+            system_program: system_program::ID,
+            token_program: spl_token::ID,
+            event_authority: event_authority(),
+            program: safe_presale::ID,
+        },
+        safe_presale::instruction::BuyPresale {
+            amount: 100_000_000,
+        },
+    );
+    report.record("buy_presale", env.compute_units(buy_presale, &[]).await);
+    assert_eq!(
+        env.token_amount(pool_quote_token_account).await,
+        100_000_000
+    );
+
+    let quote_pool = instruction(
+        safe_presale::accounts::QuotePoolCtx { pool },
+        safe_presale::instruction::QuotePool {},
+    );
+    report.record("quote_pool", env.compute_units(quote_pool, &[]).await);
+
+    for frozen in [true, false] {
+        let set_pool_frozen = instruction(
+            safe_presale::accounts::SetPoolFrozenCtx {
+                config: config(),
+                pool,
+                admin: payer,
+                event_authority: event_authority(),
+                program: safe_presale::ID,
+            },
+            safe_presale::instruction::SetPoolFrozen { frozen },
+        );
+        report.record(
+            "set_pool_frozen",
+            env.compute_units(set_pool_frozen, &[]).await,
+        );
+    }
+
+    let presale_time_limit = env.pool(pool).await.presale_time_limit;
+    env.warp_to_timestamp(presale_time_limit + 1).await;
+    let sync_pool_status = instruction(
+        safe_presale::accounts::SyncPoolStatusCtx {
+            pool,
+            config: config(),
+            event_authority: event_authority(),
+            program: safe_presale::ID,
+        },
+        safe_presale::instruction::SyncPoolStatus {},
+    );
+    report.record(
+        "sync_pool_status",
+        env.compute_units(sync_pool_status, &[]).await,
+    );
+    assert_eq!(env.pool(pool).await.status(), PoolStatus::Failed);
+
+    let withdraw = instruction(
+        safe_presale::accounts::Withdraw {
+            purchase_receipt,
+            nft_owner_nft_token_account: nft_token_account,
+            pool,
+            nft_owner_quote_mint_token_account: payer_quote_token_account,
+            pool_quote_mint_token_account: pool_quote_token_account,
+            quote_mint,
+            nft_owner: payer,
+            nft_metadata: metadata(&nft),
+            staking_program_record: None,
+            staking_authority: None,
+            receipt_payer: payer,
+            payer,
+            system_program: system_program::ID,
+            token_program: spl_token::ID,
+            associated_token_program: associated_token::ID,
+            event_authority: event_authority(),
+            program: safe_presale::ID,
+        },
+        safe_presale::instruction::Withdraw {},
+    );
+    report.record("withdraw", env.compute_units(withdraw, &[]).await);
+    assert_eq!(env.token_amount(pool_quote_token_account).await, 0);
+
+    report.check();
+}
+
+#[tokio::test]
+#[ignore = "needs target/deploy/safe_presale.so, run `anchor build` first"]
+async fn claims_stay_within_compute_budget() {
+    let mut env = TestEnv::start().await;
+    let mut report = Report::default();
+    let creator = env.payer();
+    let presale = Presale::init_with(&mut env, |args| {
+        args.dissolve_quorum_basis_points = 5_000;
+        args.dissolve_vote_window = 3600;
+    })
+    .await;
+
+    // One contributor per way of claiming: escrowed, claim_all and the batch claims
+    let mut contributors = vec![];
+    for amount in [500_000_000, 300_000_000, 200_000_000] {
+        let contributor = Contributor::new(&mut env, &presale, PRESALE_TARGET);
+        env.process(
+            &[presale.buy_presale(contributor.pubkey(), contributor.nft, amount)],
+            &[&contributor.wallet],
+        )
+        .await;
+        contributors.push(contributor);
+    }
+    let [escrowed, direct, batched] = &contributors[..] else {
+        unreachable!()
+    };
+    assert_eq!(env.pool(presale.pool).await.status(), PoolStatus::Filled);
+
+    let cpmm_pool = CpmmPool::new(
+        &presale.pool,
+        &presale.reward_mint,
+        &presale.quote_mint,
+        &creator,
+    );
+    let lp_mint = cpmm_pool.lp_mint;
+    env.create_cpmm_pool(&cpmm_pool, creator);
+    let presale_time_limit = env.pool(presale.pool).await.presale_time_limit;
+    env.warp_to_timestamp(presale_time_limit).await;
+    report.record(
+        "launch_token_amm",
+        env.compute_units(presale.launch_token_amm(creator, &cpmm_pool), &[])
+            .await,
+    );
+    let state = env.pool(presale.pool).await;
+    assert_eq!(state.status(), PoolStatus::Launched);
+
+    report.record(
+        "check_claim_ellgibility",
+        env.compute_units(presale.check_claim(creator, lp_mint, escrowed.nft), &[])
+            .await,
+    );
+
+    // Halfway through the vesting so that every claim transfers something
+    env.warp_to_timestamp(
+        state.vesting_started_at.get().unwrap() + i64::from(state.vesting_period) / 2,
+    )
+    .await;
+    report.record(
+        "withdraw_lp_tokens",
+        env.compute_units(
+            presale.withdraw_lp_tokens(escrowed.pubkey(), lp_mint, escrowed.nft),
+            &[&escrowed.wallet],
+        )
+        .await,
+    );
+    report.record(
+        "claim_reward_token",
+        env.compute_units(
+            presale.claim_reward_token(escrowed.pubkey(), escrowed.nft),
+            &[&escrowed.wallet],
+        )
+        .await,
+    );
+    report.record(
+        "claim_all",
+        env.compute_units(
+            presale.claim_all(direct.pubkey(), lp_mint, direct.nft),
+            &[&direct.wallet],
+        )
+        .await,
+    );
+    report.record(
+        "batch_withdraw_lp_tokens",
+        env.compute_units(
+            presale.batch_withdraw_lp_tokens(batched.pubkey(), lp_mint, &[batched.nft]),
+            &[&batched.wallet],
+        )
+        .await,
+    );
+    report.record(
+        "batch_claim_reward_token",
+        env.compute_units(
+            presale.batch_claim_reward_token(batched.pubkey(), &[batched.nft]),
+            &[&batched.wallet],
+        )
+        .await,
+    );
+    report.record(
+        "withdraw_lp_tokens_for_creators",
+        env.compute_units(
+            presale.withdraw_lp_tokens_for_creators(creator, lp_mint),
+            &[],
+        )
+        .await,
+    );
+    report.record(
+        "claim_reward_token_for_creators",
+        env.compute_units(presale.claim_reward_token_for_creators(creator), &[])
+            .await,
+    );
+
+    // Half of the contributions reach the quorum and dissolve the pool
+    report.record(
+        "vote_dissolve",
+        env.compute_units(
+            presale.vote_dissolve(escrowed.pubkey(), escrowed.nft),
+            &[&escrowed.wallet],
+        )
+        .await,
+    );
+    assert_eq!(env.pool(presale.pool).await.status(), PoolStatus::Cancelled);
+    report.record(
+        "claim_dissolved_lp_tokens",
+        env.compute_units(
+            presale.claim_dissolved_lp_tokens(escrowed.pubkey(), lp_mint, escrowed.nft),
+            &[&escrowed.wallet],
+        )
+        .await,
+    );
+
+    report.check();
+}
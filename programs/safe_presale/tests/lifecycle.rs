@@ -0,0 +1,341 @@
+//! Whole presale lifecycles on a local runtime, launching on the mock CPMM. Needs the
+//! deployable program, run `anchor build` first.
+mod common;
+
+use anchor_lang::solana_program::instruction::InstructionError;
+use anchor_spl::associated_token::get_associated_token_address;
+use common::{mock_cpmm::*, presale::*, *};
+use mpl_token_metadata::accounts::Metadata;
+use safe_presale::{error::CustomError, instructions::ApproveClaimArgs, state::PoolStatus};
+use solana_sdk::{signature::Signer, transaction::TransactionError};
+
+const VESTING_PERIOD: i64 = 86400;
+
+/// Share of `supply` owed for `amount` contributed to a filled presale, rounded down.
+fn pro_rata(supply: u64, amount: u64) -> u64 {
+    (u128::from(supply) * u128::from(amount) / u128::from(PRESALE_TARGET)) as u64
+}
+
+/// Linear vesting of `total` at `elapsed` seconds, rounded down like the program.
+fn vested(total: u64, elapsed: i64) -> u64 {
+    (u128::from(total) * elapsed.min(VESTING_PERIOD) as u128 / VESTING_PERIOD as u128) as u64
+}
+
+#[tokio::test]
+#[ignore = "needs target/deploy/safe_presale.so, run `anchor build` first"]
+async fn failed_presale_refunds_contributors() {
+    let mut env = TestEnv::start().await;
+    let presale = Presale::init(&mut env).await;
+    let contributor = Contributor::new(&mut env, &presale, PRESALE_TARGET);
+    let pool_quote_token_account = get_associated_token_address(&presale.pool, &presale.quote_mint);
+    let contributor_quote_token_account =
+        get_associated_token_address(&contributor.pubkey(), &presale.quote_mint);
+
+    env.process(
+        &[presale.buy_presale(contributor.pubkey(), contributor.nft, 300_000_000)],
+        &[&contributor.wallet],
+    )
+    .await;
+    assert_eq!(env.pool(presale.pool).await.status(), PoolStatus::Open);
+    assert_eq!(
+        env.token_amount(pool_quote_token_account).await,
+        300_000_000
+    );
+    // 1% fee to the fee collector on top of the contribution
+    assert_eq!(
+        env.token_amount(contributor_quote_token_account).await,
+        PRESALE_TARGET - 303_000_000
+    );
+
+    let presale_time_limit = env.pool(presale.pool).await.presale_time_limit;
+    env.warp_to_timestamp(presale_time_limit).await;
+    env.process(
+        &[presale.withdraw(contributor.pubkey(), contributor.nft)],
+        &[&contributor.wallet],
+    )
+    .await;
+
+    let state = env.pool(presale.pool).await;
+    assert_eq!(state.status(), PoolStatus::Failed);
+    assert_eq!(state.liquidity_collected, 300_000_000);
+    assert_eq!(env.token_amount(pool_quote_token_account).await, 0);
+    assert_eq!(
+        env.token_amount(contributor_quote_token_account).await,
+        PRESALE_TARGET - 3_000_000
+    );
+}
+
+#[tokio::test]
+#[ignore = "needs target/deploy/safe_presale.so, run `anchor build` first"]
+async fn launched_presale_vests_to_contributors_and_creator() {
+    let mut env = TestEnv::start().await;
+    let creator = env.payer();
+    let presale = Presale::init(&mut env).await;
+    let contributors = [
+        Contributor::new(&mut env, &presale, PRESALE_TARGET),
+        Contributor::new(&mut env, &presale, PRESALE_TARGET),
+    ];
+    let amounts = [600_000_000, 400_000_000];
+
+    for (contributor, amount) in contributors.iter().zip(amounts) {
+        env.process(
+            &[presale.buy_presale(contributor.pubkey(), contributor.nft, amount)],
+            &[&contributor.wallet],
+        )
+        .await;
+    }
+    let state = env.pool(presale.pool).await;
+    assert_eq!(state.status(), PoolStatus::Filled);
+    assert_eq!(state.liquidity_collected, PRESALE_TARGET);
+    assert_eq!(state.purchase_receipt_count, 2);
+    // The first purchase locked the reward mint metadata
+    let reward_mint_metadata = env.account(metadata(&presale.reward_mint)).await;
+    assert!(
+        !Metadata::from_bytes(&reward_mint_metadata.data)
+            .unwrap()
+            .is_mutable
+    );
+
+    // Launch once the presale ends
+    let cpmm_pool = CpmmPool::new(
+        &presale.pool,
+        &presale.reward_mint,
+        &presale.quote_mint,
+        &creator,
+    );
+    env.create_cpmm_pool(&cpmm_pool, creator);
+    env.warp_to_timestamp(state.presale_time_limit).await;
+    env.process(&[presale.launch_token_amm(creator, &cpmm_pool)], &[])
+        .await;
+
+    let state = env.pool(presale.pool).await;
+    assert_eq!(state.status(), PoolStatus::Launched);
+    assert_eq!(state.lp_mint.get(), Some(cpmm_pool.lp_mint));
+    assert_eq!(state.launch_payer.get(), Some(creator));
+    let vesting_started_at = state.vesting_started_at.get().unwrap();
+    assert_eq!(vesting_started_at, state.presale_time_limit);
+    let lp_mint_supply = liquidity(state.liquidity_pool_supply, PRESALE_TARGET) - LOCKED_LIQUIDITY;
+    let lp_mint_supply_for_creator = lp_mint_supply * 500 / 10_000;
+    assert_eq!(state.lp_mint_supply.get(), Some(lp_mint_supply));
+    assert_eq!(
+        state.lp_mint_supply_for_creator.get(),
+        Some(lp_mint_supply_for_creator)
+    );
+    assert_eq!(
+        env.token_amount(cpmm_pool.token_0_vault).await
+            + env.token_amount(cpmm_pool.token_1_vault).await,
+        state.liquidity_pool_supply + PRESALE_TARGET
+    );
+    assert_eq!(
+        env.token_amount(cpmm_pool.pool_token_lp).await,
+        lp_mint_supply
+    );
+    assert_eq!(env.token_amount(cpmm_pool.user_token_lp).await, 0);
+
+    // Escrow every entitlement, pro rata to the contribution
+    let lp_for_contributors = lp_mint_supply - lp_mint_supply_for_creator;
+    let mint_for_contributors = state.initial_supply - state.initial_supply_for_creator;
+    let mut entitlements = vec![];
+    for (contributor, amount) in contributors.iter().zip(amounts) {
+        env.process(
+            &[presale.check_claim(creator, cpmm_pool.lp_mint, contributor.nft)],
+            &[],
+        )
+        .await;
+        let receipt = env
+            .purchase_receipt(purchase_receipt(&presale.pool, &contributor.nft))
+            .await;
+        let lp_elligible = pro_rata(lp_for_contributors, amount);
+        let mint_elligible = pro_rata(mint_for_contributors, amount);
+        assert_eq!(receipt.lp_elligible, Some(lp_elligible));
+        assert_eq!(receipt.mint_elligible, Some(mint_elligible));
+        entitlements.push((lp_elligible, mint_elligible));
+    }
+    let state = env.pool(presale.pool).await;
+    assert_eq!(state.purchase_receipts_checked, 2);
+    assert!(state.lp_mint_distributed <= lp_for_contributors);
+    assert!(state.initial_supply_distributed <= mint_for_contributors);
+
+    // Claim halfway through the vesting, then once it has ended
+    let creator_lp_token_account = get_associated_token_address(&creator, &cpmm_pool.lp_mint);
+    let creator_reward_token_account = get_associated_token_address(&creator, &presale.reward_mint);
+    for elapsed in [VESTING_PERIOD / 2, VESTING_PERIOD] {
+        env.warp_to_timestamp(vesting_started_at + elapsed).await;
+        for (contributor, (lp_elligible, mint_elligible)) in contributors.iter().zip(&entitlements)
+        {
+            env.process(
+                &[
+                    presale.withdraw_lp_tokens(
+                        contributor.pubkey(),
+                        cpmm_pool.lp_mint,
+                        contributor.nft,
+                    ),
+                    presale.claim_reward_token(contributor.pubkey(), contributor.nft),
+                ],
+                &[&contributor.wallet],
+            )
+            .await;
+            let lp_token_account =
+                get_associated_token_address(&contributor.pubkey(), &cpmm_pool.lp_mint);
+            let reward_token_account =
+                get_associated_token_address(&contributor.pubkey(), &presale.reward_mint);
+            assert_eq!(
+                env.token_amount(lp_token_account).await,
+                vested(*lp_elligible, elapsed)
+            );
+            assert_eq!(
+                env.token_amount(reward_token_account).await,
+                vested(*mint_elligible, elapsed)
+            );
+        }
+
+        env.process(
+            &[
+                presale.withdraw_lp_tokens_for_creators(creator, cpmm_pool.lp_mint),
+                presale.claim_reward_token_for_creators(creator),
+            ],
+            &[],
+        )
+        .await;
+        assert_eq!(
+            env.token_amount(creator_lp_token_account).await,
+            vested(lp_mint_supply_for_creator, elapsed)
+        );
+        assert_eq!(
+            env.token_amount(creator_reward_token_account).await,
+            vested(state.initial_supply_for_creator, elapsed)
+        );
+    }
+
+    // Only the rounding dust of the pro rata split stays in the pool
+    let state = env.pool(presale.pool).await;
+    assert_eq!(state.lp_mint_claimed_by_creator, lp_mint_supply_for_creator);
+    assert_eq!(
+        state.initial_supply_claimed_by_creator,
+        state.initial_supply_for_creator
+    );
+    let pool_lp_dust = env.token_amount(cpmm_pool.pool_token_lp).await;
+    assert_eq!(
+        pool_lp_dust,
+        lp_for_contributors - state.lp_mint_distributed
+    );
+    assert!(pool_lp_dust < contributors.len() as u64);
+}
+
+#[tokio::test]
+#[ignore = "needs target/deploy/safe_presale.so, run `anchor build` first"]
+async fn approved_delegate_claims_are_capped() {
+    let mut env = TestEnv::start().await;
+    let creator = env.payer();
+    let presale = Presale::init(&mut env).await;
+    let contributor = Contributor::new(&mut env, &presale, PRESALE_TARGET);
+    env.process(
+        &[presale.buy_presale(contributor.pubkey(), contributor.nft, PRESALE_TARGET)],
+        &[&contributor.wallet],
+    )
+    .await;
+    let cpmm_pool = CpmmPool::new(
+        &presale.pool,
+        &presale.reward_mint,
+        &presale.quote_mint,
+        &creator,
+    );
+    env.create_cpmm_pool(&cpmm_pool, creator);
+    let state = env.pool(presale.pool).await;
+    env.warp_to_timestamp(state.presale_time_limit).await;
+    env.process(
+        &[
+            presale.launch_token_amm(creator, &cpmm_pool),
+            presale.check_claim(creator, cpmm_pool.lp_mint, contributor.nft),
+        ],
+        &[],
+    )
+    .await;
+
+    let delegate = env.create_wallet();
+    let purchase_receipt = purchase_receipt(&presale.pool, &contributor.nft);
+    let approval = claim_approval(
+        &contributor.pubkey(),
+        &delegate.pubkey(),
+        Some(purchase_receipt),
+    );
+    let approve = |max_lp_per_claim| {
+        presale.approve_claim(
+            contributor.pubkey(),
+            delegate.pubkey(),
+            ApproveClaimArgs {
+                purchase_receipt: Some(purchase_receipt),
+                expires_at: None,
+                max_lp_per_claim: Some(max_lp_per_claim),
+                max_mint_per_claim: None,
+            },
+        )
+    };
+    let withdraw = presale.withdraw_lp_tokens_by(
+        delegate.pubkey(),
+        contributor.pubkey(),
+        cpmm_pool.lp_mint,
+        contributor.nft,
+        Some(approval),
+    );
+    env.process(&[approve(1_000)], &[&contributor.wallet]).await;
+    let vesting_started_at = env
+        .pool(presale.pool)
+        .await
+        .vesting_started_at
+        .get()
+        .unwrap();
+    env.warp_to_timestamp(vesting_started_at + VESTING_PERIOD / 2)
+        .await;
+
+    // Repeating the claim in one transaction does not get around the cap
+    assert_eq!(
+        env.process_err(&[withdraw.clone(), withdraw.clone()], &[&delegate])
+            .await,
+        TransactionError::InstructionError(
+            1,
+            InstructionError::Custom(CustomError::ApprovedClaimRepeated.into())
+        )
+    );
+    env.process(std::slice::from_ref(&withdraw), &[&delegate])
+        .await;
+    let lp_token_account = get_associated_token_address(&contributor.pubkey(), &cpmm_pool.lp_mint);
+    assert_eq!(env.token_amount(lp_token_account).await, 1_000);
+
+    // Re-approving after a claim needs a revoke first
+    assert!(matches!(
+        env.process_err(&[approve(2_000)], &[&contributor.wallet])
+            .await,
+        TransactionError::InstructionError(0, _)
+    ));
+    env.process(
+        &[
+            presale.revoke_claim_approval(contributor.pubkey(), approval),
+            approve(2_000),
+        ],
+        &[&contributor.wallet],
+    )
+    .await;
+    env.warp_to_timestamp(vesting_started_at + VESTING_PERIOD / 2 + 1)
+        .await;
+    env.process(&[withdraw], &[&delegate]).await;
+    assert_eq!(env.token_amount(lp_token_account).await, 3_000);
+
+    // Approvals don't extend to the batch instructions
+    assert_eq!(
+        env.process_err(
+            &[presale.batch_withdraw_lp_tokens_by(
+                delegate.pubkey(),
+                contributor.pubkey(),
+                cpmm_pool.lp_mint,
+                &[contributor.nft],
+            )],
+            &[&delegate]
+        )
+        .await,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(CustomError::BatchRequiresNftOwner.into())
+        )
+    );
+}
@@ -205,6 +205,39 @@ describe("Safe Presale", () => {
     return u64;
   }
 
+  step("Initialize the program config", async () => {
+    const [config] = PublicKey.findProgramAddressSync(
+      [Buffer.from("config")],
+      program.programId
+    );
+    if (await program.provider.connection.getAccountInfo(config)) {
+      return;
+    }
+    const [programData] = PublicKey.findProgramAddressSync(
+      [program.programId.toBuffer()],
+      new PublicKey("BPFLoaderUpgradeab1e11111111111111111111111")
+    );
+    try {
+      await program.methods
+        .initConfig(toWeb3JsPublicKey(signer.publicKey))
+        .accounts({
+          safePresaleProgram: program.programId,
+          programData: programData,
+          payer: signer.publicKey,
+        })
+        .signers([toWeb3JsKeypair(signer)])
+        .rpc();
+    } catch (e) {
+      console.log(e);
+    }
+    const data = await program.account.config.fetch(config);
+    assert(
+      data.admin.toBase58() === signer.publicKey.toString(),
+      "Wrong admin"
+    );
+    assert(!data.paused, "Program should not be paused");
+  });
+
   step("Initialize a pool", async () => {
     const randomKey = generateRandomU64();
     const [rewardMintKey] = PublicKey.findProgramAddressSync(
@@ -227,6 +260,8 @@ describe("Safe Presale", () => {
     const initialSupply = new BN(700000000);
     const presaleTarget = new BN(LAMPORTS_PER_SOL * 0.5);
     const vestingPeriod = 3 * 24 * 60 * 60; //3days in seconds
+    const rewardVestingPeriod = 24 * 60 * 60; //1day in seconds
+    const creatorRewardVestingPeriod = 7 * 24 * 60 * 60; //7days in seconds
     const presaleDuration = 15; // in seconds
 
     const [rewardMint_metadata] = PublicKey.findProgramAddressSync(
@@ -257,6 +292,12 @@ describe("Safe Presale", () => {
           delegate: null,
           maxAmountPerPurchase: new BN(LAMPORTS_PER_SOL),
           vestingPeriod: vestingPeriod,
+          rewardVestingPeriod: rewardVestingPeriod,
+          creatorRewardVestingPeriod: creatorRewardVestingPeriod,
+          dissolveQuorumBasisPoints: 5000,
+          dissolveVoteWindow: 3 * 24 * 60 * 60,
+          claimPeriod: null,
+          reclaimDestination: null,
           liquidityPoolSupply: liquidityPoolSupply,
           initialSupply: initialSupply,
           presaleDuration: presaleDuration,
@@ -303,6 +344,14 @@ describe("Safe Presale", () => {
       "Wrong liquidity pool supply"
     );
     assert(data.vestingPeriod === vestingPeriod, "Wrong vesting period");
+    assert(
+      data.rewardVestingPeriod === rewardVestingPeriod,
+      "Wrong reward vesting period"
+    );
+    assert(
+      data.creatorRewardVestingPeriod === creatorRewardVestingPeriod,
+      "Wrong creator reward vesting period"
+    );
     assert(data.vestingStartedAt === null, "Vesting should not have started");
   });
 
@@ -799,6 +848,14 @@ describe("Safe Presale", () => {
       poolId,
       true
     );
+    const [rewardMintMetadata] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("metadata"),
+        toWeb3JsPublicKey(MPL_TOKEN_METADATA_PROGRAM_ID).toBuffer(),
+        rewardMint.mint.toBuffer(),
+      ],
+      toWeb3JsPublicKey(MPL_TOKEN_METADATA_PROGRAM_ID)
+    );
     const ixs = [];
     ixs.push(
       await program.methods
@@ -813,6 +870,7 @@ describe("Safe Presale", () => {
           tokenProgram: TOKEN_PROGRAM_ID,
           ammCoinMint: rewardMint.mint,
           ammPcMint: NATIVE_MINT,
+          rewardMintMetadata: rewardMintMetadata,
         })
         .signers([toWeb3JsKeypair(signer)])
         .remainingAccounts(remainingAccounts)
@@ -885,7 +943,7 @@ describe("Safe Presale", () => {
       program.programId
     );
     const purchaseReceiptLpTokenAccount = getAssociatedTokenAddressSync(
-      poolData.lpMint,
+      poolData.lpMint.value,
       purchaseReceipt,
       true
     );
@@ -895,7 +953,7 @@ describe("Safe Presale", () => {
       true
     );
     const poolLpTokenAccount = getAssociatedTokenAddressSync(
-      poolData.lpMint,
+      poolData.lpMint.value,
       poolId,
       true
     );
@@ -914,7 +972,7 @@ describe("Safe Presale", () => {
           purchaseReceiptLpTokenAccount: purchaseReceiptLpTokenAccount,
           poolLpTokenAccount: poolLpTokenAccount,
           purchaseReceipt: purchaseReceipt,
-          lpMint: poolData.lpMint,
+          lpMint: poolData.lpMint.value,
           pool: poolId,
           payer: toWeb3JsPublicKey(signer.publicKey),
           tokenProgram: TOKEN_PROGRAM_ID,
@@ -934,12 +992,12 @@ describe("Safe Presale", () => {
       program.programId
     );
     const purchaseReceiptLpTokenAccount = getAssociatedTokenAddressSync(
-      poolData.lpMint,
+      poolData.lpMint.value,
       purchaseReceipt,
       true
     );
     const nftOwnerLpTokenAccount = getAssociatedTokenAddressSync(
-      poolData.lpMint,
+      poolData.lpMint.value,
       toWeb3JsPublicKey(signer.publicKey),
       true
     );
@@ -949,7 +1007,7 @@ describe("Safe Presale", () => {
       true
     );
     const poolAuthorityLpTokenAccount = getAssociatedTokenAddressSync(
-      poolData.lpMint,
+      poolData.lpMint.value,
       poolData.authority,
       true
     );
@@ -960,10 +1018,13 @@ describe("Safe Presale", () => {
           purchaseReceiptLpTokenAccount: purchaseReceiptLpTokenAccount,
           purchaseReceipt: purchaseReceipt,
           pool: poolId,
-          lpMint: poolData.lpMint,
+          lpMint: poolData.lpMint.value,
           nftOwner: signer.publicKey,
           nftOwnerNftTokenAccount: payerOriginalMintAta,
           nftOwnerLpTokenAccount: nftOwnerLpTokenAccount,
+          stakingProgramRecord: null,
+          stakingAuthority: null,
+          claimApproval: null,
           payer: signer.publicKey,
           tokenProgram: TOKEN_PROGRAM_ID,
         })